@@ -0,0 +1,55 @@
+//! Benchmarks for linear algebra.
+//!
+//! These compare [`Matrix::transform_points`] against multiplying each point by the matrix.
+
+#![feature(test)]
+
+extern crate test;
+
+use pylon_engine::{Matrix, Point, Rotation, Vector};
+use test::Bencher;
+
+/// The number of points transformed per iteration.
+const POINT_COUNT: usize = 1_000_000;
+
+/// Creates the matrix of a scale, rotation, and translation, along with the points to transform.
+fn create_inputs() -> (Matrix, Vec<Point>) {
+    let matrix = Matrix::from_scale_rotation_translation(
+        Vector::from_direction([2., 0.5, -1.]),
+        Rotation::new(0.3, -1.2, 2.),
+        Vector::from_direction([4., -5., 6.]),
+    );
+    let points = (0..POINT_COUNT)
+        .map(|i| {
+            let i = i as f32;
+            Point { x: i, y: -0.5 * i, z: 0.25 * i }
+        })
+        .collect();
+
+    (matrix, points)
+}
+
+#[bench]
+fn transform_points_1m(b: &mut Bencher) {
+    let (matrix, original) = create_inputs();
+    let mut points = original.clone();
+    b.iter(|| {
+        // Restoring the points keeps them from growing without bound across iterations.
+        points.copy_from_slice(&original);
+        matrix.transform_points(&mut points);
+        test::black_box(&points);
+    });
+}
+
+#[bench]
+fn mul_each_point_1m(b: &mut Bencher) {
+    let (matrix, original) = create_inputs();
+    let mut points = original.clone();
+    b.iter(|| {
+        points.copy_from_slice(&original);
+        for point in &mut points {
+            *point = matrix * *point;
+        }
+        test::black_box(&points);
+    });
+}
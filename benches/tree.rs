@@ -0,0 +1,40 @@
+//! Benchmarks for transformation hierarchies.
+//!
+//! Run these with and without the **rayon** feature to compare the serial and parallel paths of
+//! [`Hierarchy::update_transforms`].
+
+#![feature(test)]
+
+extern crate test;
+
+use pylon_engine::{tree::Hierarchy, Point, Rotation, Transform};
+use test::Bencher;
+
+/// The number of children of the root, and of each of those children.
+const FANOUT: usize = 100;
+
+/// Creates a hierarchy of 10,000 nodes: one root with 99 children, each of which has 100 children.
+fn create_hierarchy() -> Hierarchy {
+    let transform = Transform::new(Point { x: 1., y: 0., z: 0. }, Rotation::new(0., 0., 0.1), 1.);
+
+    let mut hierarchy = Hierarchy::new();
+    let root = hierarchy.add_root(transform);
+    for _ in 1..FANOUT {
+        let child = hierarchy.add_child(root, transform);
+        for _ in 0..FANOUT {
+            hierarchy.add_child(child, transform);
+        }
+    }
+    assert_eq!(hierarchy.len(), 10_000);
+
+    hierarchy
+}
+
+#[bench]
+fn update_transforms_10k(b: &mut Bencher) {
+    let mut hierarchy = create_hierarchy();
+    b.iter(|| {
+        hierarchy.update_transforms();
+        test::black_box(&hierarchy);
+    });
+}
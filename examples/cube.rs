@@ -2,12 +2,17 @@ use std::{mem, ops::Range};
 
 use fps_counter::FPSCounter;
 use pylon_engine::{
+    controls::Orbit,
     BindGroupSlot,
+    Camera,
+    CameraResources,
     CameraTransformsUniform,
     Material,
     Matrix,
+    Mesh,
     MeshTriangle,
     MeshVertex,
+    ObjectTransforms,
     ObjectTransformsUniform,
     Point,
     Renderer,
@@ -15,7 +20,7 @@ use pylon_engine::{
 use wgpu::BufferAddress;
 use wgpu_allocators::{Allocator as _, HeapUsages, NonZeroBufferAddress};
 use winit::{
-    event::{ElementState, Event, MouseButton, WindowEvent},
+    event::{ElementState, Event, MouseButton, MouseScrollDelta, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
     window::{Window, WindowBuilder},
 };
@@ -34,34 +39,14 @@ fn main() {
     let mut command_encoder = gfx.device().create_command_encoder(
         &wgpu::CommandEncoderDescriptor { label: None },
     );
-    // We will store the camera and object transformation matrices in this heap.
-    let uniform_heap = wgpu_allocators::Heap::new(
-        gfx.device(),
-        // To make things simple, we'll allocate 512 bytes, which should be more than enough to
-        // store both transformation matrices.
-        // SAFETY: 512 is nonzero.
-        unsafe { NonZeroBufferAddress::new_unchecked(512) },
-        HeapUsages::UNIFORM,
-    );
-    let mut uniform_stack = wgpu_allocators::Stack::new(&uniform_heap);
-    let camera = create_camera(
-        &gfx,
-        &mut command_encoder,
-        &uniform_heap,
-        &mut uniform_stack,
-    );
-    let mut cube = create_cube(
-        &gfx,
-        &mut command_encoder,
-        &uniform_heap,
-        &mut uniform_stack,
-    );
-    // We must unmap the uniform buffer before the command buffer can be submitted.
-    uniform_heap.unmap();
+    let mut camera = create_camera(&gfx);
+    let mut cube = create_cube(&gfx, &mut command_encoder);
     gfx.queue().submit(Some(command_encoder.finish()));
 
+    // Dragging with the left mouse button orbits the camera about the cube, and scrolling zooms.
+    let mut orbit = Orbit::new(&camera);
     let mut tick_count: f32 = 0.;
-    let mut mouse_position = Point::ORIGIN;
+    let mut cursor_position = None;
     let mut mouse_is_down = false;
     let mut last_fps = 0;
 
@@ -72,11 +57,21 @@ fn main() {
             Event::WindowEvent { event, .. } => {
                 match event {
                     WindowEvent::CursorMoved { position, .. } => {
-                        let [x, y] = [position.x, position.y].map(|coord| {
-                            (((coord / (WINDOW_LENGTH as f64)) * 2.0) - 1.0) as f32
-                        });
-                        mouse_position.x = x;
-                        mouse_position.y = y;
+                        if let (true, Some((x, y))) = (mouse_is_down, cursor_position) {
+                            let delta = ((position.x - x) as f32, (position.y - y) as f32);
+                            orbit.update(&mut camera, delta, 0.);
+                            write_camera_transforms(&gfx, &camera);
+                        }
+                        cursor_position = Some((position.x, position.y));
+                    }
+                    WindowEvent::MouseWheel { delta, .. } => {
+                        let scroll = match delta {
+                            MouseScrollDelta::LineDelta(_, lines) => lines,
+                            // Treat 50 pixels as one line.
+                            MouseScrollDelta::PixelDelta(pixels) => (pixels.y / 50.0) as f32,
+                        };
+                        orbit.update(&mut camera, (0., 0.), scroll);
+                        write_camera_transforms(&gfx, &camera);
                     }
                     WindowEvent::MouseInput { button, state, .. } => {
                         if matches!(button, MouseButton::Left) {
@@ -106,8 +101,8 @@ fn main() {
                 {
                     let orbit_angle = tick_count / 100.0;
                     let position = tn.position_mut();
-                    position.x = mouse_position.x + (orbit_angle.cos() / 10.0);
-                    position.y = mouse_position.y + (orbit_angle.sin() / 10.0);
+                    position.x = orbit_angle.cos() / 10.0;
+                    position.y = orbit_angle.sin() / 10.0;
                 }
 
                 // Update cube rotation.
@@ -120,47 +115,26 @@ fn main() {
 
                 // Update cube scale.
                 {
-                    *tn.scale_mut() = if mouse_is_down {
-                        0.5
-                    } else {
-                        0.05 + ((tick_count / 10_000.0).sin() + 1.0) / 50.0
-                    };
+                    tn.set_uniform_scale(0.05 + ((tick_count / 10_000.0).sin() + 1.0) / 50.0);
                 }
 
                 tn.invalidate_cache();
 
-                let mut command_encoder = gfx.device().create_command_encoder(
-                    &wgpu::CommandEncoderDescriptor { label: None },
-                );
                 // The cube's logical position, rotation, and scale has been modified&mdash;however,
                 // the vertex shader doesn't know this yet. To convey this information to the GPU,
-                // we must update the object transformation matrix, which is contained within
-                // `uniform_heap`. And, of course, before we can write to `uniform_heap`, we need to
-                // map it into CPU memory first.
-                uniform_heap.map_range_async(
-                    cube.transforms_range.clone(),
-                    wgpu::MapMode::Write,
-                );
-                // As GPU buffer mapping is asynchronous, the buffer won't actually be mapped into
-                // CPU memory until the device is polled. Here, the `wgpu::Maintain::Wait`
-                // argument synchronously stalls the CPU until the buffer is mapped.
-                gfx.device().poll(wgpu::Maintain::Wait);
-                // With that setup out of the way, we can finally write the new transformation
-                // matrix to the staging buffer and then immediately flush it to the GPU-local
-                // buffer, which is what the vertex shader actually sees.
-                uniform_heap.write_and_flush(
-                    &mut command_encoder,
-                    cube.transforms_range.clone(),
-                    bytemuck::bytes_of(&tn.local_transformation_matrix().to_array()),
-                );
-                // I'm not really sure why the GPU can't do this for us, but *wgpu* will get upset
-                // if our staging buffer is still mapped when the command buffer is submitted.
-                uniform_heap.unmap();
-                // And off our commands go!
-                gfx.queue().submit(Some(command_encoder.finish()));
-
-                let render = gfx.create_render();
-                render.add_pass().x();
+                // we must update the object transforms in the cube's uniform buffer. The write is
+                // staged by the queue and lands before the next submission.
+                let matrix = tn.local_transformation_matrix();
+                gfx.update_object_transform(&cube, &matrix);
+
+                // Each frame is recorded as a job of one or more passes. A pass binds the camera
+                // and then draws objects; both calls return the pass so that they may be chained.
+                let mut render = gfx.create_render();
+                render
+                    .add_pass()
+                    .with_camera(camera.transforms_uniform())
+                    .draw_object(&cube)
+                    .expect("failed to draw cube");
                 render.submit();
 
                 tick_count += 1.0;
@@ -207,61 +181,54 @@ fn create_gfx(window: &Window) -> Renderer {
     .unwrap()
 }
 
-fn create_camera(
-    gfx: &Renderer,
-    command_encoder: &mut wgpu::CommandEncoder,
-    uniform_heap: &wgpu_allocators::Heap,
-    uniform_stack: &mut wgpu_allocators::Stack,
-) -> Camera {
-    let transformation_matrix_range = uniform_stack.alloc(
-        // SAFETY: The size of `[[f32; 4]; 4]` is nonzero.
-        unsafe {
-            NonZeroBufferAddress::new_unchecked(mem::size_of::<[[f32; 4]; 4]>() as u64)
+fn create_camera(gfx: &Renderer) -> Camera<CubeCameraResources> {
+    // The matrix depends on the camera, so the buffer starts zeroed and is written below.
+    let transforms_buffer = gfx.create_uniform_buffer(&[0; mem::size_of::<[[f32; 4]; 4]>()]);
+
+    let camera = Camera::new(
+        // The camera looks at the origin, about which the cube orbits.
+        Point { x: 0., y: 0., z: -2. },
+        Point::ORIGIN,
+        CubeCameraResources {
+            transforms_uniform: gfx.create_camera_transforms_uniform(
+                transforms_buffer.as_entire_buffer_binding(),
+            ),
+            transforms_buffer,
         },
-        // SAFETY: 256 is nonzero.
-        unsafe { NonZeroBufferAddress::new_unchecked(256) },
-    )
-    .expect("transformation matrix allocation failed");
+    );
 
-    let camera = Camera {
-        transforms_uniform: gfx.create_camera_transforms_uniform(
-            uniform_heap.binding(transformation_matrix_range.clone())
-        ),
-    };
+    write_camera_transforms(gfx, &camera);
+
+    camera
+}
 
-    uniform_heap.write_and_flush(
-        command_encoder,
-        transformation_matrix_range,
+/// Writes the transformation matrix of the camera to its uniform buffer, as is needed whenever
+/// the camera moves.
+fn write_camera_transforms(gfx: &Renderer, camera: &Camera<CubeCameraResources>) {
+    gfx.queue().write_buffer(
+        &camera.resources.transforms_buffer,
+        0,
         bytemuck::bytes_of(&camera.transformation_matrix().to_array()),
     );
-
-    camera
 }
 
-struct Camera {
+struct CubeCameraResources {
+    /// The uniform buffer containing the camera transformation matrix.
+    transforms_buffer: wgpu::Buffer,
     transforms_uniform: CameraTransformsUniform,
 }
 
-impl pylon_engine::Camera for Camera {
+impl CameraResources for CubeCameraResources {
     fn transforms_uniform(&self) -> &CameraTransformsUniform {
         &self.transforms_uniform
     }
 }
 
-impl Camera {
-    fn transformation_matrix(&self) -> Matrix {
-        Matrix::IDENTITY
-    }
-}
-
-fn create_cube(
-    gfx: &Renderer,
-    command_encoder: &mut wgpu::CommandEncoder,
-    uniform_heap: &wgpu_allocators::Heap,
-    uniform_stack: &mut wgpu_allocators::Stack,
-) -> Cube {
+fn create_cube(gfx: &Renderer, command_encoder: &mut wgpu::CommandEncoder) -> Cube {
     let mesh = create_cube_mesh();
 
+    // Index and vertex buffers are suballocated from one heap. This is overkill for a single cube,
+    // but it scales to many objects.
     let index_and_vertex_heap = wgpu_allocators::Heap::new(
         gfx.device(),
         // SAFETY: 512 is nonzero.
@@ -290,7 +257,7 @@ fn create_cube(
         // SAFETY: None of the terms are zero, so the product of them must be nonzero.
         unsafe {
             NonZeroBufferAddress::new_unchecked(
-                (3 * mem::size_of::<f32>() * mesh.vertex_pool.len()) as u64,
+                (mem::size_of::<MeshVertex>() * mesh.vertex_pool.len()) as u64,
             )
         },
         // SAFETY: 256 is nonzero.
@@ -305,19 +272,12 @@ fn create_cube(
     index_and_vertex_heap.flush(command_encoder);
     index_and_vertex_heap.unmap();
 
-    let transforms_range = uniform_stack.alloc(
-        // SAFETY: `ObjectTransforms` is not a ZST, so the size must be nonzero.
-        unsafe {
-            NonZeroBufferAddress::new_unchecked(mem::size_of::<[[f32; 4]; 4]>() as u64)
-        },
-        // SAFETY: 256 is nonzero.
-        unsafe { NonZeroBufferAddress::new_unchecked(256) },
-    )
-    .expect("object transforms allocation failed");
+    let transforms_buffer =
+        gfx.create_uniform_buffer(bytemuck::bytes_of(&ObjectTransforms::new(&Matrix::IDENTITY)));
 
     Cube {
         mesh,
-        render_pipeline: gfx.create_pipeline(wgpu::ShaderSource::Wgsl(
+        render_pipeline: pollster::block_on(gfx.create_pipeline(wgpu::ShaderSource::Wgsl(
             std::borrow::Cow::Borrowed(r#"
                 @fragment
                 fn main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
@@ -329,12 +289,13 @@ fn create_cube(
                     );
                 }
             "#)
-        )),
+        )))
+        .expect("failed to create render pipeline"),
         transforms_node: pylon_engine::tree::Node::default(),
-        transforms_range: transforms_range.clone(),
         transforms_uniform: gfx.create_object_transforms_uniform(
-            uniform_heap.binding(transforms_range)
+            transforms_buffer.as_entire_buffer_binding(),
         ),
+        transforms_buffer,
         index_and_vertex_heap,
         index_buffer_range,
         vertex_buffer_range,
@@ -342,48 +303,28 @@ fn create_cube(
 }
 
 fn create_cube_mesh() -> Mesh {
+    // Corners are shared between faces, so each normal points away from the center of the cube.
+    // This is the magnitude of each component of such a normal.
+    const N: f32 = 0.577_350_3;
+
     Mesh {
         vertex_pool: vec![
-            // 0.
-            MeshVertex {
-                // Left, lower, back.
-                point: Point { x: -1., y: -1., z: -1. },
-            },
-            // 1.
-            MeshVertex {
-                // Left, lower, front.
-                point: Point { x: -1., y: -1., z: 1. },
-            },
-            // 2.
-            MeshVertex {
-                // Left, upper, back.
-                point: Point { x: -1., y: 1., z: -1. },
-            },
-            // 3.
-            MeshVertex {
-                // Left, upper, front.
-                point: Point { x: -1., y: 1., z: 1. },
-            },
-            // 4.
-            MeshVertex {
-                // Right, lower, back.
-                point: Point { x: 1., y: -1., z: -1. },
-            },
-            // 5.
-            MeshVertex {
-                // Right, lower, front.
-                point: Point { x: 1., y: -1., z: 1. },
-            },
-            // 6.
-            MeshVertex {
-                // Right, upper, back.
-                point: Point { x: 1., y: 1., z: -1. },
-            },
-            // 7.
-            MeshVertex {
-                // Right, upper, front.
-                point: Point { x: 1., y: 1., z: 1. },
-            },
+            // 0: left, lower, back.
+            MeshVertex::new(Point { x: -1., y: -1., z: -1. }, Point { x: -N, y: -N, z: -N }),
+            // 1: left, lower, front.
+            MeshVertex::new(Point { x: -1., y: -1., z: 1. }, Point { x: -N, y: -N, z: N }),
+            // 2: left, upper, back.
+            MeshVertex::new(Point { x: -1., y: 1., z: -1. }, Point { x: -N, y: N, z: -N }),
+            // 3: left, upper, front.
+            MeshVertex::new(Point { x: -1., y: 1., z: 1. }, Point { x: -N, y: N, z: N }),
+            // 4: right, lower, back.
+            MeshVertex::new(Point { x: 1., y: -1., z: -1. }, Point { x: N, y: -N, z: -N }),
+            // 5: right, lower, front.
+            MeshVertex::new(Point { x: 1., y: -1., z: 1. }, Point { x: N, y: -N, z: N }),
+            // 6: right, upper, back.
+            MeshVertex::new(Point { x: 1., y: 1., z: -1. }, Point { x: N, y: N, z: -N }),
+            // 7: right, upper, front.
+            MeshVertex::new(Point { x: 1., y: 1., z: 1. }, Point { x: N, y: N, z: N }),
         ],
         triangles: vec![
             // Left face.
@@ -408,19 +349,14 @@ fn create_cube_mesh() -> Mesh {
     }
 }
 
-struct Mesh {
-    vertex_pool: Vec<MeshVertex>,
-    triangles: Vec<MeshTriangle>,
-}
-
 struct Cube {
     /// The mesh.
     mesh: Mesh,
     /// The render pipeline for this cube.
     render_pipeline: wgpu::RenderPipeline,
     transforms_node: pylon_engine::tree::Node,
-    /// The range of bytes in the uniform heap allocated to the transformation matrix for this cube.
-    transforms_range: Range<BufferAddress>,
+    /// The uniform buffer containing the transforms for this cube.
+    transforms_buffer: wgpu::Buffer,
     /// The uniform for this cube's transformation matrix.
     transforms_uniform: ObjectTransformsUniform,
     /// The [heap](wgpu_allocators::Heap) containing the index and vertex buffers for this cube.
@@ -435,7 +371,11 @@ struct Cube {
 
 impl pylon_engine::Object for Cube {
     fn triangle_count(&self) -> u32 {
-        self.mesh.triangles.len() as u32
+        self.mesh.triangle_count()
+    }
+
+    fn vertex_count(&self) -> u32 {
+        self.mesh.vertex_count()
     }
 
     fn render_pipeline(&self) -> &wgpu::RenderPipeline {
@@ -446,6 +386,11 @@ impl pylon_engine::Object for Cube {
         &self.transforms_uniform
     }
 
+    fn transforms_buffer(&self) -> Option<(&wgpu::Buffer, BufferAddress)> {
+        // The uniform is bound to the entire buffer.
+        Some((&self.transforms_buffer, 0))
+    }
+
     fn bind_group_slots<'a>(&'a self) -> &[BindGroupSlot<'a>] {
         // Our fragment shader is extremely simple and doesn't need any bind groups.
         &[]
@@ -455,7 +400,15 @@ impl pylon_engine::Object for Cube {
         self.index_and_vertex_heap.slice(self.index_buffer_range.clone())
     }
 
+    fn index_buffer_size(&self) -> Option<BufferAddress> {
+        Some(self.index_buffer_range.end - self.index_buffer_range.start)
+    }
+
     fn vertex_buffer<'a>(&'a self) -> wgpu::BufferSlice<'a> {
         self.index_and_vertex_heap.slice(self.vertex_buffer_range.clone())
     }
+
+    fn vertex_buffer_size(&self) -> Option<BufferAddress> {
+        Some(self.vertex_buffer_range.end - self.vertex_buffer_range.start)
+    }
 }
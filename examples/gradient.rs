@@ -0,0 +1,205 @@
+use std::mem;
+
+use pylon_engine::{
+    renderer::PipelineOptions,
+    BindGroupSlot,
+    Camera,
+    CameraResources,
+    CameraTransformsUniform,
+    Color,
+    Matrix,
+    MeshVertex,
+    ObjectTransforms,
+    ObjectTransformsUniform,
+    Point,
+    Renderer,
+};
+use wgpu::util::DeviceExt as _;
+use winit::{
+    event::{Event, WindowEvent},
+    event_loop::{ControlFlow, EventLoop},
+    window::{Window, WindowBuilder},
+};
+
+/// The width and height, in pixels, of the window that will be rendered to.
+const WINDOW_LENGTH: u32 = 512;
+
+/// Runs the gradient demo.
+fn main() {
+    let event_loop = EventLoop::new();
+    let window = create_window(&event_loop);
+
+    let gfx = create_gfx(&window);
+    let camera = create_camera(&gfx);
+    let triangle = create_triangle(&gfx);
+
+    event_loop.run(move |event, _, ctrl_flow| {
+        *ctrl_flow = ControlFlow::Wait;
+
+        match event {
+            Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
+                *ctrl_flow = ControlFlow::Exit;
+            }
+            Event::RedrawRequested(_) => {
+                let mut render = gfx.create_render();
+                render
+                    .add_pass()
+                    .with_camera(camera.transforms_uniform())
+                    .draw_object(&triangle)
+                    .expect("failed to draw triangle");
+                render.submit();
+            }
+            _ => {}
+        }
+    });
+}
+
+fn create_window(event_loop: &EventLoop<()>) -> Window {
+    WindowBuilder::new()
+        .with_inner_size(winit::dpi::LogicalSize::new(WINDOW_LENGTH, WINDOW_LENGTH))
+        .with_resizable(false)
+        .with_title("Gradient")
+        .build(event_loop)
+        .expect("failed to build window")
+}
+
+fn create_gfx(window: &Window) -> Renderer {
+    let mut gfx = pollster::block_on(unsafe {
+        Renderer::new(
+            window,
+            wgpu::Backends::all(),
+            wgpu::PowerPreference::HighPerformance,
+            pylon_engine::renderer::SurfaceSize {
+                width: WINDOW_LENGTH,
+                height: WINDOW_LENGTH,
+            },
+            wgpu::PresentMode::Fifo,
+        )
+    })
+    .unwrap();
+    gfx.set_clear_color(Some(Color::new(0., 0., 0., 1.)));
+
+    gfx
+}
+
+fn create_camera(gfx: &Renderer) -> Camera<GradientCameraResources> {
+    // The matrix depends on the camera, so the buffer starts zeroed and is written below.
+    let transforms_buffer = gfx.create_uniform_buffer(&[0; mem::size_of::<[[f32; 4]; 4]>()]);
+
+    let camera = Camera::new(
+        Point { x: 0., y: 0., z: -2.5 },
+        Point::ORIGIN,
+        GradientCameraResources {
+            transforms_uniform: gfx.create_camera_transforms_uniform(
+                transforms_buffer.as_entire_buffer_binding(),
+            ),
+            transforms_buffer,
+        },
+    );
+
+    gfx.queue().write_buffer(
+        &camera.resources.transforms_buffer,
+        0,
+        bytemuck::bytes_of(&camera.transformation_matrix().to_array()),
+    );
+
+    camera
+}
+
+struct GradientCameraResources {
+    /// The uniform buffer containing the camera transformation matrix.
+    transforms_buffer: wgpu::Buffer,
+    transforms_uniform: CameraTransformsUniform,
+}
+
+impl CameraResources for GradientCameraResources {
+    fn transforms_uniform(&self) -> &CameraTransformsUniform {
+        &self.transforms_uniform
+    }
+}
+
+fn create_triangle(gfx: &Renderer) -> Triangle {
+    // Each corner is one primary color, which blends into the others across the triangle.
+    let normal = Point { x: 0., y: 0., z: -1. };
+    let vertices = [
+        MeshVertex::new(Point { x: -1., y: -0.8, z: 0. }, normal).with_color([1., 0., 0., 1.]),
+        MeshVertex::new(Point { x: 1., y: -0.8, z: 0. }, normal).with_color([0., 1., 0., 1.]),
+        MeshVertex::new(Point { x: 0., y: 0.9, z: 0. }, normal).with_color([0., 0., 1., 1.]),
+    ];
+    let vertex_buffer = gfx.device().create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Triangle vertex buffer"),
+        contents: bytemuck::cast_slice(&vertices),
+        usage: wgpu::BufferUsages::VERTEX,
+    });
+    let index_buffer = gfx.device().create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Triangle index buffer"),
+        contents: bytemuck::cast_slice(&[0u32, 1, 2]),
+        usage: wgpu::BufferUsages::INDEX,
+    });
+
+    let transforms_buffer =
+        gfx.create_uniform_buffer(bytemuck::bytes_of(&ObjectTransforms::new(&Matrix::IDENTITY)));
+
+    Triangle {
+        // The fragment shader must accept the normal as well as the color, even if unused.
+        render_pipeline: pollster::block_on(gfx.create_vertex_colored_pipeline(
+            wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(r#"
+                @fragment
+                fn main(
+                    @location(0) normal: vec3<f32>,
+                    @location(1) color: vec4<f32>,
+                ) -> @location(0) vec4<f32> {
+                    return color;
+                }
+            "#)),
+            &PipelineOptions::default(),
+        ))
+        .expect("failed to create render pipeline"),
+        transforms_uniform: gfx.create_object_transforms_uniform(
+            transforms_buffer.as_entire_buffer_binding(),
+        ),
+        index_buffer,
+        vertex_buffer,
+    }
+}
+
+struct Triangle {
+    /// The render pipeline, which colors fragments by their interpolated vertex colors.
+    render_pipeline: wgpu::RenderPipeline,
+    /// The uniform for this triangle's transformation matrix.
+    transforms_uniform: ObjectTransformsUniform,
+    /// The index buffer containing the triangle's three indices.
+    index_buffer: wgpu::Buffer,
+    /// The vertex buffer containing the triangle's three [`MeshVertex`]s.
+    vertex_buffer: wgpu::Buffer,
+}
+
+impl pylon_engine::Object for Triangle {
+    fn triangle_count(&self) -> u32 {
+        1
+    }
+
+    fn vertex_count(&self) -> u32 {
+        3
+    }
+
+    fn render_pipeline(&self) -> &wgpu::RenderPipeline {
+        &self.render_pipeline
+    }
+
+    fn transforms_uniform(&self) -> &ObjectTransformsUniform {
+        &self.transforms_uniform
+    }
+
+    fn bind_group_slots<'a>(&'a self) -> &[BindGroupSlot<'a>] {
+        &[]
+    }
+
+    fn index_buffer<'a>(&'a self) -> wgpu::BufferSlice<'a> {
+        self.index_buffer.slice(..)
+    }
+
+    fn vertex_buffer<'a>(&'a self) -> wgpu::BufferSlice<'a> {
+        self.vertex_buffer.slice(..)
+    }
+}
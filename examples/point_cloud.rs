@@ -0,0 +1,240 @@
+use std::mem;
+
+use pylon_engine::{
+    renderer::PipelineOptions,
+    BindGroupSlot,
+    Camera,
+    CameraResources,
+    CameraTransformsUniform,
+    Color,
+    Matrix,
+    MeshVertex,
+    ObjectTransforms,
+    ObjectTransformsUniform,
+    Point,
+    Renderer,
+};
+use wgpu::util::DeviceExt as _;
+use winit::{
+    event::{Event, WindowEvent},
+    event_loop::{ControlFlow, EventLoop},
+    window::{Window, WindowBuilder},
+};
+
+/// The width and height, in pixels, of the window that will be rendered to.
+const WINDOW_LENGTH: u32 = 512;
+
+/// The number of points in the cloud.
+const POINT_COUNT: usize = 20_000;
+
+/// Runs the point cloud demo.
+fn main() {
+    let event_loop = EventLoop::new();
+    let window = create_window(&event_loop);
+
+    let gfx = create_gfx(&window);
+    let camera = create_camera(&gfx);
+    let mut cloud = create_cloud(&gfx);
+
+    let mut tick_count: f32 = 0.;
+
+    event_loop.run(move |event, _, ctrl_flow| {
+        *ctrl_flow = ControlFlow::Poll;
+
+        match event {
+            Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
+                *ctrl_flow = ControlFlow::Exit;
+            }
+            Event::MainEventsCleared => {
+                window.request_redraw();
+            }
+            Event::RedrawRequested(_) => {
+                let tn = &mut cloud.transforms_node;
+
+                // Spin the cloud about the Y axis.
+                tn.rotation_mut().y = tick_count / 200.0;
+                tn.invalidate_cache();
+
+                let matrix = tn.local_transformation_matrix();
+                gfx.update_object_transform(&cloud, &matrix);
+
+                // Point clouds have no triangles, so each vertex is drawn as a point instead of
+                // drawing indexed triangles with `draw_object`.
+                let mut render = gfx.create_render();
+                render
+                    .add_pass()
+                    .with_camera(camera.transforms_uniform())
+                    .draw_points(&cloud)
+                    .expect("failed to draw point cloud");
+                render.submit();
+
+                tick_count += 1.0;
+            }
+            _ => {}
+        }
+    });
+}
+
+fn create_window(event_loop: &EventLoop<()>) -> Window {
+    WindowBuilder::new()
+        .with_inner_size(winit::dpi::LogicalSize::new(WINDOW_LENGTH, WINDOW_LENGTH))
+        .with_resizable(false)
+        .with_title("Point Cloud")
+        .build(event_loop)
+        .expect("failed to build window")
+}
+
+fn create_gfx(window: &Window) -> Renderer {
+    let mut gfx = pollster::block_on(unsafe {
+        Renderer::new(
+            window,
+            wgpu::Backends::all(),
+            wgpu::PowerPreference::HighPerformance,
+            pylon_engine::renderer::SurfaceSize {
+                width: WINDOW_LENGTH,
+                height: WINDOW_LENGTH,
+            },
+            wgpu::PresentMode::Fifo,
+        )
+    })
+    .unwrap();
+    gfx.set_clear_color(Some(Color::new(0., 0., 0., 1.)));
+
+    gfx
+}
+
+fn create_camera(gfx: &Renderer) -> Camera<CloudCameraResources> {
+    // The matrix depends on the camera, so the buffer starts zeroed and is written below.
+    let transforms_buffer = gfx.create_uniform_buffer(&[0; mem::size_of::<[[f32; 4]; 4]>()]);
+
+    let camera = Camera::new(
+        Point { x: 0., y: -1., z: -3. },
+        Point::ORIGIN,
+        CloudCameraResources {
+            transforms_uniform: gfx.create_camera_transforms_uniform(
+                transforms_buffer.as_entire_buffer_binding(),
+            ),
+            transforms_buffer,
+        },
+    );
+
+    gfx.queue().write_buffer(
+        &camera.resources.transforms_buffer,
+        0,
+        bytemuck::bytes_of(&camera.transformation_matrix().to_array()),
+    );
+
+    camera
+}
+
+struct CloudCameraResources {
+    /// The uniform buffer containing the camera transformation matrix.
+    transforms_buffer: wgpu::Buffer,
+    transforms_uniform: CameraTransformsUniform,
+}
+
+impl CameraResources for CloudCameraResources {
+    fn transforms_uniform(&self) -> &CameraTransformsUniform {
+        &self.transforms_uniform
+    }
+}
+
+fn create_cloud(gfx: &Renderer) -> Cloud {
+    let points = create_cloud_points();
+    let vertex_buffer = gfx.device().create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Point cloud vertex buffer"),
+        contents: bytemuck::cast_slice(&points),
+        usage: wgpu::BufferUsages::VERTEX,
+    });
+
+    let transforms_buffer =
+        gfx.create_uniform_buffer(bytemuck::bytes_of(&ObjectTransforms::new(&Matrix::IDENTITY)));
+
+    Cloud {
+        vertex_count: points.len() as u32,
+        // Points are colored by their normals so that the shape of the cloud is visible.
+        render_pipeline: pollster::block_on(gfx.create_pipeline_with_options(
+            wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(r#"
+                @fragment
+                fn main(@location(0) normal: vec3<f32>) -> @location(0) vec4<f32> {
+                    return vec4<f32>(0.5 + (normal * 0.5), 1.0);
+                }
+            "#)),
+            &PipelineOptions::points(),
+        ))
+        .expect("failed to create render pipeline"),
+        transforms_node: pylon_engine::tree::Node::default(),
+        transforms_uniform: gfx.create_object_transforms_uniform(
+            transforms_buffer.as_entire_buffer_binding(),
+        ),
+        transforms_buffer,
+        vertex_buffer,
+    }
+}
+
+/// Scatters points over a unit sphere on a Fibonacci spiral, which spaces them almost evenly.
+fn create_cloud_points() -> Vec<MeshVertex> {
+    let golden_angle = std::f32::consts::PI * (3. - 5f32.sqrt());
+
+    (0..POINT_COUNT)
+        .map(|i| {
+            let y = 1. - (2. * (i as f32) + 1.) / (POINT_COUNT as f32);
+            let radius = (1. - (y * y)).sqrt();
+            let (sin, cos) = (golden_angle * (i as f32)).sin_cos();
+            let point = Point { x: radius * cos, y, z: radius * sin };
+
+            // On a unit sphere, the normal is the point itself.
+            MeshVertex::new(point, point)
+        })
+        .collect()
+}
+
+struct Cloud {
+    /// The number of points.
+    vertex_count: u32,
+    /// The point-list render pipeline for this cloud.
+    render_pipeline: wgpu::RenderPipeline,
+    transforms_node: pylon_engine::tree::Node,
+    /// The uniform buffer containing the transforms for this cloud.
+    transforms_buffer: wgpu::Buffer,
+    /// The uniform for this cloud's transformation matrix.
+    transforms_uniform: ObjectTransformsUniform,
+    /// The vertex buffer containing one [`MeshVertex`] per point.
+    vertex_buffer: wgpu::Buffer,
+}
+
+impl pylon_engine::Object for Cloud {
+    fn triangle_count(&self) -> u32 {
+        0
+    }
+
+    fn vertex_count(&self) -> u32 {
+        self.vertex_count
+    }
+
+    fn render_pipeline(&self) -> &wgpu::RenderPipeline {
+        &self.render_pipeline
+    }
+
+    fn transforms_uniform(&self) -> &ObjectTransformsUniform {
+        &self.transforms_uniform
+    }
+
+    fn transforms_buffer(&self) -> Option<(&wgpu::Buffer, wgpu::BufferAddress)> {
+        Some((&self.transforms_buffer, 0))
+    }
+
+    fn bind_group_slots<'a>(&'a self) -> &[BindGroupSlot<'a>] {
+        &[]
+    }
+
+    fn index_buffer<'a>(&'a self) -> wgpu::BufferSlice<'a> {
+        // `draw_points` never binds the index buffer, and a point cloud has none, so any slice
+        // will do.
+        self.vertex_buffer.slice(..)
+    }
+
+    fn vertex_buffer<'a>(&'a self) -> wgpu::BufferSlice<'a> {
+        self.vertex_buffer.slice(..)
+    }
+}
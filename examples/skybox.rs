@@ -0,0 +1,186 @@
+use std::mem;
+
+use pylon_engine::{
+    controls::Orbit,
+    Camera,
+    CameraResources,
+    CameraTransformsUniform,
+    Point,
+    Renderer,
+    Vector,
+};
+use winit::{
+    event::{Event, WindowEvent},
+    event_loop::{ControlFlow, EventLoop},
+    window::{Window, WindowBuilder},
+};
+
+/// The width and height, in pixels, of the window that will be rendered to.
+const WINDOW_LENGTH: u32 = 512;
+
+/// The width and height, in texels, of each face of the cubemap.
+const FACE_LENGTH: u32 = 256;
+
+/// Runs the skybox demo.
+fn main() {
+    let event_loop = EventLoop::new();
+    let window = create_window(&event_loop);
+
+    let gfx = create_gfx(&window);
+    let mut camera = create_camera(&gfx);
+    let cubemap = create_cubemap(&gfx);
+
+    // The camera circles below its target, and so looks slightly upward, as the horizon turns
+    // past.
+    let mut orbit = Orbit::new(&camera);
+    orbit.elevation = 0.3;
+
+    event_loop.run(move |event, _, ctrl_flow| {
+        *ctrl_flow = ControlFlow::Poll;
+
+        match event {
+            Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
+                *ctrl_flow = ControlFlow::Exit;
+            }
+            Event::MainEventsCleared => {
+                window.request_redraw();
+            }
+            Event::RedrawRequested(_) => {
+                orbit.update(&mut camera, (1., 0.), 0.);
+                write_camera_transforms(&gfx, &camera);
+
+                let mut render = gfx.create_render();
+                // Scene passes would go here; the sky fills only the pixels that they leave empty.
+                gfx.render_skybox(&mut render, &camera, &cubemap);
+                render.submit();
+            }
+            _ => {}
+        }
+    });
+}
+
+fn create_window(event_loop: &EventLoop<()>) -> Window {
+    WindowBuilder::new()
+        .with_inner_size(winit::dpi::LogicalSize::new(WINDOW_LENGTH, WINDOW_LENGTH))
+        .with_resizable(false)
+        .with_title("Skybox")
+        .build(event_loop)
+        .expect("failed to build window")
+}
+
+fn create_gfx(window: &Window) -> Renderer {
+    pollster::block_on(unsafe {
+        Renderer::new(
+            window,
+            wgpu::Backends::all(),
+            wgpu::PowerPreference::HighPerformance,
+            pylon_engine::renderer::SurfaceSize {
+                width: WINDOW_LENGTH,
+                height: WINDOW_LENGTH,
+            },
+            wgpu::PresentMode::Fifo,
+        )
+    })
+    .unwrap()
+}
+
+fn create_camera(gfx: &Renderer) -> Camera<SkyboxCameraResources> {
+    // The matrix depends on the camera, so the buffer starts zeroed and is written every frame.
+    let transforms_buffer = gfx.create_uniform_buffer(&[0; mem::size_of::<[[f32; 4]; 4]>()]);
+
+    Camera::new(
+        Point { x: 0., y: 0., z: -3. },
+        Point::ORIGIN,
+        SkyboxCameraResources {
+            transforms_uniform: gfx.create_camera_transforms_uniform(
+                transforms_buffer.as_entire_buffer_binding(),
+            ),
+            transforms_buffer,
+        },
+    )
+}
+
+struct SkyboxCameraResources {
+    /// The uniform buffer containing the camera transformation matrix.
+    ///
+    /// The skybox computes its own matrix from the camera, but scene objects drawn before it would
+    /// use this one.
+    transforms_buffer: wgpu::Buffer,
+    transforms_uniform: CameraTransformsUniform,
+}
+
+impl CameraResources for SkyboxCameraResources {
+    fn transforms_uniform(&self) -> &CameraTransformsUniform {
+        &self.transforms_uniform
+    }
+}
+
+/// Writes the camera transformation matrix to the camera's uniform buffer.
+fn write_camera_transforms(gfx: &Renderer, camera: &Camera<SkyboxCameraResources>) {
+    gfx.queue().write_buffer(
+        &camera.resources.transforms_buffer,
+        0,
+        bytemuck::bytes_of(&camera.transformation_matrix().to_array()),
+    );
+}
+
+/// Creates a cubemap of a sky that fades from blue overhead to pale at the horizon, above brown
+/// ground.
+///
+/// Faces loaded from image files, as with the *image* crate, are passed to
+/// [`Renderer::create_cubemap`] the same way: as six buffers of RGBA8 texels.
+fn create_cubemap(gfx: &Renderer) -> wgpu::TextureView {
+    let faces: Vec<Vec<u8>> = (0..6).map(create_face).collect();
+
+    gfx.create_cubemap(FACE_LENGTH, [
+        &faces[0],
+        &faces[1],
+        &faces[2],
+        &faces[3],
+        &faces[4],
+        &faces[5],
+    ])
+}
+
+/// Creates the texels of the given cubemap layer.
+fn create_face(layer: u32) -> Vec<u8> {
+    let mut texels = Vec::with_capacity((4 * FACE_LENGTH * FACE_LENGTH) as usize);
+    for row in 0..FACE_LENGTH {
+        for col in 0..FACE_LENGTH {
+            // The texel center, from -1 at the left and top edges to 1 at the right and bottom.
+            let u = (2. * (col as f32 + 0.5) / (FACE_LENGTH as f32)) - 1.;
+            let v = (2. * (row as f32 + 0.5) / (FACE_LENGTH as f32)) - 1.;
+            // The direction of each texel, following the orientation of *wgpu* cubemap faces.
+            let [x, y, z] = match layer {
+                0 => [1., -v, -u],
+                1 => [-1., -v, u],
+                2 => [u, 1., v],
+                3 => [u, -1., -v],
+                4 => [u, -v, 1.],
+                _ => [-u, -v, -1.],
+            };
+            let direction = Vector::from_direction([x, y, z]).normalize();
+
+            texels.extend(sky_color(direction));
+        }
+    }
+
+    texels
+}
+
+/// The sRGB-encoded color of the sky in the given direction.
+fn sky_color(direction: Vector) -> [u8; 4] {
+    // Pylon's negative Y axis points up.
+    let height = -direction.y();
+    let [r, g, b] = if height >= 0. {
+        let horizon = [200., 220., 240.];
+        let zenith = [40., 90., 200.];
+        let t = height.sqrt();
+
+        [0, 1, 2].map(|i| horizon[i] + ((zenith[i] - horizon[i]) * t))
+    } else {
+        [90., 70., 50.]
+    };
+
+    [r as u8, g as u8, b as u8, 255]
+}
@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Bounding volumes, as for picking.
+
+use crate::{Mesh, Point, Vector};
+
+impl Mesh {
+    /// The smallest [`Aabb`] in mesh space that contains every vertex of this mesh, or `None` if
+    /// the vertex pool is empty.
+    pub fn aabb(&self) -> Option<Aabb> {
+        Aabb::from_points(self.vertex_pool.iter().map(|vertex| vertex.point))
+    }
+}
+
+/// An axis-aligned bounding box.
+///
+/// Like [`Point`], an `Aabb` doesn't prescribe a coordinate space. To test a world-space ray from
+/// [`Camera::screen_ray`](crate::Camera::screen_ray) against the mesh-space bounds of an object,
+/// transform the ray by the inverse of the object's transformation matrix first.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Aabb {
+    /// The corner with the smallest coordinates.
+    pub min: Point,
+    /// The corner with the largest coordinates.
+    pub max: Point,
+}
+
+impl Aabb {
+    /// The smallest `Aabb` that contains every given point, or `None` if there are no points.
+    pub fn from_points(points: impl IntoIterator<Item = Point>) -> Option<Self> {
+        let mut points = points.into_iter().map(Vector::from);
+        let first = points.next()?;
+        let (min, max) = points.fold((first, first), |(min, max), point| {
+            (min.min(point), max.max(point))
+        });
+
+        Some(Self {
+            min: Point::from(min),
+            max: Point::from(max),
+        })
+    }
+
+    /// The distance along `direction` from `origin` at which a ray enters this box, or `None` if
+    /// the ray misses.
+    ///
+    /// The distance is in units of the length of `direction`, so it is a true distance when
+    /// `direction` is a unit vector. If `origin` lies within this box, the distance is 0.
+    pub fn intersect_ray(&self, origin: Point, direction: Vector) -> Option<f32> {
+        let origin = [origin.x, origin.y, origin.z];
+        let direction = [direction.x(), direction.y(), direction.z()];
+        let min = [self.min.x, self.min.y, self.min.z];
+        let max = [self.max.x, self.max.y, self.max.z];
+
+        // This is the slab method: the ray is clipped against the pair of planes bounding each
+        // axis, and it hits the box if the clipped intervals overlap.
+        let mut entry = 0_f32;
+        let mut exit = f32::INFINITY;
+        for axis in 0..3 {
+            // Division by zero produces infinities, which correctly make parallel rays either miss
+            // or ignore this axis.
+            let inverse = 1. / direction[axis];
+            let mut near = (min[axis] - origin[axis]) * inverse;
+            let mut far = (max[axis] - origin[axis]) * inverse;
+            if near > far {
+                std::mem::swap(&mut near, &mut far);
+            }
+
+            entry = entry.max(near);
+            exit = exit.min(far);
+            if entry > exit {
+                return None;
+            }
+        }
+
+        Some(entry)
+    }
+}
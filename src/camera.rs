@@ -0,0 +1,383 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The [`Camera`].
+
+use std::fmt;
+
+use crate::{CameraTransformsUniform, Matrix, Point, Radians, Vector};
+
+/// The interface to user-managed camera resources.
+pub trait CameraResources {
+    fn transforms_uniform(&self) -> &CameraTransformsUniform;
+}
+
+/// The handedness of a world-space coordinate system.
+///
+/// Pylon's own world space is [right-handed](Self::Right); see the [crate docs](crate#handedness).
+/// A [`Camera`] with [`Left`](Self::Left) handedness mirrors the world along the Z axis before
+/// viewing it so that scenes authored in a left-handed coordinate system are not drawn mirrored.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Handedness {
+    /// A right-handed coordinate system, which is Pylon's own.
+    #[default]
+    Right,
+    /// A left-handed coordinate system.
+    Left,
+}
+
+impl Handedness {
+    /// The matrix that converts coordinates of this handedness to Pylon's right-handed world space.
+    ///
+    /// This is the identity for [`Right`](Self::Right) and negates Z for [`Left`](Self::Left).
+    pub fn to_right_handed_matrix(self) -> Matrix {
+        match self {
+            Self::Right => Matrix::IDENTITY,
+            Self::Left => Matrix::new(
+                1., 0.,  0., 0.,
+                0., 1.,  0., 0.,
+                0., 0., -1., 0.,
+                0., 0.,  0., 1.,
+            ),
+        }
+    }
+}
+
+/// A camera that looks from one point in world space at another.
+///
+/// Pylon manages the placement of the camera, but GPU resources are user-managed through
+/// [`resources`](Self::resources). Whenever the camera moves, the new
+/// [transformation matrix](Self::transformation_matrix) must be written to the buffer behind
+/// [the camera's uniform](Self::transforms_uniform).
+pub struct Camera<R: CameraResources> {
+    /// The position of the camera in world space.
+    pub position: Point,
+    /// The point in world space that the camera looks at.
+    pub target: Point,
+    /// The rotation, in radians, of the camera about its line of sight.
+    ///
+    /// Roll is applied after the camera is turned toward its [target](Self::target), so it tilts
+    /// the horizon without changing what the camera looks at. Positive roll turns the scene
+    /// counterclockwise in the viewport: at a roll of π/2, the positive Y axis of world space,
+    /// which otherwise points to the bottom of the viewport, points to the right, and the positive
+    /// X axis points to the top.
+    pub roll: f32,
+    /// The vertical field of view, in radians.
+    pub fov_y: f32,
+    /// The ratio of the width of the viewport to its height.
+    ///
+    /// This should be updated with [`set_aspect`](Self::set_aspect) whenever the surface is
+    /// resized.
+    pub aspect: f32,
+    /// The distance from the camera to the near clipping plane.
+    ///
+    /// Depth precision is distributed roughly in proportion to `1 / distance`, so most of it is
+    /// spent close to the near plane. Halving `near` roughly halves the precision everywhere else,
+    /// whereas moving [`far`](Self::far) has little effect, so `near` should be as large as the
+    /// scene allows; a `near` that is too small makes distant surfaces z-fight.
+    ///
+    /// This must be positive. [`set_clip_planes`](Self::set_clip_planes) validates it.
+    pub near: f32,
+    /// The distance from the camera to the far clipping plane.
+    ///
+    /// This must be greater than [`near`](Self::near).
+    pub far: f32,
+    /// The handedness of the world space that [`position`](Self::position),
+    /// [`target`](Self::target), and the objects viewed by this camera are in.
+    pub handedness: Handedness,
+    /// The user-managed GPU resources of this camera.
+    pub resources: R,
+}
+
+impl<R: CameraResources> Camera<R> {
+    /// Creates a new `Camera` at `position` that looks at `target` without roll.
+    ///
+    /// The camera has a 60-degree vertical field of view, a square aspect ratio, and clipping
+    /// planes at distances of 0.1 and 100.
+    pub fn new(position: Point, target: Point, resources: R) -> Self {
+        Self {
+            position,
+            target,
+            roll: 0.,
+            fov_y: std::f32::consts::FRAC_PI_3,
+            aspect: 1.,
+            near: 0.1,
+            far: 100.,
+            handedness: Handedness::Right,
+            resources,
+        }
+    }
+
+    /// Sets the [roll](Self::roll), which may be given in [`Radians`] or
+    /// [`Degrees`](crate::Degrees).
+    pub fn set_roll(&mut self, roll: impl Into<Radians>) {
+        self.roll = roll.into().0;
+    }
+
+    /// Sets the [vertical field of view](Self::fov_y), which may be given in [`Radians`] or
+    /// [`Degrees`](crate::Degrees).
+    pub fn set_fov_y(&mut self, fov_y: impl Into<Radians>) {
+        self.fov_y = fov_y.into().0;
+    }
+
+    /// Sets the distances to the [near](Self::near) and [far](Self::far) clipping planes.
+    ///
+    /// # Errors
+    ///
+    /// This fails with [`ClipPlanesError::NonPositiveNear`] if `near` is not positive, and
+    /// otherwise with [`ClipPlanesError::FarNotBeyondNear`] if `far` is not greater than `near`; a
+    /// NaN distance is neither. On failure, the planes are left as they are.
+    pub fn set_clip_planes(&mut self, near: f32, far: f32) -> Result<(), ClipPlanesError> {
+        if near.is_nan() || near <= 0. {
+            return Err(ClipPlanesError::NonPositiveNear { near });
+        }
+        if far.is_nan() || far <= near {
+            return Err(ClipPlanesError::FarNotBeyondNear { near, far });
+        }
+        self.near = near;
+        self.far = far;
+
+        Ok(())
+    }
+
+    /// Sets the [aspect ratio](Self::aspect) from the size, in pixels, of the viewport.
+    pub fn set_aspect(&mut self, width: u32, height: u32) {
+        self.aspect = (width as f32) / (height as f32);
+    }
+
+    /// The uniform that contains the camera transformation matrix.
+    pub fn transforms_uniform(&self) -> &CameraTransformsUniform {
+        self.resources.transforms_uniform()
+    }
+
+    /// Creates the camera transformation matrix, which transforms world space to clip space.
+    ///
+    /// This is the product of the [projection](Self::projection_matrix) and
+    /// [view](Self::view_matrix) matrices and is what the camera uniform should contain.
+    pub fn transformation_matrix(&self) -> Matrix {
+        self.projection_matrix() * self.view_matrix()
+    }
+
+    /// The ray in world space that passes through the given point on the viewport.
+    ///
+    /// `ndc_x` and `ndc_y` are normalized device coordinates, where `(-1, -1)` is the bottom-left
+    /// corner of the viewport and `(1, 1)` is the top-right corner. The ray originates on the near
+    /// clipping plane, and its direction is a unit vector pointing away from the camera.
+    ///
+    /// `None` is returned if the [transformation matrix](Self::transformation_matrix) is not
+    /// invertible, as when [`position`](Self::position) and [`target`](Self::target) coincide.
+    pub fn screen_ray(&self, ndc_x: f32, ndc_y: f32) -> Option<(Point, Vector)> {
+        let inverse = self.transformation_matrix().inverse()?;
+        // The built-in vertex shader flips the Y axis after transformation, so we flip it back.
+        let near = inverse.mul_point(Point { x: ndc_x, y: -ndc_y, z: 0. });
+        let far = inverse.mul_point(Point { x: ndc_x, y: -ndc_y, z: 1. });
+        let direction = (Vector::from(far) - Vector::from(near)).normalize();
+
+        Some((near, direction))
+    }
+
+    /// Creates the perspective projection matrix, which transforms camera space to clip space.
+    pub fn projection_matrix(&self) -> Matrix {
+        Matrix::perspective(self.fov_y, self.aspect, self.near, self.far)
+    }
+
+    /// Creates the view matrix, which transforms world space to camera space.
+    ///
+    /// In camera space, the camera lies at the origin and looks along the positive Z axis. The
+    /// positive X axis points to the right of the viewport and, as the built-in vertex shader
+    /// flips Y, the positive Y axis points to the bottom, before these axes are turned by the
+    /// [roll](Self::roll). If [`position`](Self::position) and [`target`](Self::target) coincide,
+    /// the camera has no line of sight, and the matrix contains NaN elements.
+    ///
+    /// For [left-handed](Handedness::Left) cameras, world space is mirrored along the Z axis first.
+    pub fn view_matrix(&self) -> Matrix {
+        let to_right_handed = self.handedness.to_right_handed_matrix();
+        let Point { x, y, z } = to_right_handed.mul_point(self.position);
+        let position = Vector::from_direction([x, y, z]);
+        let Point { x, y, z } = to_right_handed.mul_point(self.target);
+        let target = Vector::from_direction([x, y, z]);
+
+        let forward = (target - position).normalize();
+        let mut world_up = Vector::from_direction([0., 1., 0.]);
+        // When looking straight up or down, the world up direction is parallel to the line of
+        // sight and can't be used to orient the camera.
+        if forward.cross(world_up).length() <= f32::EPSILON {
+            world_up = Vector::from_direction([0., 0., 1.]);
+        }
+        let right = world_up.cross(forward).normalize();
+        let up = forward.cross(right);
+
+        // Roll rotates the right and up directions about the line of sight.
+        let (sin, cos) = self.roll.sin_cos();
+        let (right, up) = ((right * cos) + (up * sin), (up * cos) - (right * sin));
+
+        let view = Matrix::new(
+            right.x(),   right.y(),   right.z(),   -right.dot(position),
+            up.x(),      up.y(),      up.z(),      -up.dot(position),
+            forward.x(), forward.y(), forward.z(), -forward.dot(position),
+            0.,          0.,          0.,          1.,
+        );
+
+        view * to_right_handed
+    }
+}
+
+/// An error that occurs when [setting clipping planes](Camera::set_clip_planes).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClipPlanesError {
+    /// The near clipping plane was not in front of the camera.
+    NonPositiveNear {
+        /// The offending distance to the near clipping plane.
+        near: f32,
+    },
+    /// The far clipping plane was not beyond the near clipping plane.
+    FarNotBeyondNear {
+        /// The distance to the near clipping plane.
+        near: f32,
+        /// The offending distance to the far clipping plane.
+        far: f32,
+    },
+}
+
+impl fmt::Display for ClipPlanesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonPositiveNear { near } => {
+                write!(f, "near clipping plane distance {} is not positive", near)
+            }
+            Self::FarNotBeyondNear { near, far } => {
+                write!(
+                    f,
+                    "far clipping plane distance {} is not greater than near distance {}",
+                    far,
+                    near,
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClipPlanesError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Camera resources for tests that never render.
+    struct NoResources;
+
+    impl CameraResources for NoResources {
+        fn transforms_uniform(&self) -> &CameraTransformsUniform {
+            unreachable!("the camera is never rendered")
+        }
+    }
+
+    fn camera(position: Point, target: Point) -> Camera<NoResources> {
+        Camera::new(position, target, NoResources)
+    }
+
+    #[test]
+    fn projects_target_to_clip_space_center() {
+        let target = Point { x: 4., y: 0., z: 5. };
+        let camera = camera(Point { x: 1., y: 2., z: -3. }, target);
+
+        let Point { x, y, z } = camera.transformation_matrix().mul_point(target);
+        assert!(x.abs() < 1e-5 && y.abs() < 1e-5, "({}, {}) is not the center", x, y);
+        assert!((0. ..=1.).contains(&z));
+    }
+
+    #[test]
+    fn unprojects_screen_center_along_line_of_sight() {
+        let position = Point { x: 1., y: 2., z: -3. };
+        let target = Point { x: 4., y: 0., z: 5. };
+        let camera = camera(position, target);
+
+        let (origin, direction) = camera.screen_ray(0., 0.).expect("camera should be invertible");
+        let forward = (Vector::from(target) - Vector::from(position)).normalize();
+        assert!((direction - forward).length() < 1e-4, "{} is not {}", direction, forward);
+        // The ray starts on the near plane, in front of the camera.
+        let offset = Vector::from(origin) - Vector::from(position);
+        assert!((offset.dot(forward) - camera.near).abs() < 1e-4);
+    }
+
+    #[test]
+    fn left_handed_camera_sees_scene_mirrored_along_z() {
+        let mirror = |Point { x, y, z }: Point| Point { x, y, z: -z };
+        let position = Point { x: 1., y: 2., z: -3. };
+        let target = Point { x: 4., y: 0., z: 5. };
+        let point = Point { x: 2., y: 1., z: 1. };
+
+        let mut left = camera(position, target);
+        left.handedness = Handedness::Left;
+        let right = camera(mirror(position), mirror(target));
+
+        // A left-handed scene looks the same as its mirror image seen by a right-handed camera.
+        let seen_left = left.transformation_matrix().mul_point(point);
+        let seen_right = right.transformation_matrix().mul_point(mirror(point));
+        let error = Vector::from(seen_left) - Vector::from(seen_right);
+        assert!(error.length() < 1e-5, "{:?} is not {:?}", seen_left, seen_right);
+
+        // Without the mirror, the scene is seen differently.
+        let unmirrored = camera(position, target).transformation_matrix().mul_point(point);
+        assert!((Vector::from(seen_left) - Vector::from(unmirrored)).length() > 1e-2);
+    }
+
+    #[test]
+    fn rolls_up_direction_into_right_direction() {
+        let direction = |v: Vector| Vector::from_direction([v.x(), v.y(), v.z()]);
+        let position = Point { x: 0., y: 0., z: -5. };
+        let unrolled = camera(position, Point::ORIGIN);
+        let mut rolled = camera(position, Point::ORIGIN);
+        rolled.roll = std::f32::consts::FRAC_PI_2;
+
+        // The rows of the view matrix are the camera's right, up, and forward directions.
+        let (unrolled_view, rolled_view) = (unrolled.view_matrix(), rolled.view_matrix());
+        let error = direction(rolled_view.row(0)) - direction(unrolled_view.row(1));
+        assert!(error.length() < 1e-6, "right {} is not the unrolled up", rolled_view.row(0));
+        let error = direction(rolled_view.row(2)) - direction(unrolled_view.row(2));
+        assert!(error.length() < 1e-6, "roll changed the line of sight");
+
+        // World +Y points to the right of the viewport, and world +X points to the top, which is
+        // -Y in camera space before the vertex shader flips it.
+        let y = rolled_view.mul_direction(Vector::from_direction([0., 1., 0.]));
+        let x = rolled_view.mul_direction(Vector::from_direction([1., 0., 0.]));
+        assert!((y.x() - 1.).abs() < 1e-6 && (x.y() + 1.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rejects_invalid_clip_planes() {
+        let mut camera = camera(Point::ORIGIN, Point { x: 0., y: 0., z: 1. });
+        camera.set_clip_planes(0.5, 50.).expect("clip planes should be valid");
+
+        // Errors carry the offending distances, which are compared bitwise so that NaN matches.
+        for near in [0., -1., f32::NAN] {
+            let result = camera.set_clip_planes(near, 10.);
+            assert!(
+                matches!(
+                    result,
+                    Err(ClipPlanesError::NonPositiveNear { near: n })
+                        if n.to_bits() == near.to_bits(),
+                ),
+                "near {} gave {:?}",
+                near,
+                result,
+            );
+        }
+        for far in [1., 0.5, f32::NAN] {
+            let result = camera.set_clip_planes(1., far);
+            assert!(
+                matches!(
+                    result,
+                    Err(ClipPlanesError::FarNotBeyondNear { near: 1., far: f })
+                        if f.to_bits() == far.to_bits(),
+                ),
+                "far {} gave {:?}",
+                far,
+                result,
+            );
+        }
+
+        // The planes are left as they were.
+        assert_eq!((camera.near, camera.far), (0.5, 50.));
+    }
+}
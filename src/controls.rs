@@ -0,0 +1,178 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Helpers that move a [`Camera`] in response to user input.
+
+use std::f32::consts::FRAC_PI_2;
+
+use crate::{Camera, CameraResources, Point, Vector};
+
+/// Orbit controls, which move a camera over a sphere centered on its [target](Camera::target).
+///
+/// The camera's position relative to its target is given in spherical coordinates:
+/// [`azimuth`](Self::azimuth) is the angle about the Y axis, [`elevation`](Self::elevation) is the
+/// angle above the XZ plane, toward the positive Y axis, and [`distance`](Self::distance) is the
+/// radius of the sphere. Mouse movement changes the angles and scrolling changes the distance;
+/// [`update`](Self::update) then moves the camera accordingly. The target itself never moves, so
+/// it may be changed freely between updates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Orbit {
+    /// The angle, in radians, of the camera about the Y axis.
+    ///
+    /// At an azimuth of 0, the camera lies on the positive Z side of its target.
+    pub azimuth: f32,
+    /// The angle, in radians, of the camera above the XZ plane.
+    ///
+    /// This is kept within [`MAX_ELEVATION`](Self::MAX_ELEVATION) of the plane.
+    pub elevation: f32,
+    /// The distance from the camera to its target.
+    ///
+    /// This is kept between [`min_distance`](Self::min_distance) and
+    /// [`max_distance`](Self::max_distance).
+    pub distance: f32,
+    /// The change in angle, in radians, for each unit of mouse movement.
+    pub rotate_speed: f32,
+    /// The factor by which each unit of scrolling toward the target shrinks the distance.
+    ///
+    /// This should be between 0 and 1; scrolling away from the target divides by it instead.
+    pub zoom_factor: f32,
+    /// The closest that the camera may get to its target.
+    pub min_distance: f32,
+    /// The farthest that the camera may get from its target.
+    pub max_distance: f32,
+}
+
+impl Orbit {
+    /// The greatest magnitude of [`elevation`](Self::elevation).
+    ///
+    /// This is just short of a right angle: were the camera directly above or below its target,
+    /// its line of sight would be parallel to the Y axis, and the camera would flip as it passed
+    /// over the pole.
+    pub const MAX_ELEVATION: f32 = FRAC_PI_2 - 0.01;
+
+    /// Creates a new `Orbit` that leaves the camera where it is.
+    ///
+    /// The camera moves a hundredth of a radian per unit of mouse movement and 10% closer per
+    /// unit of scrolling, and the distance is limited to the camera's clipping planes. If the
+    /// camera and its target coincide, the camera is placed at the near clipping plane on the
+    /// positive Z side of the target.
+    pub fn new<R: CameraResources>(camera: &Camera<R>) -> Self {
+        let offset = Vector::from(camera.position) - Vector::from(camera.target);
+        let distance = offset.length();
+        let (azimuth, elevation) = if distance > 0. {
+            (offset.x().atan2(offset.z()), (offset.y() / distance).asin())
+        } else {
+            (0., 0.)
+        };
+
+        let mut orbit = Self {
+            azimuth,
+            elevation,
+            distance,
+            rotate_speed: 0.01,
+            zoom_factor: 0.9,
+            min_distance: camera.near,
+            max_distance: camera.far,
+        };
+        orbit.clamp();
+
+        orbit
+    }
+
+    /// Rotates and zooms by the given input and moves the camera to match, returning its new
+    /// position.
+    ///
+    /// `mouse_delta` is the movement of the mouse, as in pixels, along the X and Y axes: movement
+    /// along X changes the azimuth, and movement along Y changes the elevation. `scroll` is
+    /// positive toward the target. As the camera's [transformation
+    /// matrix](Camera::transformation_matrix) changes, it must be rewritten to the camera's
+    /// uniform afterward.
+    pub fn update<R: CameraResources>(
+        &mut self,
+        camera: &mut Camera<R>,
+        mouse_delta: (f32, f32),
+        scroll: f32,
+    ) -> Point {
+        let (dx, dy) = mouse_delta;
+        self.azimuth = (self.azimuth + (dx * self.rotate_speed)) % std::f32::consts::TAU;
+        self.elevation += dy * self.rotate_speed;
+        self.distance *= self.zoom_factor.powf(scroll);
+        self.clamp();
+
+        camera.position = self.position(camera.target);
+
+        camera.position
+    }
+
+    /// The position of a camera orbiting the given target.
+    pub fn position(&self, target: Point) -> Point {
+        let (sin_azimuth, cos_azimuth) = self.azimuth.sin_cos();
+        let (sin_elevation, cos_elevation) = self.elevation.sin_cos();
+
+        Point {
+            x: target.x + (self.distance * cos_elevation * sin_azimuth),
+            y: target.y + (self.distance * sin_elevation),
+            z: target.z + (self.distance * cos_elevation * cos_azimuth),
+        }
+    }
+
+    /// Keeps the elevation and distance within their limits.
+    fn clamp(&mut self) {
+        self.elevation = self.elevation.clamp(-Self::MAX_ELEVATION, Self::MAX_ELEVATION);
+        self.distance = self.distance.max(self.min_distance).min(self.max_distance);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CameraTransformsUniform;
+
+    /// Camera resources for tests that never render.
+    struct NoResources;
+
+    impl CameraResources for NoResources {
+        fn transforms_uniform(&self) -> &CameraTransformsUniform {
+            unreachable!("the camera is never rendered")
+        }
+    }
+
+    fn assert_point_eq(a: Point, b: Point) {
+        let offset = Vector::from(a) - Vector::from(b);
+        assert!(offset.length() < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn new_orbit_leaves_camera_in_place() {
+        let target = Point { x: 1., y: -2., z: 3. };
+        let camera = Camera::new(Point { x: 4., y: 2., z: -1. }, target, NoResources);
+
+        let orbit = Orbit::new(&camera);
+        assert_point_eq(orbit.position(target), camera.position);
+    }
+
+    #[test]
+    fn clamps_elevation_and_distance_after_large_deltas() {
+        let mut camera = Camera::new(Point { x: 0., y: 0., z: 5. }, Point::ORIGIN, NoResources);
+        let mut orbit = Orbit::new(&camera);
+
+        orbit.update(&mut camera, (0., 1e6), 1e3);
+        assert_eq!(orbit.elevation, Orbit::MAX_ELEVATION);
+        assert_eq!(orbit.distance, camera.near);
+
+        orbit.update(&mut camera, (0., -1e6), -1e3);
+        assert_eq!(orbit.elevation, -Orbit::MAX_ELEVATION);
+        assert_eq!(orbit.distance, camera.far);
+    }
+
+    #[test]
+    fn update_moves_only_the_position() {
+        let target = Point { x: 1., y: -2., z: 3. };
+        let mut camera = Camera::new(Point { x: 4., y: 2., z: -1. }, target, NoResources);
+        let mut orbit = Orbit::new(&camera);
+
+        let position = orbit.update(&mut camera, (30., -20.), 2.);
+        assert_eq!(camera.target, target);
+        assert_eq!(camera.position, position);
+        assert_point_eq(orbit.position(target), position);
+    }
+}
@@ -9,6 +9,8 @@
 //! managed. As such, memory management is performed externally and interfaced through Pylon via
 //! [`Camera`] and [`Object`].
 //!
+//! For simple cases, a [`Scene`] manages the camera and object uniforms itself.
+//!
 //! # Coordinate Spaces
 //!
 //! There are four coordinate spaces: **mesh** space, **world** space, **camera** space, and
@@ -45,15 +47,46 @@
 //! Clip space is the final destination for vertices and is produced by constraining camera space to
 //! the range `[-1, 1]` in all axes. During rasterization, clip space is compressed into a 2D
 //! viewport.
+//!
+//! # Handedness
+//!
+//! World space is right-handed. Seen through a [`Camera`] at the origin looking along the positive
+//! Z axis, the positive X axis points to the right of the viewport, the positive Y axis points to
+//! the bottom, and the positive Z axis points into the screen, away from the viewer.
+//!
+//! Assets from right-handed tools where Y points up and Z points toward the viewer need only be
+//! rotated half a turn about the X axis. Assets from left-handed tools are mirrored relative to
+//! Pylon's world space; rather than converting them, set the camera's
+//! [`handedness`](Camera::handedness) to [`Handedness::Left`], which flips Z at the view stage.
+//! Left-handed assets where Y points up must still be rotated as above.
+//!
+//! # Optional Features
+//!
+//! - **serde**: implements *serde*'s `Serialize` and `Deserialize` for plain data types like
+//!   [`Point`], [`Rotation`], [`Transform`], [`Matrix`], and [`MeshVertex`]. GPU-bound types are
+//!   excluded.
+//! - **image**: adds `Renderer::save_frame_png`, which saves the frame of a headless renderer to a
+//!   PNG file.
+//! - **rayon**: makes [`tree::Hierarchy::update_transforms`] compute the global transformation
+//!   matrices of each level of the hierarchy in parallel.
 
 #![feature(portable_simd)]
 
+pub mod bounds;
+pub mod camera;
+pub mod controls;
 pub mod linear;
 pub mod renderer;
+pub mod scene;
+pub mod transform;
 pub mod tree;
 
-pub use linear::{Matrix, Vector};
+pub use bounds::Aabb;
+pub use camera::{Camera, CameraResources, ClipPlanesError, Handedness};
+pub use linear::{Degrees, Matrix, Quaternion, Radians, Vector};
 pub use renderer::Renderer;
+pub use scene::Scene;
+pub use transform::Transform;
 
 /// The integral type for indexing a mesh's vertex pool.
 pub type MeshVertexIndex = u32;
@@ -66,6 +99,18 @@ impl From<Vector> for Point {
     }
 }
 
+impl From<(f32, f32, f32)> for Point {
+    fn from((x, y, z): (f32, f32, f32)) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl Default for Point {
+    fn default() -> Self {
+        Self::ORIGIN
+    }
+}
+
 /// A singular location within a coordinate space.
 ///
 /// The `Point` type definition does not prescribe a particular coordinate space to constrain its
@@ -73,8 +118,11 @@ impl From<Vector> for Point {
 /// example, in the context of clip space, all coordinates within a `Point` must lie between -1 and
 /// 1. It should also be noted that the fields [`x`](Self::x), [`y`](Self::y), and [`z`](Self::z)
 /// are unlimited and may contain arbitrary values.
+///
+/// The default `Point` is [`ORIGIN`](Self::ORIGIN).
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point {
     /// The X coordinate.
     pub x: f32,
@@ -87,6 +135,19 @@ pub struct Point {
 impl Point {
     /// The point that lies at `(0, 0, 0)`.
     pub const ORIGIN: Self = Self { x: 0., y: 0., z: 0. };
+
+    /// This point with each coordinate restricted to the box spanned by `min` and `max`.
+    ///
+    /// A point outside the box is moved onto its nearest boundary. As with [`Vector::clamp`], it
+    /// is the caller's responsibility to ensure that no coordinate of `min` exceeds the
+    /// corresponding coordinate of `max`; where one does, that coordinate is taken from `max`.
+    pub fn clamp(&self, min: Point, max: Point) -> Point {
+        Point {
+            x: self.x.max(min.x).min(max.x),
+            y: self.y.max(min.y).min(max.y),
+            z: self.z.max(min.z).min(max.z),
+        }
+    }
 }
 
 impl From<Point> for Vector {
@@ -95,12 +156,21 @@ impl From<Point> for Vector {
     }
 }
 
+impl Default for Rotation {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
 /// Gimbal rotation across three axes.
 ///
 /// [`x`](Self::x), [`y`](Self::y), and [`z`](Self::z) are in radians. The Z axis is rotated first,
 /// followed by Y and then X.
+///
+/// The default `Rotation` is [`ZERO`](Self::ZERO).
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rotation {
     /// The rotation, in radians, in the X axis.
     ///
@@ -118,18 +188,556 @@ pub struct Rotation {
 
 impl Rotation {
     pub const ZERO: Self = Self { x: 0., y: 0., z: 0. };
+
+    /// Creates a new `Rotation` from an angle in each axis.
+    ///
+    /// Each angle may be given in [`Radians`] or [`Degrees`].
+    pub fn new(x: impl Into<Radians>, y: impl Into<Radians>, z: impl Into<Radians>) -> Self {
+        Self {
+            x: x.into().0,
+            y: y.into().0,
+            z: z.into().0,
+        }
+    }
+
+    /// Creates a new `Rotation` from angles in degrees.
+    ///
+    /// The angles are converted to radians, which is the unit that `Rotation` stores. This is
+    /// equivalent to [`new`](Self::new) with [`Degrees`].
+    pub fn from_degrees(x: f32, y: f32, z: f32) -> Self {
+        Self::new(Degrees(x), Degrees(y), Degrees(z))
+    }
+
+    /// The X, Y, and Z rotations of this `Rotation`, in degrees.
+    pub fn to_degrees(&self) -> (f32, f32, f32) {
+        (
+            linear::rad_to_deg(self.x),
+            linear::rad_to_deg(self.y),
+            linear::rad_to_deg(self.z),
+        )
+    }
+
+    /// Creates a transformation matrix for this rotation.
+    ///
+    /// This is the product of the X, Y, and Z rotation matrices. Because we're using
+    /// pre-multiplication, the Z rotation is applied first, followed by Y and then X.
+    pub fn to_matrix(&self) -> Matrix {
+        return
+            self.create_x_matrix() *
+            self.create_y_matrix() *
+            self.create_z_matrix();
+    }
+
+    /// Linearly interpolates between this rotation and `other`.
+    ///
+    /// Each axis is interpolated independently, such that `t = 0` produces `self` and `t = 1`
+    /// produces `other`. This is a naive Euler angle interpolation&mdash;not a spherical linear
+    /// interpolation (slerp)&mdash;so the intermediate orientations may not follow the shortest
+    /// path between the two rotations.
+    pub fn lerp(&self, other: Rotation, t: f32) -> Rotation {
+        let lerp = |a: f32, b: f32| a + ((b - a) * t);
+
+        Self {
+            x: lerp(self.x, other.x),
+            y: lerp(self.y, other.y),
+            z: lerp(self.z, other.z),
+        }
+    }
+
+    /// Creates a transformation matrix for the X rotation.
+    fn create_x_matrix(&self) -> Matrix {
+        let SinCos { sin: s, cos: c } = SinCos::new(self.x);
+
+        Matrix::new(
+            1., 0., 0., 0.,
+            0.,  c, -s, 0.,
+            0.,  s,  c, 0.,
+            0., 0., 0., 1.,
+        )
+    }
+
+    /// Creates a transformation matrix for the Y rotation.
+    fn create_y_matrix(&self) -> Matrix {
+        let SinCos { sin: s, cos: c } = SinCos::new(self.y);
+
+        Matrix::new(
+             c, 0.,  s, 0.,
+            0., 1., 0., 0.,
+            -s, 0.,  c, 0.,
+            0., 0., 0., 1.,
+        )
+    }
+
+    /// Creates a transformation matrix for the Z rotation.
+    fn create_z_matrix(&self) -> Matrix {
+        let SinCos { sin: s, cos: c } = SinCos::new(self.z);
+
+        Matrix::new(
+             c, -s, 0., 0.,
+             s,  c, 0., 0.,
+            0., 0., 1., 0.,
+            0., 0., 0., 1.,
+        )
+    }
+}
+
+impl SinCos {
+    fn new(radians: f32) -> Self {
+        Self {
+            sin: radians.sin(),
+            cos: radians.cos(),
+        }
+    }
+}
+
+struct SinCos {
+    sin: f32,
+    cos: f32,
+}
+
+/// An RGBA color with components in linear space.
+///
+/// Pylon's surface has an sRGB format, so the GPU encodes linear colors as sRGB when they are
+/// written to it. Colors picked from image editors and the web are usually sRGB-encoded already and
+/// should be converted with [`from_srgb_u8`](Self::from_srgb_u8) or [`from_srgb`](Self::from_srgb).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Color {
+    /// The linear red component, from 0 to 1.
+    pub r: f32,
+    /// The linear green component, from 0 to 1.
+    pub g: f32,
+    /// The linear blue component, from 0 to 1.
+    pub b: f32,
+    /// The alpha component, from 0 (transparent) to 1 (opaque).
+    ///
+    /// Alpha is never sRGB-encoded.
+    pub a: f32,
+}
+
+impl Color {
+    pub const TRANSPARENT: Self = Self::new(0., 0., 0., 0.);
+    pub const BLACK: Self = Self::new(0., 0., 0., 1.);
+    pub const WHITE: Self = Self::new(1., 1., 1., 1.);
+
+    /// Creates a new `Color` from linear components.
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Creates a new `Color` from sRGB-encoded components from 0 to 1 and a linear alpha.
+    pub fn from_srgb(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self::new(srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a)
+    }
+
+    /// Creates a new `Color` from 8-bit sRGB-encoded components and an 8-bit linear alpha, as in
+    /// CSS hex colors.
+    ///
+    /// For example, the sRGB mid-gray `(188, 188, 188)` is approximately 0.5 in linear space.
+    pub fn from_srgb_u8(r: u8, g: u8, b: u8, a: u8) -> Self {
+        let unorm = |c: u8| f32::from(c) / 255.;
+
+        Self::from_srgb(unorm(r), unorm(g), unorm(b), unorm(a))
+    }
+
+    /// Converts this color to a *wgpu* color, which is likewise in linear space.
+    pub fn to_wgpu(self) -> wgpu::Color {
+        wgpu::Color {
+            r: f64::from(self.r),
+            g: f64::from(self.g),
+            b: f64::from(self.b),
+            a: f64::from(self.a),
+        }
+    }
+}
+
+impl From<Color> for wgpu::Color {
+    fn from(color: Color) -> Self {
+        color.to_wgpu()
+    }
+}
+
+/// Decodes an sRGB-encoded component to linear space.
+fn srgb_to_linear(c: f32) -> f32 {
+    // This is the piecewise sRGB transfer function from IEC 61966-2-1.
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// A triangle mesh.
+///
+/// Meshes may be created from a vertex pool and the triangles that index into it with
+/// [`new`](Self::new) or built incrementally with a [`MeshBuilder`].
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mesh {
+    /// The vertices referenced by [`triangles`](Self::triangles).
+    pub vertex_pool: Vec<MeshVertex>,
+    /// The triangles of this mesh, each a triad of indices into
+    /// [`vertex_pool`](Self::vertex_pool).
+    pub triangles: Vec<MeshTriangle>,
+}
+
+impl Mesh {
+    /// Creates a new `Mesh` from a vertex pool and the triangles that index into it.
+    pub fn new(vertex_pool: Vec<MeshVertex>, triangles: Vec<MeshTriangle>) -> Self {
+        Self { vertex_pool, triangles }
+    }
+
+    /// Creates a new `Mesh` from parallel arrays of vertex attributes and a flat array of indices,
+    /// as stored by glTF and many other formats.
+    ///
+    /// The attributes of each vertex are interleaved into a [`MeshVertex`], and each three indices
+    /// form a triangle. If `normals` or `uvs` is `None`, every normal or texture coordinate is
+    /// zero. Every vertex is [opaque white](MeshVertex::DEFAULT_COLOR).
+    ///
+    /// This fails if `normals` or `uvs` is not as long as `positions`, if the number of indices is
+    /// not a multiple of three, or if an index is out of range.
+    pub fn from_arrays(
+        positions: &[[f32; 3]],
+        normals: Option<&[[f32; 3]]>,
+        uvs: Option<&[[f32; 2]]>,
+        indices: &[MeshVertexIndex],
+    ) -> Result<Self, MeshError> {
+        let check_len = |attribute, len: Option<usize>| match len {
+            Some(len) if len != positions.len() => Err(MeshError::AttributeLengthMismatch {
+                attribute,
+                len,
+                vertex_count: positions.len(),
+            }),
+            _ => Ok(()),
+        };
+        check_len("normals", normals.map(<[_]>::len))?;
+        check_len("uvs", uvs.map(<[_]>::len))?;
+        if !indices.len().is_multiple_of(3) {
+            return Err(MeshError::IncompleteTriangle { index_count: indices.len() });
+        }
+        if let Some(&index) = indices.iter().find(|&&index| index as usize >= positions.len()) {
+            return Err(MeshError::IndexOutOfRange { index, vertex_count: positions.len() });
+        }
+
+        let to_point = |[x, y, z]: [f32; 3]| Point { x, y, z };
+        let vertex_pool = positions
+            .iter()
+            .enumerate()
+            .map(|(i, &position)| {
+                MeshVertex::new(
+                    to_point(position),
+                    normals.map_or(Point::ORIGIN, |normals| to_point(normals[i])),
+                )
+                .with_uv(uvs.map_or([0., 0.], |uvs| uvs[i]))
+            })
+            .collect();
+        let triangles = indices
+            .chunks_exact(3)
+            .map(|triangle| MeshTriangle([triangle[0], triangle[1], triangle[2]]))
+            .collect();
+
+        Ok(Self { vertex_pool, triangles })
+    }
+
+    /// The vertices referenced by [the triangles](Self::triangles) of this mesh.
+    pub fn vertex_pool(&self) -> &[MeshVertex] {
+        &self.vertex_pool
+    }
+
+    /// The triangles of this mesh.
+    pub fn triangles(&self) -> &[MeshTriangle] {
+        &self.triangles
+    }
+
+    /// The number of vertices in [the vertex pool](Self::vertex_pool).
+    ///
+    /// This is suitable for [`Object::vertex_count`].
+    pub fn vertex_count(&self) -> u32 {
+        self.vertex_pool.len() as u32
+    }
+
+    /// The number of triangles in this mesh.
+    ///
+    /// This is suitable for [`Object::triangle_count`].
+    pub fn triangle_count(&self) -> u32 {
+        self.triangles.len() as u32
+    }
+
+    /// Appends the vertices and triangles of `other` to this mesh.
+    ///
+    /// The indices of the appended triangles are offset by the previous size of
+    /// [the vertex pool](Self::vertex_pool) so that they refer to the appended vertices. The merged
+    /// mesh may then be drawn in a single call.
+    ///
+    /// # Panics
+    ///
+    /// This panics, leaving this mesh unmodified, if the merged vertex pool would be too large to
+    /// be indexed by [`MeshVertexIndex`].
+    pub fn merge(&mut self, other: &Mesh) {
+        let max_vertex_count = u64::from(MeshVertexIndex::MAX) + 1;
+        let vertex_count = (self.vertex_pool.len() as u64) + (other.vertex_pool.len() as u64);
+        assert!(
+            vertex_count <= max_vertex_count,
+            "merged mesh would have {} vertices, but `MeshVertexIndex` can index only {}",
+            vertex_count,
+            max_vertex_count,
+        );
+        // This can't truncate, as the merged pool is indexable.
+        let offset = self.vertex_pool.len() as MeshVertexIndex;
+
+        self.vertex_pool.extend_from_slice(&other.vertex_pool);
+        self.triangles.extend(
+            other.triangles.iter().map(|MeshTriangle(indices)| {
+                MeshTriangle(indices.map(|index| index + offset))
+            }),
+        );
+    }
+
+    /// Transforms the vertices of this mesh in place, as for baking an object's transformation
+    /// matrix into its mesh before [merging](Self::merge) it with others in world space.
+    ///
+    /// Points are multiplied by `matrix` without a perspective divide. Normals are multiplied by
+    /// the [normal matrix](ObjectTransforms::normal_matrix), the inverse-transpose of `matrix`,
+    /// which keeps them perpendicular to the surface under non-uniform scale, and are then
+    /// renormalized. Zero normals are left as they are.
+    ///
+    /// A matrix that mirrors the mesh, having a negative determinant, reverses the winding order of
+    /// its triangles, which is not corrected here.
+    pub fn transform(&mut self, matrix: &Matrix) {
+        let normal_matrix = Matrix::from(ObjectTransforms::new(matrix).normal_matrix);
+        for vertex in &mut self.vertex_pool {
+            vertex.point = *matrix * vertex.point;
+
+            let normal = normal_matrix.mul_direction(Vector::from_direction([
+                vertex.normal.x,
+                vertex.normal.y,
+                vertex.normal.z,
+            ]));
+            let length = normal.length();
+            if length > 0. {
+                vertex.normal = Point::from(normal / length);
+            }
+        }
+    }
+
+    /// Reverses the winding order of every triangle in this mesh, which turns it inside out.
+    ///
+    /// The indices of each triangle are reversed, so `[a, b, c]` becomes `[c, b, a]`. This
+    /// corrects meshes from tools with the opposite winding convention to Pylon's, in which the
+    /// front of a triangle is the side from which its vertices appear counterclockwise.
+    pub fn flip_winding(&mut self) {
+        for MeshTriangle(indices) in &mut self.triangles {
+            indices.reverse();
+        }
+    }
+
+    /// Reverses the winding order of each triangle that faces away from its vertex normals.
+    ///
+    /// A triangle `[a, b, c]` faces along its geometric normal, `(b - a) x (c - a)`. If that
+    /// points away from the sum of the normals of its vertices, the triangle is
+    /// [flipped](Self::flip_winding). Triangles whose geometric normal or vertex normals sum to
+    /// zero, as for degenerate triangles, are left as they are.
+    pub fn fix_winding_from_normals(&mut self) {
+        let to_vector = |Point { x, y, z }: Point| Vector::from_direction([x, y, z]);
+        for MeshTriangle(indices) in &mut self.triangles {
+            let [a, b, c] = indices.map(|index| self.vertex_pool[index as usize]);
+            let (pa, pb, pc) = (to_vector(a.point), to_vector(b.point), to_vector(c.point));
+            let geometric_normal = (pb - pa).cross(pc - pa);
+            let vertex_normal = to_vector(a.normal) + to_vector(b.normal) + to_vector(c.normal);
+
+            if geometric_normal.dot(vertex_normal) < 0. {
+                indices.reverse();
+            }
+        }
+    }
+}
+
+/// Builds a [`Mesh`] one vertex and triangle at a time, as for procedural geometry.
+///
+/// Vertices are numbered in the order they are pushed, and triangles may refer only to vertices
+/// that have already been pushed, so the built mesh never has an out-of-range index.
+///
+/// With [vertex deduplication](Self::deduplicate_vertices), pushing a vertex that is bitwise
+/// identical to an earlier one returns the earlier vertex's index rather than growing the vertex
+/// pool.
+#[derive(Clone, Debug, Default)]
+pub struct MeshBuilder {
+    mesh: Mesh,
+    /// The index of each distinct vertex by its bits, if vertices are deduplicated.
+    vertex_indices: Option<std::collections::HashMap<[u32; 12], MeshVertexIndex>>,
+}
+
+impl MeshBuilder {
+    /// Creates a new `MeshBuilder` for an empty mesh that doesn't deduplicate vertices.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether or not to deduplicate vertices as they are pushed.
+    ///
+    /// Vertices are compared bitwise, so `0.0` and `-0.0` are distinct. This applies only to
+    /// vertices pushed after it is enabled.
+    pub fn deduplicate_vertices(mut self, deduplicate: bool) -> Self {
+        self.vertex_indices = deduplicate.then(std::collections::HashMap::new);
+        self
+    }
+
+    /// Appends a vertex to the vertex pool, returning its index.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the vertex pool is too large to be indexed by [`MeshVertexIndex`].
+    pub fn push_vertex(&mut self, vertex: MeshVertex) -> MeshVertexIndex {
+        let key: [u32; 12] = bytemuck::cast(vertex);
+        if let Some(&index) = self.vertex_indices.as_ref().and_then(|indices| indices.get(&key)) {
+            return index;
+        }
+
+        let index = MeshVertexIndex::try_from(self.mesh.vertex_pool.len())
+            .expect("vertex pool is too large to be indexed by `MeshVertexIndex`");
+        self.mesh.vertex_pool.push(vertex);
+        if let Some(ref mut indices) = self.vertex_indices {
+            indices.insert(key, index);
+        }
+
+        index
+    }
+
+    /// Appends a triangle of previously-pushed vertices.
+    ///
+    /// # Panics
+    ///
+    /// This panics if any index does not refer to a pushed vertex.
+    pub fn push_triangle(&mut self, indices: [MeshVertexIndex; 3]) {
+        let vertex_count = self.mesh.vertex_count();
+        for index in indices {
+            assert!(
+                index < vertex_count,
+                "vertex index {} is out of range for a vertex pool of {} vertices",
+                index,
+                vertex_count,
+            );
+        }
+        self.mesh.triangles.push(MeshTriangle(indices));
+    }
+
+    /// The number of vertices in the vertex pool so far.
+    pub fn vertex_count(&self) -> u32 {
+        self.mesh.vertex_count()
+    }
+
+    /// The number of triangles so far.
+    pub fn triangle_count(&self) -> u32 {
+        self.mesh.triangle_count()
+    }
+
+    /// Finishes the mesh.
+    pub fn build(self) -> Mesh {
+        self.mesh
+    }
 }
 
+/// An error that occurs when [creating a mesh from arrays](Mesh::from_arrays).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MeshError {
+    /// An array of vertex attributes was not as long as the array of positions.
+    AttributeLengthMismatch {
+        /// The name of the offending array.
+        attribute: &'static str,
+        /// The length of the offending array.
+        len: usize,
+        /// The number of positions.
+        vertex_count: usize,
+    },
+    /// The number of indices was not a multiple of three.
+    IncompleteTriangle {
+        /// The offending number of indices.
+        index_count: usize,
+    },
+    /// An index referred to a vertex beyond the end of the array of positions.
+    IndexOutOfRange {
+        /// The offending index.
+        index: MeshVertexIndex,
+        /// The number of positions.
+        vertex_count: usize,
+    },
+}
+
+impl std::fmt::Display for MeshError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AttributeLengthMismatch { attribute, len, vertex_count } => {
+                write!(f, "{} {} given for {} vertices", len, attribute, vertex_count)
+            }
+            Self::IncompleteTriangle { index_count } => {
+                write!(f, "{} indices do not form whole triangles", index_count)
+            }
+            Self::IndexOutOfRange { index, vertex_count } => {
+                write!(f, "index {} is out of range for {} vertices", index, vertex_count)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MeshError {}
+
 /// A vertex within a mesh.
+#[repr(C)]
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MeshVertex {
     /// The location of this vertex in mesh space.
     pub point: Point,
+    /// The unit vector, in mesh space, that is perpendicular to the surface at this vertex.
+    ///
+    /// Pylon's built-in vertex shader transforms normals to world space by the object's normal
+    /// matrix and passes them to fragment shaders at `@location(0)`. They are also drawn by
+    /// [`Renderer::render_debug`].
+    pub normal: Point,
+    /// The linear RGBA color of this vertex.
+    ///
+    /// This is interpolated across triangles and passed to the fragment shaders of
+    /// [vertex-colored pipelines](Renderer::create_vertex_colored_pipeline); other built-in
+    /// shaders ignore it. Custom vertex shaders receive it at `@location(2)`.
+    #[cfg_attr(feature = "serde", serde(default = "MeshVertex::default_color"))]
+    pub color: [f32; 4],
+    /// The texture coordinates of this vertex.
+    ///
+    /// Built-in shaders ignore these. Custom vertex shaders receive them at `@location(3)`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub uv: [f32; 2],
 }
 
 unsafe impl bytemuck::Pod for MeshVertex {}
 unsafe impl bytemuck::Zeroable for MeshVertex {}
 
+impl MeshVertex {
+    /// The color of vertices that are not otherwise colored, which is opaque white.
+    pub const DEFAULT_COLOR: [f32; 4] = [1., 1., 1., 1.];
+
+    /// Creates a new `MeshVertex` of the [default color](Self::DEFAULT_COLOR) with zero texture
+    /// coordinates.
+    pub const fn new(point: Point, normal: Point) -> Self {
+        Self { point, normal, color: Self::DEFAULT_COLOR, uv: [0., 0.] }
+    }
+
+    /// Sets the color of this vertex.
+    pub const fn with_color(self, color: [f32; 4]) -> Self {
+        Self { color, ..self }
+    }
+
+    /// Sets the texture coordinates of this vertex.
+    pub const fn with_uv(self, uv: [f32; 2]) -> Self {
+        Self { uv, ..self }
+    }
+
+    /// The [default color](Self::DEFAULT_COLOR), for vertices deserialized without one.
+    #[cfg(feature = "serde")]
+    fn default_color() -> [f32; 4] {
+        Self::DEFAULT_COLOR
+    }
+}
+
 impl MeshTriangle {
     /// Creates a new `MeshTriangle` from a triad of vertex indices.
     pub const fn new(indices: [MeshVertexIndex; 3]) -> Self {
@@ -139,20 +747,25 @@ impl MeshTriangle {
 
 /// A triangle within a mesh.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MeshTriangle([MeshVertexIndex; 3]);
 
 unsafe impl bytemuck::Pod for MeshTriangle {}
 unsafe impl bytemuck::Zeroable for MeshTriangle {}
 
-/// The interface to user-managed camera resources.
-pub trait Camera {
-    fn transforms_uniform(&self) -> &CameraTransformsUniform;
-}
-
 /// The interface to user-managed object resources.
 pub trait Object {
     fn triangle_count(&self) -> u32;
 
+    /// The number of [`MeshVertex`]s in [the vertex buffer](Self::vertex_buffer).
+    ///
+    /// This is needed only to draw every vertex, as [`Renderer::render_debug`] does for normals
+    /// and [`Pass::draw_points`](renderer::Pass::draw_points) does for points. The default
+    /// implementation returns zero, so objects that don't implement this draw no normals or points.
+    fn vertex_count(&self) -> u32 {
+        0
+    }
+
     /// The [pipeline](wgpu::RenderPipeline) to be used during rendering of this object.
     ///
     /// This type may be created via [`Renderer::create_pipeline`].
@@ -160,6 +773,42 @@ pub trait Object {
 
     fn transforms_uniform(&self) -> &ObjectTransformsUniform;
 
+    /// The dynamic offset, in bytes, of this object's transformation matrix relative to the binding
+    /// of [its transforms uniform](Self::transforms_uniform).
+    ///
+    /// Objects that share a uniform buffer should each report a distinct offset. The offset must be
+    /// a multiple of the device's `min_uniform_buffer_offset_alignment` limit, which is 256 bytes
+    /// on most hardware.
+    ///
+    /// The default implementation returns zero, which is correct for objects that own their
+    /// uniform.
+    fn transforms_offset(&self) -> u32 {
+        0
+    }
+
+    /// The buffer behind [this object's transforms uniform](Self::transforms_uniform) and the
+    /// offset, in bytes, of this object's [`ObjectTransforms`] within it, if the object exposes
+    /// them.
+    ///
+    /// The offset is that of the uniform's binding within the buffer plus
+    /// [`transforms_offset`](Self::transforms_offset). Exposing the buffer lets
+    /// [`Renderer::update_object_transform`] rewrite the object's transforms. The default
+    /// implementation returns `None`.
+    fn transforms_buffer(&self) -> Option<(&wgpu::Buffer, wgpu::BufferAddress)> {
+        None
+    }
+
+    /// Whether or not this object is drawn.
+    ///
+    /// Invisible objects are skipped entirely: they issue no draw calls, aren't drawn by
+    /// [`Renderer::render_debug`], and cast no shadows in [`Renderer::render_depth`]. Toggling
+    /// this is cheaper than rebuilding the list of objects to draw.
+    ///
+    /// The default implementation returns `true`.
+    fn visible(&self) -> bool {
+        true
+    }
+
     /// The [bind group slots](BindGroupSlot) to be assigned for rendering this object.
     ///
     /// Slots are assigned in ascending order of index. If a slot is assigned to twice, the first
@@ -171,19 +820,88 @@ pub trait Object {
     ///
     /// To guarantee vertex shader compatibility, this buffer should contain a packed sequence of
     /// [`MeshTriangle`]s.
+    ///
+    /// The index and [vertex](Self::vertex_buffer) buffers need not be slices of the same buffer,
+    /// and either may be shared with other objects. Objects whose vertices differ but whose
+    /// triangles are the same may share one index buffer. Indices are relative to the start of the
+    /// vertex buffer slice unless the object is drawn with
+    /// [`Pass::draw_object_with_base_vertex`](renderer::Pass::draw_object_with_base_vertex).
     fn index_buffer<'a>(&'a self) -> wgpu::BufferSlice<'a>;
 
+    /// The size, in bytes, of [the index buffer slice](Self::index_buffer), if known.
+    ///
+    /// *wgpu* doesn't expose the size of a buffer slice, so this is reported separately. In debug
+    /// builds, Pylon checks that a known size is a whole number of [`MeshTriangle`]s and covers
+    /// [`triangle_count`](Self::triangle_count) triangles. The default implementation returns
+    /// `None`, which skips these checks.
+    fn index_buffer_size(&self) -> Option<wgpu::BufferAddress> {
+        None
+    }
+
     /// A slice into a GPU buffer that contains the vertex data for this object.
     ///
     /// To guarantee vertex shader compatibility, this buffer should contain a sequence of
     /// [`MeshVertex`]s.
     fn vertex_buffer<'a>(&'a self) -> wgpu::BufferSlice<'a>;
+
+    /// The size, in bytes, of [the vertex buffer slice](Self::vertex_buffer), if known.
+    ///
+    /// In debug builds, Pylon checks that a known size is a whole number of [`MeshVertex`]s. The
+    /// default implementation returns `None`, which skips this check.
+    fn vertex_buffer_size(&self) -> Option<wgpu::BufferAddress> {
+        None
+    }
 }
 
 pub struct CameraTransformsUniform(TransformsUniform);
 
 pub struct ObjectTransformsUniform(TransformsUniform);
 
+/// The contents of the uniform buffer binding behind an [`ObjectTransformsUniform`].
+///
+/// This is 128 bytes: two 4x4 matrices of `f32`, in column-major order. The built-in vertex shader
+/// declares the same layout.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct ObjectTransforms {
+    /// The matrix that transforms points of the object from mesh space to world space.
+    pub transformation_matrix: [[f32; 4]; 4],
+    /// The matrix that transforms normals of the object from mesh space to world space.
+    ///
+    /// This is the inverse-transpose of the upper-left 3x3 of
+    /// [the transformation matrix](Self::transformation_matrix), which keeps normals perpendicular
+    /// to their surface under non-uniform scale. It is padded to 4x4 as WGSL aligns each column of
+    /// a 3x3 matrix to 16 bytes regardless; the padding is that of the identity matrix.
+    pub normal_matrix: [[f32; 4]; 4],
+}
+
+unsafe impl bytemuck::Pod for ObjectTransforms {}
+unsafe impl bytemuck::Zeroable for ObjectTransforms {}
+
+impl ObjectTransforms {
+    /// Creates a new `ObjectTransforms` from a transformation matrix, computing its normal matrix.
+    ///
+    /// If the transformation matrix is not invertible, as when an object is scaled by zero, the
+    /// normal matrix is the identity.
+    pub fn new(transformation_matrix: &Matrix) -> Self {
+        let mut normal_matrix = transformation_matrix
+            .inverse()
+            .map_or(Matrix::IDENTITY, |inverse| inverse.transpose())
+            .to_array();
+        // Transposition moves the inverse translation into the bottom row, which normals have no
+        // use for.
+        for column in &mut normal_matrix[..3] {
+            column[3] = 0.;
+        }
+        normal_matrix[3] = [0., 0., 0., 1.];
+
+        Self {
+            transformation_matrix: transformation_matrix.to_array(),
+            normal_matrix,
+        }
+    }
+}
+
 struct TransformsUniform {
     bind_group: wgpu::BindGroup,
 }
@@ -195,3 +913,251 @@ pub struct BindGroupSlot<'a> {
     /// The bind group to be assigned to the slot described by [`index`](Self::index).
     pub bind_group: &'a wgpu::BindGroup,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asserts that each element of `a` is within a small tolerance of that of `b`.
+    fn assert_matrix_eq(a: Matrix, b: Matrix) {
+        let (a, b) = (a.to_array(), b.to_array());
+        for (a, b) in a.iter().flatten().zip(b.iter().flatten()) {
+            assert!((a - b).abs() < 1e-5, "{:?} != {:?}", a, b);
+        }
+    }
+
+    /// Creates a cube from -1 to 1 in each axis whose triangles face outward.
+    ///
+    /// Vertex `i` lies at -1 or 1 in X, Y, and Z according to bits 0, 1, and 2 of `i`, and its
+    /// normal points away from the center.
+    fn cube() -> Mesh {
+        let vertex_pool = (0..8)
+            .map(|i| {
+                let coordinate = |bit: u32| if i & (1 << bit) == 0 { -1. } else { 1. };
+                let point = Point { x: coordinate(0), y: coordinate(1), z: coordinate(2) };
+
+                MeshVertex::new(point, point)
+            })
+            .collect();
+        let triangles = [
+            [4, 6, 2], [4, 2, 0], [1, 3, 7], [1, 7, 5],
+            [0, 1, 5], [0, 5, 4], [6, 7, 3], [6, 3, 2],
+            [2, 3, 1], [2, 1, 0], [4, 5, 7], [4, 7, 6],
+        ];
+
+        Mesh::new(vertex_pool, triangles.into_iter().map(MeshTriangle::new).collect())
+    }
+
+    #[test]
+    fn rotation_from_degrees_is_in_radians() {
+        let rotation = Rotation::from_degrees(180., 0., 0.);
+        assert!((rotation.x - std::f32::consts::PI).abs() < 1e-6);
+        assert_eq!((rotation.y, rotation.z), (0., 0.));
+    }
+
+    #[test]
+    fn rotation_matrix_matches_node() {
+        let rotation = Rotation::new(0.3, -1.2, 2.5);
+        let node: std::rc::Rc<tree::Node> = tree::Node::root(Point::ORIGIN, rotation, 1.);
+        assert_matrix_eq(rotation.to_matrix(), node.local_transformation_matrix());
+
+        // A quarter turn about Z turns the X axis into the Y axis.
+        let quarter_turn = Rotation::new(0., 0., std::f32::consts::FRAC_PI_2);
+        let Point { x, y, z } = quarter_turn.to_matrix() * Point { x: 1., y: 0., z: 0. };
+        assert!(x.abs() < 1e-6 && (y - 1.).abs() < 1e-6 && z.abs() < 1e-6);
+    }
+
+    #[test]
+    fn rotation_lerp_matches_node_at_midpoint() {
+        let a = Rotation::new(0., 0.5, -1.);
+        let b = Rotation::new(1., 1.5, 1.);
+        assert_matrix_eq(a.lerp(b, 0.).to_matrix(), a.to_matrix());
+        assert_matrix_eq(a.lerp(b, 1.).to_matrix(), b.to_matrix());
+
+        let midpoint: std::rc::Rc<tree::Node> =
+            tree::Node::root(Point::ORIGIN, Rotation::new(0.5, 1., 0.), 1.);
+        assert_matrix_eq(a.lerp(b, 0.5).to_matrix(), midpoint.local_transformation_matrix());
+    }
+
+    #[test]
+    fn converts_srgb_to_linear() {
+        let gray = Color::from_srgb_u8(188, 188, 188, 255);
+        for c in [gray.r, gray.g, gray.b] {
+            assert!((c - 0.5).abs() < 0.005, "{} is not about 0.5", c);
+        }
+        assert_eq!(gray.a, 1.);
+
+        // The ends of the range and alpha are unchanged.
+        assert_eq!(Color::from_srgb(0., 1., 0., 0.25), Color::new(0., 1., 0., 0.25));
+    }
+
+    #[test]
+    fn creates_cube_mesh() {
+        let mesh = cube();
+        assert_eq!(mesh.vertex_count(), 8);
+        assert_eq!(mesh.triangle_count(), 12);
+        assert_eq!(mesh.triangles()[1].0, [4, 2, 0]);
+        assert_eq!(mesh.vertex_pool()[7].point, Point { x: 1., y: 1., z: 1. });
+    }
+
+    #[test]
+    fn merges_meshes_with_offset_indices() {
+        let mut mesh = cube();
+        mesh.merge(&cube());
+
+        assert_eq!(mesh.vertex_pool().len(), 16);
+        assert_eq!(mesh.triangles().len(), 24);
+        let (first, second) = mesh.triangles().split_at(12);
+        for (MeshTriangle(a), MeshTriangle(b)) in first.iter().zip(second) {
+            assert_eq!(a.map(|index| index + 8), *b);
+        }
+        assert!(mesh.triangles().iter().flat_map(|MeshTriangle(t)| t).all(|&index| index < 16));
+    }
+
+    #[test]
+    fn transform_translates_every_vertex() {
+        let translation = Matrix::new(
+            1., 0., 0., 1.,
+            0., 1., 0., 2.,
+            0., 0., 1., 3.,
+            0., 0., 0., 1.,
+        );
+        let original = cube();
+        let mut mesh = cube();
+        mesh.transform(&translation);
+
+        for (before, after) in original.vertex_pool().iter().zip(mesh.vertex_pool()) {
+            let Point { x, y, z } = before.point;
+            assert_eq!(after.point, Point { x: x + 1., y: y + 2., z: z + 3. });
+            // Translation doesn't affect directions, but normals are renormalized.
+            let direction = |Point { x, y, z }: Point| Vector::from_direction([x, y, z]);
+            let normal = direction(before.normal).normalize();
+            assert!((direction(after.normal) - normal).length() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn clamps_point_onto_box_boundary() {
+        let min = Point { x: -1., y: -1., z: -1. };
+        let max = Point { x: 1., y: 2., z: 3. };
+
+        let outside = Point { x: -5., y: 0.5, z: 10. };
+        assert_eq!(outside.clamp(min, max), Point { x: -1., y: 0.5, z: 3. });
+        let inside = Point { x: 0.5, y: 1.5, z: -0.5 };
+        assert_eq!(inside.clamp(min, max), inside);
+        assert_eq!(max.clamp(min, max), max);
+    }
+
+    #[test]
+    fn builds_quad_with_deduplicated_vertices() {
+        let normal = Point { x: 0., y: 0., z: -1. };
+        let corner = |x, y| MeshVertex::new(Point { x, y, z: 0. }, normal);
+        let corners = [corner(0., 0.), corner(1., 0.), corner(1., 1.), corner(0., 1.)];
+
+        let mut builder = MeshBuilder::new().deduplicate_vertices(true);
+        for triangle in [[0, 1, 2], [0, 2, 3]] {
+            let indices = triangle.map(|i| builder.push_vertex(corners[i]));
+            builder.push_triangle(indices);
+        }
+        let mesh = builder.build();
+
+        assert_eq!(mesh.vertex_count(), 4);
+        assert_eq!(mesh.triangle_count(), 2);
+        let indices: Vec<_> = mesh.triangles().iter().map(|MeshTriangle(t)| *t).collect();
+        assert_eq!(indices, [[0, 1, 2], [0, 2, 3]]);
+    }
+
+    #[test]
+    fn rejects_mismatched_attribute_lengths() {
+        let positions = [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]];
+        let normals = [[0., 0., 1.]; 2];
+
+        let result = Mesh::from_arrays(&positions, Some(&normals), None, &[0, 1, 2]);
+        assert_eq!(
+            result.map(|_| ()),
+            Err(MeshError::AttributeLengthMismatch {
+                attribute: "normals",
+                len: 2,
+                vertex_count: 3,
+            }),
+        );
+        let result = Mesh::from_arrays(&positions, None, Some(&[[0., 0.]; 4]), &[0, 1, 2]);
+        assert_eq!(
+            result.map(|_| ()),
+            Err(MeshError::AttributeLengthMismatch {
+                attribute: "uvs",
+                len: 4,
+                vertex_count: 3,
+            }),
+        );
+        let normals = [[0., 0., 1.]; 3];
+        assert!(Mesh::from_arrays(&positions, Some(&normals), None, &[0, 1, 2]).is_ok());
+    }
+
+    #[test]
+    fn interleaves_uvs_from_arrays() {
+        let positions = [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]];
+        let uvs = [[0., 0.], [1., 0.], [0., 1.]];
+
+        let mesh = Mesh::from_arrays(&positions, None, Some(&uvs), &[0, 1, 2])
+            .expect("arrays should be valid");
+        let mesh_uvs: Vec<_> = mesh.vertex_pool().iter().map(|vertex| vertex.uv).collect();
+        assert_eq!(mesh_uvs, uvs);
+        // Without UVs, every texture coordinate is zero.
+        let mesh = Mesh::from_arrays(&positions, None, None, &[0, 1, 2])
+            .expect("arrays should be valid");
+        assert!(mesh.vertex_pool().iter().all(|vertex| vertex.uv == [0., 0.]));
+    }
+
+    #[test]
+    fn flips_cube_winding() {
+        let original = cube();
+        let mut mesh = cube();
+        mesh.flip_winding();
+
+        for (MeshTriangle([a, b, c]), MeshTriangle(flipped)) in
+            original.triangles().iter().zip(mesh.triangles())
+        {
+            assert_eq!(*flipped, [*c, *b, *a]);
+        }
+        // Flipping twice restores the original winding.
+        mesh.flip_winding();
+        let indices = |mesh: &Mesh| mesh.triangles().iter().map(|MeshTriangle(t)| *t).collect();
+        let restored: Vec<_> = indices(&mesh);
+        assert_eq!(restored, indices(&original));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn mesh_round_trips_through_ron() {
+        let normal = Point { x: 0., y: 0., z: -1. };
+        let mesh = Mesh::new(
+            vec![
+                MeshVertex::new(Point { x: -1., y: 1., z: 0. }, normal),
+                MeshVertex::new(Point { x: 1., y: 1., z: 0. }, normal).with_color([1., 0., 0., 1.]),
+                MeshVertex::new(Point { x: 0., y: -1., z: 0.5 }, normal).with_uv([0.5, 1.]),
+            ],
+            vec![MeshTriangle::new([0, 1, 2])],
+        );
+
+        let ron = ron::to_string(&mesh).unwrap();
+        let deserialized: Mesh = ron::from_str(&ron).unwrap();
+        let bytes = |mesh: &Mesh| {
+            let vertices: &[u8] = bytemuck::cast_slice(&mesh.vertex_pool);
+            let triangles: &[u8] = bytemuck::cast_slice(&mesh.triangles);
+
+            (vertices.to_vec(), triangles.to_vec())
+        };
+        assert_eq!(bytes(&deserialized), bytes(&mesh));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn matrix_round_trips_through_ron() {
+        let matrix = Transform::new(Point { x: 1., y: 2., z: 3. }, Rotation::new(0.1, 0.2, 0.3), 2.)
+            .to_matrix();
+
+        let deserialized: Matrix = ron::from_str(&ron::to_string(&matrix).unwrap()).unwrap();
+        assert_eq!(deserialized.to_array(), matrix.to_array());
+    }
+}
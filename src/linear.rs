@@ -1,30 +1,213 @@
 //! Linear algebra definitions.
+//!
+//! [Matrices](Matrix) and [vectors](Vector) are generic over their element type, which may be
+//! either [`f32`] or [`f64`]. The default element type is [`Scalar`], which is what the GPU
+//! expects; `f64` matrices may be used for extra precision on the CPU and then [cast](Matrix::cast)
+//! to `f32` before they are uploaded.
 
-use std::{ops::{Add, AddAssign, Mul, MulAssign}, simd::Simd};
+use std::{
+    fmt::{self, Debug, Display},
+    ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub},
+    simd::{num::SimdFloat, Simd, SimdElement},
+};
+
+use crate::{Point, Rotation};
 
 /// The backing storage unit of [matrices](Matrix) and [vectors](Vector).
 pub type Scalar = f32;
 
-impl Matrix {
+/// A [`Matrix`] of [`f64`]s.
+pub type Matrix64 = Matrix<f64>;
+
+/// A [`Vector`] of [`f64`]s.
+pub type Vector64 = Vector<f64>;
+
+/// Converts an angle in degrees to radians.
+pub fn deg_to_rad(degrees: Scalar) -> Scalar {
+    degrees.to_radians()
+}
+
+/// Converts an angle in radians to degrees.
+pub fn rad_to_deg(radians: Scalar) -> Scalar {
+    radians.to_degrees()
+}
+
+impl From<Degrees> for Radians {
+    fn from(degrees: Degrees) -> Self {
+        Self(deg_to_rad(degrees.0))
+    }
+}
+
+/// A bare [`Scalar`] is taken to be in radians, which is what angle-taking functions expected
+/// before [`Radians`] existed.
+impl From<Scalar> for Radians {
+    fn from(radians: Scalar) -> Self {
+        Self(radians)
+    }
+}
+
+/// An angle in radians.
+///
+/// Functions that take an angle accept `impl Into<Radians>`, so they may be passed either a
+/// `Radians` or a [`Degrees`]. A bare [`Scalar`] is also accepted and is taken to be in radians.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Radians(pub Scalar);
+
+impl From<Radians> for Degrees {
+    fn from(radians: Radians) -> Self {
+        Self(rad_to_deg(radians.0))
+    }
+}
+
+/// An angle in degrees.
+///
+/// This is converted to [`Radians`] wherever an angle is taken.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Degrees(pub Scalar);
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+/// A floating-point type that may serve as the element type of [matrices](Matrix) and
+/// [vectors](Vector).
+///
+/// This trait is sealed and is implemented only for [`f32`] and [`f64`].
+pub trait Float:
+    private::Sealed
+    + SimdElement
+    + Copy
+    + Send
+    + Sync
+    + Debug
+    + Display
+    + PartialEq
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// Converts an `f64` to this type, rounding if necessary.
+    fn from_f64(value: f64) -> Self;
+
+    /// Converts this value to an `f64`.
+    fn to_f64(self) -> f64;
+
+    /// The square root of this value.
+    fn sqrt(self) -> Self;
+
+    #[doc(hidden)]
+    fn lanes_add(a: Simd<Self, 4>, b: Simd<Self, 4>) -> Simd<Self, 4>;
+
+    #[doc(hidden)]
+    fn lanes_mul(a: Simd<Self, 4>, b: Simd<Self, 4>) -> Simd<Self, 4>;
+
+    #[doc(hidden)]
+    fn lanes_min(a: Simd<Self, 4>, b: Simd<Self, 4>) -> Simd<Self, 4>;
+
+    #[doc(hidden)]
+    fn lanes_max(a: Simd<Self, 4>, b: Simd<Self, 4>) -> Simd<Self, 4>;
+
+    #[doc(hidden)]
+    fn reduce_sum(lanes: Simd<Self, 4>) -> Self;
+
+    #[doc(hidden)]
+    fn reduce_min(lanes: Simd<Self, 4>) -> Self;
+
+    #[doc(hidden)]
+    fn reduce_max(lanes: Simd<Self, 4>) -> Self;
+
+    #[doc(hidden)]
+    fn lanes_are_finite(lanes: Simd<Self, 4>) -> bool;
+}
+
+/// Implements [`Float`] for a primitive floating-point type.
+macro_rules! impl_float {
+    ($ty:ty) => {
+        impl Float for $ty {
+            const ZERO: Self = 0.;
+            const ONE: Self = 1.;
+
+            fn from_f64(value: f64) -> Self {
+                value as Self
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn sqrt(self) -> Self {
+                <$ty>::sqrt(self)
+            }
+
+            fn lanes_add(a: Simd<Self, 4>, b: Simd<Self, 4>) -> Simd<Self, 4> {
+                a + b
+            }
+
+            fn lanes_mul(a: Simd<Self, 4>, b: Simd<Self, 4>) -> Simd<Self, 4> {
+                a * b
+            }
+
+            fn lanes_min(a: Simd<Self, 4>, b: Simd<Self, 4>) -> Simd<Self, 4> {
+                a.simd_min(b)
+            }
+
+            fn lanes_max(a: Simd<Self, 4>, b: Simd<Self, 4>) -> Simd<Self, 4> {
+                a.simd_max(b)
+            }
+
+            fn reduce_sum(lanes: Simd<Self, 4>) -> Self {
+                lanes.reduce_sum()
+            }
+
+            fn reduce_min(lanes: Simd<Self, 4>) -> Self {
+                lanes.reduce_min()
+            }
+
+            fn reduce_max(lanes: Simd<Self, 4>) -> Self {
+                lanes.reduce_max()
+            }
+
+            fn lanes_are_finite(lanes: Simd<Self, 4>) -> bool {
+                lanes.is_finite().all()
+            }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
+
+impl<S: Float> Matrix<S> {
     /// Creates a new `Matrix` with the given 16 elements provided in left-to-right, top-to-bottom
     /// order.
     pub const fn new(
-        r0c0: Scalar,
-        r0c1: Scalar,
-        r0c2: Scalar,
-        r0c3: Scalar,
-        r1c0: Scalar,
-        r1c1: Scalar,
-        r1c2: Scalar,
-        r1c3: Scalar,
-        r2c0: Scalar,
-        r2c1: Scalar,
-        r2c2: Scalar,
-        r2c3: Scalar,
-        r3c0: Scalar,
-        r3c1: Scalar,
-        r3c2: Scalar,
-        r3c3: Scalar,
+        r0c0: S,
+        r0c1: S,
+        r0c2: S,
+        r0c3: S,
+        r1c0: S,
+        r1c1: S,
+        r1c2: S,
+        r1c3: S,
+        r2c0: S,
+        r2c1: S,
+        r2c2: S,
+        r2c3: S,
+        r3c0: S,
+        r3c1: S,
+        r3c2: S,
+        r3c3: S,
     ) -> Self {
         Self([
             Vector::new(r0c0, r1c0, r2c0, r3c0),
@@ -35,34 +218,93 @@ impl Matrix {
     }
 }
 
-/// A 4x4 square matrix of [`Scalar`](Scalar)s.
+impl<S: Float> Default for Matrix<S> {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// A 4x4 square matrix of [`Float`]s, which are [`Scalar`]s by default.
+///
+/// The default `Matrix` is [`IDENTITY`](Self::IDENTITY) rather than [`ZERO`](Self::ZERO), as the
+/// identity matrix is the transformation that leaves its operand unchanged.
 #[derive(Clone, Copy, Debug)]
-pub struct Matrix([Vector; 4]);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Matrix<S: Float = Scalar>([Vector<S>; 4]);
 
-impl Matrix {
+impl<S: Float> Matrix<S> {
     pub const ZERO: Self = Self::new(
-        0., 0., 0., 0.,
-        0., 0., 0., 0.,
-        0., 0., 0., 0.,
-        0., 0., 0., 0.,
+        S::ZERO, S::ZERO, S::ZERO, S::ZERO,
+        S::ZERO, S::ZERO, S::ZERO, S::ZERO,
+        S::ZERO, S::ZERO, S::ZERO, S::ZERO,
+        S::ZERO, S::ZERO, S::ZERO, S::ZERO,
     );
 
     pub const IDENTITY: Self = Self::new(
-        1., 0., 0., 0.,
-        0., 1., 0., 0.,
-        0., 0., 1., 0.,
-        0., 0., 0., 1.,
+        S::ONE, S::ZERO, S::ZERO, S::ZERO,
+        S::ZERO, S::ONE, S::ZERO, S::ZERO,
+        S::ZERO, S::ZERO, S::ONE, S::ZERO,
+        S::ZERO, S::ZERO, S::ZERO, S::ONE,
     );
 
-    pub fn columns(&self) -> &[Vector; 4] {
+    /// Creates a perspective projection matrix, which transforms camera space to clip space.
+    ///
+    /// `fov_y` is the vertical field of view, and `aspect` is the ratio of the width of the
+    /// viewport to its height. Points at a depth of `near` map to a clip-space depth of 0, and
+    /// points at `far` map to 1. The camera looks along the positive Z axis.
+    pub fn perspective(fov_y: impl Into<Radians>, aspect: S, near: S, far: S) -> Self {
+        let Radians(fov_y) = fov_y.into();
+        let f = S::from_f64(1. / (f64::from(fov_y) / 2.).tan());
+        let depth_scale = far / (far - near);
+
+        Self::new(
+            f / aspect, S::ZERO, S::ZERO,     S::ZERO,
+            S::ZERO,    f,       S::ZERO,     S::ZERO,
+            S::ZERO,    S::ZERO, depth_scale, -near * depth_scale,
+            S::ZERO,    S::ZERO, S::ONE,      S::ZERO,
+        )
+    }
+
+    pub fn columns(&self) -> &[Vector<S>; 4] {
         &self.0
     }
 
-    pub fn columns_mut(&mut self) -> &mut [Vector; 4] {
+    pub fn columns_mut(&mut self) -> &mut [Vector<S>; 4] {
         &mut self.0
     }
 
-    pub fn as_rows(&self) -> [Vector; 4] {
+    /// The column of this matrix at index `i`.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `i` is not less than 4.
+    pub fn column(&self, i: usize) -> Vector<S> {
+        assert!(i < 4, "column index {} is out of range for a 4x4 matrix", i);
+
+        self.0[i]
+    }
+
+    /// The row of this matrix at index `i`.
+    ///
+    /// Only the requested row is gathered from the columns, so this is cheaper than indexing into
+    /// [`as_rows`](Self::as_rows) when a single row is needed.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `i` is not less than 4.
+    pub fn row(&self, i: usize) -> Vector<S> {
+        assert!(i < 4, "row index {} is out of range for a 4x4 matrix", i);
+
+        let [c0, c1, c2, c3] = &self.0;
+        Vector::new(c0.0[i], c1.0[i], c2.0[i], c3.0[i])
+    }
+
+    /// The transpose of this matrix, whose rows are the columns of this matrix.
+    pub fn transpose(&self) -> Self {
+        Self(self.as_rows())
+    }
+
+    pub fn as_rows(&self) -> [Vector<S>; 4] {
         let cols = self.to_array();
 
         [
@@ -73,12 +315,229 @@ impl Matrix {
         ]
     }
 
-    pub fn to_array(&self) -> [[Scalar; 4]; 4] {
+    /// Creates a new `Matrix` from an array of columns.
+    ///
+    /// This is the inverse of [`to_array`](Self::to_array); each inner array is a column, not a
+    /// row.
+    pub fn from_array(cols: [[S; 4]; 4]) -> Self {
+        Self(cols.map(|[r0, r1, r2, r3]| Vector::new(r0, r1, r2, r3)))
+    }
+
+    /// Creates a new `Matrix` from its columns, as when they are basis vectors.
+    ///
+    /// This is the inverse of [`columns`](Self::columns).
+    pub const fn from_columns(columns: [Vector<S>; 4]) -> Self {
+        Self(columns)
+    }
+
+    /// Creates a new `Matrix` from its rows.
+    ///
+    /// This is the inverse of [`as_rows`](Self::as_rows). As matrices are stored by column, the
+    /// rows are transposed.
+    pub fn from_rows(rows: [Vector<S>; 4]) -> Self {
+        Self(rows).transpose()
+    }
+
+    /// Creates a diagonal matrix, whose elements off the diagonal are zero.
+    ///
+    /// With a W element of 1, this is the matrix that scales by the X, Y, and Z elements of
+    /// `diagonal`.
+    pub const fn from_diagonal(diagonal: Vector<S>) -> Self {
+        let [r0, r1, r2, r3] = diagonal.to_array();
+
+        Self::new(
+                 r0, S::ZERO, S::ZERO, S::ZERO,
+            S::ZERO,      r1, S::ZERO, S::ZERO,
+            S::ZERO, S::ZERO,      r2, S::ZERO,
+            S::ZERO, S::ZERO, S::ZERO,      r3,
+        )
+    }
+
+    pub fn to_array(&self) -> [[S; 4]; 4] {
         self.0.map(|v| v.to_array())
     }
+
+    /// Converts the elements of this matrix to another [`Float`] type.
+    ///
+    /// This is typically used to convert an `f64` matrix to an `f32` one before uploading it to
+    /// the GPU.
+    pub fn cast<T: Float>(&self) -> Matrix<T> {
+        Matrix(self.0.map(|v| v.cast()))
+    }
+
+    /// Whether or not every element of this matrix is neither infinite nor NaN.
+    ///
+    /// Dividing by zero, as in a degenerate projection, or scaling by a non-finite factor produces
+    /// a matrix that fails this check and would silently corrupt everything it transforms.
+    pub fn is_finite(&self) -> bool {
+        self.0.iter().all(Vector::is_finite)
+    }
+
+    /// The inverse of this matrix, or `None` if this matrix is singular or nearly so.
+    ///
+    /// The inverse is computed by Gauss-Jordan elimination in `f64` regardless of `S`. Whether a
+    /// matrix is nearly singular is judged relative to the magnitude of its largest element, so
+    /// scaling a matrix, however much, doesn't change whether it has an inverse.
+    pub fn inverse(&self) -> Option<Self> {
+        // This is the augmented matrix `[self | identity]`, which is reduced to
+        // `[identity | inverse]`.
+        let mut rows = [[0_f64; 8]; 4];
+        for (i, row) in self.as_rows().iter().enumerate() {
+            for (j, e) in row.to_array().into_iter().enumerate() {
+                rows[i][j] = e.to_f64();
+            }
+            rows[i][4 + i] = 1.;
+        }
+        // Pivots smaller than this are taken to be rounding error.
+        let magnitude = rows
+            .iter()
+            .flat_map(|row| &row[..4])
+            .fold(0_f64, |max, e| max.max(e.abs()));
+        let min_pivot = magnitude * f64::EPSILON;
+
+        for col in 0..4 {
+            // Partial pivoting: the row with the largest magnitude in this column is the most
+            // numerically stable divisor.
+            let pivot = (col..4)
+                .max_by(|&a, &b| rows[a][col].abs().total_cmp(&rows[b][col].abs()))
+                .unwrap();
+            if rows[pivot][col].abs() <= min_pivot {
+                return None;
+            }
+            rows.swap(col, pivot);
+
+            let divisor = rows[col][col];
+            for e in rows[col].iter_mut() {
+                *e /= divisor;
+            }
+
+            let pivot_row = rows[col];
+            for (i, row) in rows.iter_mut().enumerate() {
+                if i == col {
+                    continue;
+                }
+
+                let factor = row[col];
+                for (e, pivot_e) in row.iter_mut().zip(pivot_row) {
+                    *e -= factor * pivot_e;
+                }
+            }
+        }
+
+        let e = |row: usize, col: usize| S::from_f64(rows[row][4 + col]);
+
+        Some(Self::new(
+            e(0, 0), e(0, 1), e(0, 2), e(0, 3),
+            e(1, 0), e(1, 1), e(1, 2), e(1, 3),
+            e(2, 0), e(2, 1), e(2, 2), e(2, 3),
+            e(3, 0), e(3, 1), e(3, 2), e(3, 3),
+        ))
+    }
+
+    /// This matrix with its upper-left 3x3 basis orthonormalized by the Gram-Schmidt process and
+    /// its translation kept.
+    ///
+    /// Accumulating many rotations into one matrix also accumulates rounding error, so the basis
+    /// of a long-lived transformation drifts from orthonormality and starts to shear and scale
+    /// what it transforms; orthonormalizing periodically undoes the drift. The X column keeps its
+    /// direction, the Y column is made perpendicular to it, and the Z column is made
+    /// perpendicular to both, and each is normalized.
+    ///
+    /// This assumes that the matrix is a rigid transformation, a rotation and a translation, and
+    /// so any scale is discarded, as are the bottom-row elements of the basis columns. A reflected
+    /// basis stays reflected. If the basis is degenerate, as when a column is zero, the result
+    /// contains NaN elements.
+    pub fn orthonormalize(&self) -> Self {
+        let [c0, c1, c2, translation] = self.0;
+        let basis = |column: Vector<S>| {
+            let [x, y, z, _] = column.to_array();
+            Vector::from_direction([x, y, z])
+        };
+        let (c0, c1, c2) = (basis(c0), basis(c1), basis(c2));
+
+        let x = c0.normalize();
+        let y = (c1 - (x * x.dot(c1))).normalize();
+        let z = (c2 - (x * x.dot(c2)) - (y * y.dot(c2))).normalize();
+
+        Self([x, y, z, translation])
+    }
+
+    /// Transforms the given point by this matrix, including the perspective divide.
+    ///
+    /// The point is extended with a fourth element of 1, and the X, Y, and Z elements of the
+    /// product are then divided by its fourth element, W. This is how a projection matrix maps a
+    /// point to clip space.
+    ///
+    /// If W is zero or nearly so, the point lies at infinity (e.g., on the camera plane of a
+    /// perspective projection), and the product is returned without the divide rather than with
+    /// infinite or NaN coordinates.
+    pub fn mul_point(&self, point: Point) -> Point {
+        let product = self.mul_vector(Vector::from(point).cast());
+        let [x, y, z, w] = product.to_array().map(|e| e.to_f64());
+        let divisor = if w.abs() <= f64::from(f32::EPSILON) { 1. } else { w };
+
+        Point {
+            x: (x / divisor) as f32,
+            y: (y / divisor) as f32,
+            z: (z / divisor) as f32,
+        }
+    }
+
+    /// Transforms the given direction by this matrix.
+    ///
+    /// The fourth element of `direction` is treated as 0, so the translation of this matrix has no
+    /// effect, and no perspective divide is performed.
+    pub fn mul_direction(&self, direction: Vector<S>) -> Vector<S> {
+        let [x, y, z, _] = direction.to_array();
+
+        self.mul_vector(Vector::from_direction([x, y, z]))
+    }
+
+    /// The product of this matrix and the given column vector.
+    fn mul_vector(&self, vector: Vector<S>) -> Vector<S> {
+        let [c0, c1, c2, c3] = self.0;
+        let [x, y, z, w] = vector.to_array();
+
+        (c0 * x) + (c1 * y) + (c2 * z) + (c3 * w)
+    }
+}
+
+/// Formats a single element, honoring the precision of `f` if any.
+fn format_element<S: Float>(f: &fmt::Formatter<'_>, element: S) -> String {
+    match f.precision() {
+        Some(precision) => format!("{:.*}", precision, element),
+        None => element.to_string(),
+    }
+}
+
+impl<S: Float> Display for Matrix<S> {
+    /// Formats this matrix as four lines, one per row, with the elements of each column aligned.
+    ///
+    /// The precision flag applies to every element, so `{:.3}` prints three decimal places.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows = self.as_rows().map(|row| row.to_array().map(|e| format_element(f, e)));
+        let width = rows.iter().flatten().map(String::len).max().unwrap_or(0);
+
+        for (i, row) in rows.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let [a, b, c, d] = row;
+            write!(f, "[{:>w$}, {:>w$}, {:>w$}, {:>w$}]", a, b, c, d, w = width)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<S: Float> From<[[S; 4]; 4]> for Matrix<S> {
+    /// Interprets the given array as columns, like [`Matrix::from_array`].
+    fn from(cols: [[S; 4]; 4]) -> Self {
+        Self::from_array(cols)
+    }
 }
 
-impl Add<Self> for Matrix {
+impl<S: Float> Add<Self> for Matrix<S> {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
@@ -94,24 +553,33 @@ impl Add<Self> for Matrix {
     }
 }
 
-impl Mul<Scalar> for Matrix {
+impl<S: Float> AddAssign for Matrix<S> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<S: Float> Div<S> for Matrix<S> {
     type Output = Self;
 
-    fn mul(self, rhs: Scalar) -> Self::Output {
-        Self(self.0.map(|vector| vector * rhs))
+    /// Divides each element by `rhs`.
+    ///
+    /// This multiplies by the reciprocal of `rhs`. As with IEEE 754 division, dividing by zero
+    /// produces infinite elements, or NaN for elements that are themselves zero.
+    fn div(self, rhs: S) -> Self::Output {
+        self * (S::ONE / rhs)
     }
 }
 
-impl Mul<Matrix> for Scalar {
-    type Output = Matrix;
+impl<S: Float> Mul<S> for Matrix<S> {
+    type Output = Self;
 
-    fn mul(self, rhs: Matrix) -> Self::Output {
-        // Multiplication with a matrix is commutative.
-        rhs * self
+    fn mul(self, rhs: S) -> Self::Output {
+        Self(self.0.map(|vector| vector * rhs))
     }
 }
 
-impl Mul<Self> for Matrix {
+impl<S: Float> Mul<Self> for Matrix<S> {
     type Output = Self;
 
     fn mul(self, rhs: Self) -> Self::Output {
@@ -140,69 +608,692 @@ impl Mul<Self> for Matrix {
     }
 }
 
-impl MulAssign for Matrix {
+impl Matrix {
+    /// Creates the matrix that scales by `scale`, then rotates by `rotation`, and then translates
+    /// by `translation`.
+    ///
+    /// This is the order in which a [`Transform`](crate::Transform) is applied, so the result is
+    /// the same as [`Transform::to_matrix`](crate::Transform::to_matrix) for the equivalent
+    /// transform. Only the X, Y, and Z elements of `scale` and `translation` are used. Rather than
+    /// multiplying scale, rotation, and translation matrices, this scales the columns of the
+    /// rotation matrix and sets the translation column directly.
+    pub fn from_scale_rotation_translation(
+        scale: Vector,
+        rotation: Rotation,
+        translation: Vector,
+    ) -> Self {
+        let [x, y, z, _] = scale.to_array();
+        let [c0, c1, c2, _] = *rotation.to_matrix().columns();
+        let [tx, ty, tz, _] = translation.to_array();
+
+        Self([c0 * x, c1 * y, c2 * z, Vector::new(tx, ty, tz, 1.)])
+    }
+
+    /// Creates the matrix that scales by `scale`, then rotates by the [`Quaternion`] `rotation`,
+    /// and then translates by `translation`.
+    ///
+    /// This is [`from_scale_rotation_translation`](Self::from_scale_rotation_translation) for
+    /// rotations that aren't Euler angles, as when they are interpolated or loaded from a scene
+    /// format. The columns of the rotation matrix are the X, Y, and Z axes rotated by `rotation`,
+    /// which must be normalized.
+    pub fn from_scale_quaternion_translation(
+        scale: Vector,
+        rotation: Quaternion,
+        translation: Vector,
+    ) -> Self {
+        let [x, y, z, _] = scale.to_array();
+        let [c0, c1, c2] = [[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]]
+            .map(|axis| rotation.rotate_vector(Vector::from_direction(axis)));
+        let [tx, ty, tz, _] = translation.to_array();
+
+        Self([c0 * x, c1 * y, c2 * z, Vector::new(tx, ty, tz, 1.)])
+    }
+
+    /// Transforms each of the given points in place, as with `self * point`.
+    ///
+    /// Like multiplication by a [`Point`], this assumes an affine matrix and performs no
+    /// perspective divide. Each point is computed as a sum of the four columns of this matrix,
+    /// scaled by the coordinates of the point, in SIMD registers; the columns are loaded once for
+    /// the whole slice rather than once per point. This is intended for baking transforms into
+    /// meshes on the CPU.
+    pub fn transform_points(&self, points: &mut [Point]) {
+        let [c0, c1, c2, c3] = self.0.map(|column| column.0);
+        for point in points {
+            let product = (c0 * Simd::splat(point.x))
+                + (c1 * Simd::splat(point.y))
+                + (c2 * Simd::splat(point.z))
+                + c3;
+            let [x, y, z, _] = product.to_array();
+            *point = Point { x, y, z };
+        }
+    }
+}
+
+/// Transforms a point as an affine transformation would, without the perspective divide.
+///
+/// The point is extended with a fourth element of 1, and the fourth element of the product is
+/// dropped. For affine matrices, like those of [`Transform`](crate::Transform)s, this agrees with
+/// [`mul_point`](Matrix::mul_point); for projection matrices, `mul_point` should be used instead,
+/// as it divides by the fourth element.
+impl Mul<Point> for Matrix {
+    type Output = Point;
+
+    fn mul(self, rhs: Point) -> Self::Output {
+        Point::from(self.mul_vector(Vector::from(rhs)))
+    }
+}
+
+impl<S: Float> MulAssign<S> for Matrix<S> {
+    fn mul_assign(&mut self, rhs: S) {
+        *self = *self * rhs;
+    }
+}
+
+impl<S: Float> MulAssign for Matrix<S> {
     fn mul_assign(&mut self, rhs: Self) {
         *self = *self * rhs;
     }
 }
 
-impl Vector {
-    pub const fn new(r0: Scalar, r1: Scalar, r2: Scalar, r3: Scalar) -> Self {
+impl<S: Float> Vector<S> {
+    pub const fn new(r0: S, r1: S, r2: S, r3: S) -> Self {
         Self(Simd::from_array([r0, r1, r2, r3]))
     }
 }
 
-/// A 4x1 column matrix of [`Scalar`](Scalar)s.
+/// A 4x1 column matrix of [`Float`]s, which are [`Scalar`]s by default.
 #[derive(Clone, Copy, Debug)]
-pub struct Vector(Simd<Scalar, 4>);
+pub struct Vector<S: Float = Scalar>(Simd<S, 4>);
+
+// `Simd` doesn't implement the *serde* traits, so we (de)serialize vectors as arrays instead.
+#[cfg(feature = "serde")]
+impl<S: Float + serde::Serialize> serde::Serialize for Vector<S> {
+    fn serialize<Z: serde::Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error> {
+        self.to_array().serialize(serializer)
+    }
+}
 
-impl Vector {
-    pub const ZERO: Self = Self::new(0., 0., 0., 0.);
+#[cfg(feature = "serde")]
+impl<'de, S: Float + serde::Deserialize<'de>> serde::Deserialize<'de> for Vector<S> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let [r0, r1, r2, r3] = <[S; 4]>::deserialize(deserializer)?;
 
-    pub fn sum(&self) -> Scalar {
-        self.0.reduce_sum()
+        Ok(Self::new(r0, r1, r2, r3))
+    }
+}
+
+impl<S: Float> Vector<S> {
+    pub const ZERO: Self = Self::new(S::ZERO, S::ZERO, S::ZERO, S::ZERO);
+
+    /// Creates a new `Vector` from a direction, such that the fourth element is 0.
+    ///
+    /// Unlike points, directions are unaffected by the translation of a transformation matrix.
+    pub const fn from_direction([r0, r1, r2]: [S; 3]) -> Self {
+        Self::new(r0, r1, r2, S::ZERO)
+    }
+
+    /// The first element of this vector.
+    pub fn x(&self) -> S {
+        self.0[0]
+    }
+
+    /// The second element of this vector.
+    pub fn y(&self) -> S {
+        self.0[1]
+    }
+
+    /// The third element of this vector.
+    pub fn z(&self) -> S {
+        self.0[2]
+    }
+
+    /// The fourth element of this vector.
+    pub fn w(&self) -> S {
+        self.0[3]
+    }
+
+    pub fn sum(&self) -> S {
+        S::reduce_sum(self.0)
+    }
+
+    /// The smallest element of this vector.
+    pub fn reduce_min(&self) -> S {
+        S::reduce_min(self.0)
+    }
+
+    /// The largest element of this vector.
+    pub fn reduce_max(&self) -> S {
+        S::reduce_max(self.0)
     }
 
-    pub const fn to_array(&self) -> [Scalar; 4] {
+    /// The dot product of this vector and `other`, across all four elements.
+    pub fn dot(&self, other: Self) -> S {
+        (*self * other).sum()
+    }
+
+    /// The cross product of the X, Y, and Z elements of this vector and `other`.
+    ///
+    /// The fourth element of the product is 0, as the product is a direction.
+    pub fn cross(&self, other: Self) -> Self {
+        let [ax, ay, az, _] = self.to_array();
+        let [bx, by, bz, _] = other.to_array();
+
+        Self::from_direction([
+            (ay * bz) - (az * by),
+            (az * bx) - (ax * bz),
+            (ax * by) - (ay * bx),
+        ])
+    }
+
+    /// The Euclidean length of this vector, across all four elements.
+    pub fn length(&self) -> S {
+        self.dot(*self).sqrt()
+    }
+
+    /// This vector scaled to a [length](Self::length) of 1.
+    ///
+    /// The zero vector has no direction, so normalizing it produces NaN elements.
+    pub fn normalize(&self) -> Self {
+        *self / self.length()
+    }
+
+    /// The element-wise minimum of this vector and `other`.
+    pub fn min(&self, other: Self) -> Self {
+        Self(S::lanes_min(self.0, other.0))
+    }
+
+    /// The element-wise maximum of this vector and `other`.
+    pub fn max(&self, other: Self) -> Self {
+        Self(S::lanes_max(self.0, other.0))
+    }
+
+    /// This vector with each element restricted to the range given by the corresponding elements
+    /// of `min` and `max`.
+    ///
+    /// It is the caller's responsibility to ensure that no element of `min` exceeds the
+    /// corresponding element of `max`. Where one does, that element of the result is taken from
+    /// `max`.
+    pub fn clamp(&self, min: Self, max: Self) -> Self {
+        self.max(min).min(max)
+    }
+
+    pub const fn to_array(&self) -> [S; 4] {
         self.0.to_array()
     }
+
+    /// Whether or not every element of this vector is neither infinite nor NaN.
+    pub fn is_finite(&self) -> bool {
+        S::lanes_are_finite(self.0)
+    }
+
+    /// Converts the elements of this vector to another [`Float`] type.
+    pub fn cast<T: Float>(&self) -> Vector<T> {
+        let [r0, r1, r2, r3] = self.to_array().map(|e| T::from_f64(e.to_f64()));
+
+        Vector::new(r0, r1, r2, r3)
+    }
+}
+
+impl<S: Float> Display for Vector<S> {
+    /// Formats this vector as `(x, y, z, w)`.
+    ///
+    /// The precision flag applies to every element, so `{:.3}` prints three decimal places.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [x, y, z, w] = self.to_array().map(|e| format_element(f, e));
+
+        write!(f, "({}, {}, {}, {})", x, y, z, w)
+    }
+}
+
+impl<S: Float> From<[S; 4]> for Vector<S> {
+    fn from([r0, r1, r2, r3]: [S; 4]) -> Self {
+        Self::new(r0, r1, r2, r3)
+    }
 }
 
-impl Add<Self> for Vector {
+impl<S: Float> From<[S; 3]> for Vector<S> {
+    /// Interprets the given array as a point, such that the fourth element is 1.
+    ///
+    /// Use [`Vector::from_direction`] for directions, which are unaffected by translation.
+    fn from([r0, r1, r2]: [S; 3]) -> Self {
+        Self::new(r0, r1, r2, S::ONE)
+    }
+}
+
+impl<S: Float> Add<Self> for Vector<S> {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
-        Self(self.0 + rhs.0)
+        Self(S::lanes_add(self.0, rhs.0))
+    }
+}
+
+impl<S: Float> Sub<Self> for Vector<S> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + (rhs * -S::ONE)
     }
 }
 
-impl AddAssign for Vector {
+impl<S: Float> AddAssign for Vector<S> {
     fn add_assign(&mut self, rhs: Self) {
         *self = *self + rhs;
     }
 }
 
-impl Mul<Scalar> for Vector {
+impl<S: Float> Div<S> for Vector<S> {
     type Output = Self;
 
-    fn mul(self, rhs: Scalar) -> Self::Output {
-        Self(self.0 * Simd::splat(rhs))
+    /// Divides each element by `rhs`.
+    ///
+    /// This multiplies by the reciprocal of `rhs`. As with IEEE 754 division, dividing by zero
+    /// produces infinite elements, or NaN for elements that are themselves zero.
+    fn div(self, rhs: S) -> Self::Output {
+        self * (S::ONE / rhs)
     }
 }
 
-impl Mul<Vector> for Scalar {
-    type Output = Vector;
+impl<S: Float> Mul<S> for Vector<S> {
+    type Output = Self;
 
-    fn mul(self, rhs: Vector) -> Self::Output {
-        // Multiplication with a scalar is commutative.
-        rhs * self
+    fn mul(self, rhs: S) -> Self::Output {
+        Self(S::lanes_mul(self.0, Simd::splat(rhs)))
     }
 }
 
-impl Mul<Self> for Vector {
+impl<S: Float> Mul<Self> for Vector<S> {
     type Output = Self;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        Self(self.0 * rhs.0)
+        Self(S::lanes_mul(self.0, rhs.0))
+    }
+}
+
+impl<S: Float> MulAssign<S> for Vector<S> {
+    fn mul_assign(&mut self, rhs: S) {
+        *self = *self * rhs;
+    }
+}
+
+impl<S: Float> MulAssign for Vector<S> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+/// Implements left-hand multiplication of matrices and vectors by a primitive [`Float`].
+///
+/// This can't be implemented generically as the left-hand side would be an uncovered type
+/// parameter.
+macro_rules! impl_scalar_mul {
+    ($ty:ty) => {
+        impl Mul<Matrix<$ty>> for $ty {
+            type Output = Matrix<$ty>;
+
+            fn mul(self, rhs: Matrix<$ty>) -> Self::Output {
+                // Multiplication with a matrix is commutative.
+                rhs * self
+            }
+        }
+
+        impl Mul<Vector<$ty>> for $ty {
+            type Output = Vector<$ty>;
+
+            fn mul(self, rhs: Vector<$ty>) -> Self::Output {
+                // Multiplication with a scalar is commutative.
+                rhs * self
+            }
+        }
+    };
+}
+
+impl_scalar_mul!(f32);
+impl_scalar_mul!(f64);
+
+/// A rotation about an axis through the origin, stored as a unit quaternion.
+///
+/// The quaternion `x*i + y*j + z*k + w` for a rotation by an angle `a` about a unit axis `n` has
+/// `(x, y, z) = n * sin(a / 2)` and `w = cos(a / 2)`. Rotations follow the right-hand rule.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quaternion {
+    pub x: Scalar,
+    pub y: Scalar,
+    pub z: Scalar,
+    pub w: Scalar,
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Quaternion {
+    /// The rotation that leaves everything as it is.
+    pub const IDENTITY: Self = Self { x: 0., y: 0., z: 0., w: 1. };
+
+    /// Creates the rotation by `angle` about `axis`.
+    ///
+    /// Only the X, Y, and Z elements of `axis` are used, and they need not be normalized. The
+    /// angle may be given in [`Radians`] or [`Degrees`].
+    pub fn from_axis_angle(axis: Vector, angle: impl Into<Radians>) -> Self {
+        let [x, y, z, _] = axis.to_array();
+        let axis = Vector::from_direction([x, y, z]).normalize();
+        let Radians(angle) = angle.into();
+        let (sin, cos) = (angle / 2.).sin_cos();
+        let [x, y, z, _] = (axis * sin).to_array();
+
+        Self { x, y, z, w: cos }
+    }
+
+    /// Rotates the X, Y, and Z elements of `v` by this quaternion, keeping its W element.
+    ///
+    /// This computes `q * v * q⁻¹` in the expanded form `v + w * t + cross(q, t)`, where `t` is
+    /// `2 * cross(q, v)` and `q` is the vector part of this quaternion, which is cheaper than
+    /// creating a rotation matrix to rotate a single vector. This quaternion must be normalized.
+    pub fn rotate_vector(&self, v: Vector) -> Vector {
+        let q = Vector::from_direction([self.x, self.y, self.z]);
+        let t = q.cross(v) * 2.;
+
+        // Cross products have a W element of 0, so only `v` contributes to W.
+        v + (t * self.w) + q.cross(t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reduces_vector_to_min_and_max() {
+        let v = Vector::new(3., -1., 7., 0.5);
+        assert_eq!(v.reduce_min(), -1.);
+        assert_eq!(v.reduce_max(), 7.);
+    }
+
+    #[test]
+    fn takes_element_wise_min_and_max() {
+        let a = Vector::new(1., 5., -2., 0.);
+        let b = Vector::new(3., 4., -3., 0.);
+        assert_eq!(a.min(b).to_array(), [1., 4., -3., 0.]);
+        assert_eq!(a.max(b).to_array(), [3., 5., -2., 0.]);
+    }
+
+    #[test]
+    fn clamps_each_element() {
+        let min = Vector::new(0., 0., 0., 0.);
+        let max = Vector::new(1., 1., 1., 1.);
+        let v = Vector::new(-0.5, 0.25, 2., 1.);
+        assert_eq!(v.clamp(min, max).to_array(), [0., 0.25, 1., 1.]);
+
+        // Where `min` exceeds `max`, `max` wins.
+        let v = Vector::new(0.5, 0.5, 0.5, 0.5);
+        assert_eq!(v.clamp(max, min).to_array(), [0.; 4]);
+    }
+
+    /// Asserts that each element of `a` is within a small tolerance of that of `b`.
+    fn assert_matrix_eq(a: Matrix, b: Matrix) {
+        let (a, b) = (a.to_array(), b.to_array());
+        for (a, b) in a.iter().flatten().zip(b.iter().flatten()) {
+            assert!((a - b).abs() < 1e-5, "{:?} != {:?}", a, b);
+        }
+    }
+
+    /// A matrix whose elements are all distinct, so that transpositions are caught.
+    fn distinct_matrix() -> Matrix {
+        Matrix::new(
+             1.,  2.,  3.,  4.,
+             5.,  6.,  7.,  8.,
+             9., 10., 11., 12.,
+            13., 14., 15., 16.,
+        )
+    }
+
+    #[test]
+    fn round_trips_through_array() {
+        let m = distinct_matrix();
+        assert_eq!(Matrix::from_array(m.to_array()).to_array(), m.to_array());
+        // The array is of columns.
+        assert_eq!(m.to_array()[0], [1., 5., 9., 13.]);
+    }
+
+    #[test]
+    fn add_assign_matches_add() {
+        let (a, b) = (distinct_matrix(), distinct_matrix().transpose());
+        let mut sum = a;
+        sum += b;
+        assert_eq!(sum.to_array(), (a + b).to_array());
+
+        let (u, v) = (Vector::new(1., 2., 3., 4.), Vector::new(-4., 0.5, 2., 0.));
+        let mut sum = u;
+        sum += v;
+        assert_eq!(sum.to_array(), (u + v).to_array());
+    }
+
+    #[test]
+    fn div_matches_mul_by_reciprocal() {
+        let m = distinct_matrix();
+        assert_eq!((m / 4.).to_array(), (m * 0.25).to_array());
+
+        let v = Vector::new(1., -2., 3., 8.);
+        assert_eq!((v / 2.).to_array(), (v * 0.5).to_array());
+        assert_eq!((v / 0.).x(), f32::INFINITY);
+    }
+
+    #[test]
+    fn converts_arrays_to_points_and_vectors() {
+        // Three elements are a point, which translation moves.
+        let point: Vector = [1., 2., 3.].into();
+        assert_eq!(point.to_array(), [1., 2., 3., 1.]);
+        // Four elements are taken as they are.
+        let direction: Vector = [1., 2., 3., 0.].into();
+        assert_eq!(direction.to_array(), Vector::from_direction([1., 2., 3.]).to_array());
+
+        let mut translation = Matrix::IDENTITY;
+        translation.columns_mut()[3] = Vector::new(10., 0., 0., 1.);
+        assert_eq!(translation.mul_vector(point).to_array(), [11., 2., 3., 1.]);
+        assert_eq!(translation.mul_vector(direction).to_array(), [1., 2., 3., 0.]);
+    }
+
+    #[test]
+    fn projects_points_into_clip_space() {
+        let projection = Matrix::perspective(Degrees(90.), 1., 0.1, 100.);
+        let near = projection.mul_point(Point { x: 0., y: 0., z: 0.1 });
+        let far = projection.mul_point(Point { x: 0., y: 0., z: 100. });
+        assert!(near.z.abs() < 1e-6 && (far.z - 1.).abs() < 1e-6);
+
+        // A 90-degree field of view spans one unit in X and Y for each unit of depth.
+        let corner = projection.mul_point(Point { x: 5., y: -5., z: 5. });
+        assert!((corner.x - 1.).abs() < 1e-6 && (corner.y + 1.).abs() < 1e-6);
+        let inside = projection.mul_point(Point { x: 1., y: 2., z: 4. });
+        for e in [inside.x, inside.y, inside.z] {
+            assert!((-1. ..=1.).contains(&e), "{} is outside clip space", e);
+        }
+    }
+
+    #[test]
+    fn inverts_small_scale_matrices() {
+        let scale: Matrix = Matrix::from_diagonal(Vector::new(1e-6, 1e-6, 1e-6, 1.));
+        let inverse = scale.inverse().expect("scale matrix should be invertible");
+        assert!((inverse.to_array()[0][0] - 1e6).abs() < 1.);
+
+        // Scaling a whole matrix, here exactly, doesn't make it singular, nor invertible.
+        let tiny = 2_f32.powi(-64);
+        let invertible = distinct_matrix().transpose() + Matrix::IDENTITY;
+        assert!((invertible * tiny).inverse().is_some());
+        assert!(distinct_matrix().inverse().is_none());
+        assert!((distinct_matrix() * tiny).inverse().is_none());
+        assert!(Matrix::<f32>::ZERO.inverse().is_none());
+    }
+
+    #[test]
+    fn displays_identity_as_aligned_rows() {
+        let identity: Matrix = Matrix::IDENTITY;
+        assert_eq!(
+            identity.to_string(),
+            "[1, 0, 0, 0]\n[0, 1, 0, 0]\n[0, 0, 1, 0]\n[0, 0, 0, 1]",
+        );
+        assert_eq!(
+            format!("{:.1}", identity).lines().next(),
+            Some("[1.0, 0.0, 0.0, 0.0]"),
+        );
+        assert_eq!(format!("{:.2}", Vector::new(1., -0.5, 0., 1.)), "(1.00, -0.50, 0.00, 1.00)");
+    }
+
+    #[test]
+    fn indexes_rows_and_columns() {
+        let m = distinct_matrix();
+        for i in 0..4 {
+            assert_eq!(m.row(i).to_array(), m.as_rows()[i].to_array());
+            assert_eq!(m.column(i).to_array(), m.to_array()[i]);
+        }
+        assert_eq!(m.row(0).to_array(), [1., 2., 3., 4.]);
+        assert_eq!(m.column(3).to_array(), [4., 8., 12., 16.]);
+    }
+
+    #[test]
+    #[should_panic(expected = "row index 4 is out of range")]
+    fn panics_on_row_index_out_of_range() {
+        distinct_matrix().row(4);
+    }
+
+    #[test]
+    #[should_panic(expected = "column index 4 is out of range")]
+    fn panics_on_column_index_out_of_range() {
+        distinct_matrix().column(4);
+    }
+
+    #[test]
+    fn translates_origin_to_translation() {
+        let translation = Matrix::new(
+            1., 0., 0., 1.,
+            0., 1., 0., 2.,
+            0., 0., 1., 3.,
+            0., 0., 0., 1.,
+        );
+        let expected = Point { x: 1., y: 2., z: 3. };
+        assert_eq!(translation * Point::ORIGIN, expected);
+        assert_eq!(translation.mul_point(Point::ORIGIN), expected);
+
+        // Unlike `mul_point`, the product doesn't divide by the fourth element.
+        let mut projection = translation;
+        projection.columns_mut()[3] = Vector::new(1., 2., 3., 2.);
+        assert_eq!(projection * Point::ORIGIN, expected);
+        assert_eq!(projection.mul_point(Point::ORIGIN), Point { x: 0.5, y: 1., z: 1.5 });
+    }
+
+    #[test]
+    fn round_trips_through_columns_and_rows() {
+        let m = distinct_matrix();
+        assert_eq!(Matrix::from_columns(*m.columns()).to_array(), m.to_array());
+        assert_eq!(Matrix::from_rows(m.as_rows()).to_array(), m.to_array());
+    }
+
+    #[test]
+    fn scale_rotation_translation_matches_node() {
+        use crate::tree::Node;
+
+        let position = Point { x: 1., y: 2., z: 3. };
+        let angle = std::f32::consts::FRAC_PI_2;
+        let parent = Node::root(position, Rotation::new(0., 0., angle), 2.);
+        let child = Node::new(&parent, position, Rotation::default(), 1.);
+
+        let scale = Vector::new(2., 2., 2., 1.);
+        let translation = Vector::from(position);
+        let euler = Matrix::from_scale_rotation_translation(
+            scale,
+            Rotation::new(0., 0., angle),
+            translation,
+        );
+        let quaternion = Matrix::from_scale_quaternion_translation(
+            scale,
+            Quaternion::from_axis_angle(Vector::from_direction([0., 0., 1.]), Radians(angle)),
+            translation,
+        );
+        let unit_translation = Matrix::from_scale_quaternion_translation(
+            Vector::new(1., 1., 1., 1.),
+            Quaternion::IDENTITY,
+            translation,
+        );
+
+        let node = parent.local_transformation_matrix();
+        assert_eq!(euler.to_array(), node.to_array());
+        assert_matrix_eq(quaternion, node);
+        assert_matrix_eq(quaternion * unit_translation, child.global_transformation_matrix());
+    }
+
+    #[test]
+    fn rotates_x_axis_to_y_axis_about_z() {
+        let z = Vector::from_direction([0., 0., 1.]);
+        let rotation = Quaternion::from_axis_angle(z, Degrees(90.));
+
+        let rotated = rotation.rotate_vector(Vector::from_direction([1., 0., 0.]));
+        let error = rotated - Vector::from_direction([0., 1., 0.]);
+        assert!(error.length() < 1e-6, "{} is not the Y axis", rotated);
+    }
+
+    #[test]
+    fn orthonormalizes_drifted_rotation() {
+        let mut rotation = Rotation::new(0.3, 0.5, 0.7).to_matrix();
+        rotation.columns_mut()[3] = Vector::new(1., 2., 3., 1.);
+        // Simulate drift by stretching the X column and shearing the Y column toward it.
+        let mut drifted = rotation;
+        let [c0, c1, ..] = *drifted.columns();
+        drifted.columns_mut()[0] = c0 * 1.01;
+        drifted.columns_mut()[1] = c1 + (c0 * 0.01);
+
+        let [x, y, z, translation] = *drifted.orthonormalize().columns();
+        for (a, b) in [(x, y), (y, z), (z, x)] {
+            assert!(a.dot(b).abs() < 1e-6, "{} and {} are not perpendicular", a, b);
+        }
+        for column in [x, y, z] {
+            assert!((column.length() - 1.).abs() < 1e-6, "{} is not normalized", column);
+        }
+        // The X column keeps its direction, and the rest stay close to the original rotation.
+        assert!((x - c0).length() < 1e-6);
+        assert!((y - c1).length() < 1e-2 && (z - rotation.column(2)).length() < 1e-2);
+        assert_eq!(translation.to_array(), [1., 2., 3., 1.]);
+    }
+
+    #[test]
+    fn composes_matrix64_without_f32_rounding() {
+        let translation = |x: f64| {
+            Matrix64::new(
+                1., 0., 0.,  x,
+                0., 1., 0., 0.,
+                0., 0., 1., 0.,
+                0., 0., 0., 1.,
+            )
+        };
+
+        // 1e9 + 1 is exact in `f64` but rounds to 1e9 in `f32`.
+        let m = translation(1e9) * translation(1.);
+        let expected = Vector64::new(1e9 + 1., 0., 0., 1.);
+        assert_eq!(m.column(3).to_array(), expected.to_array());
+        assert_eq!(m.cast::<f32>().column(3).x(), 1e9);
+        assert_eq!(m.mul_direction(Vector64::from_direction([2., 0., 0.])).x(), 2.);
+    }
+
+    #[test]
+    fn transforms_points_as_multiplication_does() {
+        let m = Matrix::from_scale_rotation_translation(
+            Vector::from_direction([2., 0.5, -1.]),
+            Rotation::new(0.3, -1.2, 2.),
+            Vector::from_direction([4., -5., 6.]),
+        );
+        let original = [
+            Point::ORIGIN,
+            Point { x: 1., y: 0., z: 0. },
+            Point { x: -3.5, y: 2.25, z: 1e3 },
+            Point { x: 0.1, y: -0.2, z: 0.3 },
+        ];
+
+        let mut points = original;
+        m.transform_points(&mut points);
+        for (point, original) in points.iter().zip(original) {
+            assert_eq!(*point, m * original);
+        }
     }
 }
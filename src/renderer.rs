@@ -2,25 +2,160 @@
 
 //! Pylon's 3D renderer.
 
+use std::{fmt, ops::Range, sync::OnceLock};
+
 use raw_window_handle::HasRawWindowHandle;
-use wgpu::*;
+use wgpu::{util::DeviceExt as _, *};
 
 use crate::{
     CameraTransformsUniform,
+    Color,
+    Matrix,
     MeshVertex,
+    Object,
+    ObjectTransforms,
     ObjectTransformsUniform,
     TransformsUniform,
 };
-pub use render::Job;
+pub use batch::{BatchedObject, MeshBatch, MeshRange};
+pub use builder::RendererBuilder;
+pub use debug::DebugOverlay;
+pub use dynamic_mesh::DynamicMesh;
+pub use instancing::{InstanceBuffer, ObjectInstance, INSTANCE_BUFFER_LAYOUT};
+pub use render::{ColorLoad, DepthLoad, Job, Pass, RenderStats, StoreOps};
+pub use sampler::{SamplerError, SamplerOptions};
+#[cfg(feature = "image")]
+pub use screenshot::ScreenshotError;
 
+mod batch;
+mod builder;
+pub mod debug;
+mod depth;
+mod dynamic_mesh;
+mod headless;
+mod immediate;
+mod instancing;
+mod pipeline_cache;
+mod prepass;
 mod render;
+mod sampler;
+#[cfg(feature = "image")]
+mod screenshot;
+mod skybox;
+mod timing;
+mod validation;
+mod viewport;
 
-/// The hardcoded texture format for [`Renderer::surface`] and which serves as the output of the
-/// fragment shader.
+/// The default format of the surface frame, which is the output of the fragment shader.
+///
+/// Colors written to this format are assumed to be linear and are sRGB-encoded on write.
 const SURFACE_FORMAT: TextureFormat = TextureFormat::Bgra8UnormSrgb;
 
+/// The format of the surface frame when sRGB encoding is [disabled](RendererBuilder::srgb).
+///
+/// Colors written to this format are stored as-is.
+const LINEAR_SURFACE_FORMAT: TextureFormat = TextureFormat::Bgra8Unorm;
+
+/// WGSL functions for tonemapping and gamma encoding, which may be prepended to fragment shader
+/// source.
+///
+/// Fragment shaders that compute high-dynamic-range colors must map them into `[0, 1]` before
+/// output; `tonemap_reinhard` and `tonemap_aces` do so. For a
+/// [linear surface](RendererBuilder::srgb), `linear_to_srgb` applies the sRGB transfer function
+/// that the default surface format would otherwise apply in hardware, and `srgb_to_linear` decodes
+/// colors that are already sRGB-encoded. All functions operate on `vec3<f32>` RGB colors.
+///
+/// ```ignore
+/// let source = format!("{}\n{}", pylon_engine::renderer::TONEMAPPING_WGSL, fragment_source);
+/// ```
+pub const TONEMAPPING_WGSL: &str = include_str!("shaders/tonemapping.wgsl");
+
+/// The format of [shadow maps](Renderer::render_depth).
 const DEPTH_FORMAT: TextureFormat = TextureFormat::Depth24Plus;
 
+/// The format of a renderer's depth texture, as chosen with [`RendererBuilder::depth_format`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DepthFormat {
+    /// At least 24 bits of depth, in a representation chosen by the backend.
+    ///
+    /// This is supported everywhere and is usually the cheapest choice in memory and bandwidth,
+    /// but its precision varies between adapters: some store 24-bit normalized integers, whose
+    /// precision is spread evenly over the depth range, and so distant surfaces may z-fight. Its
+    /// stencil variant, [`TextureFormat::Depth24PlusStencil8`], is also supported everywhere.
+    #[default]
+    Depth24Plus,
+    /// 32-bit floating-point depth.
+    ///
+    /// This is also supported everywhere and has the same precision on every adapter, which is
+    /// most useful for effects that reconstruct positions from depth or that draw distant
+    /// geometry. It may cost more memory than [`Depth24Plus`](Self::Depth24Plus). Its stencil
+    /// variant, [`TextureFormat::Depth32FloatStencil8`], requires
+    /// [`Features::DEPTH32FLOAT_STENCIL8`], which Pylon requests when a stencil buffer is
+    /// [requested](RendererBuilder::stencil) too.
+    Depth32Float,
+}
+
+impl DepthFormat {
+    /// The format of a depth texture of this format, including an 8-bit stencil aspect if
+    /// `stencil` is `true`.
+    pub fn texture_format(self, stencil: bool) -> TextureFormat {
+        match (self, stencil) {
+            (Self::Depth24Plus, false) => TextureFormat::Depth24Plus,
+            (Self::Depth24Plus, true) => TextureFormat::Depth24PlusStencil8,
+            (Self::Depth32Float, false) => TextureFormat::Depth32Float,
+            (Self::Depth32Float, true) => TextureFormat::Depth32FloatStencil8,
+        }
+    }
+}
+
+/// The format of the indices within [object index buffers](crate::Object::index_buffer).
+///
+/// This must agree with [`MeshVertexIndex`](crate::MeshVertexIndex).
+const INDEX_FORMAT: IndexFormat = IndexFormat::Uint32;
+
+/// The vertex attributes of [`MeshVertex`], in order of field.
+const MESH_VERTEX_ATTRIBUTES: [VertexAttribute; 4] = vertex_attr_array![
+    // The point.
+    0 => Float32x3,
+    // The normal.
+    1 => Float32x3,
+    // The color.
+    2 => Float32x4,
+    // The texture coordinates.
+    3 => Float32x2,
+];
+
+/// The layout of the vertex buffers of [objects](crate::Object), which contain [`MeshVertex`]s.
+///
+/// Custom vertex shaders, as given to
+/// [`create_pipeline_with_vertex_shader`](Renderer::create_pipeline_with_vertex_shader), receive
+/// the point of each vertex at `@location(0)` and the normal at `@location(1)`, both as
+/// `vec3<f32>`, the color at `@location(2)` as a `vec4<f32>`, and the texture coordinates at
+/// `@location(3)` as a `vec2<f32>`. Shaders need not declare every attribute, but the stride is
+/// always 48 bytes, so buffers of bare points and normals, 24 bytes per vertex, are incompatible.
+/// Pipelines created outside of Pylon should use this layout for buffer 0.
+pub const VERTEX_BUFFER_LAYOUT: VertexBufferLayout<'static> = VertexBufferLayout {
+    array_stride: std::mem::size_of::<MeshVertex>() as BufferAddress,
+    step_mode: VertexStepMode::Vertex,
+    attributes: &MESH_VERTEX_ATTRIBUTES,
+};
+
+/// The shader modules and targets of a pipeline created by
+/// [`Renderer::create_pipeline_with_settings`].
+#[derive(Clone, Copy)]
+struct PipelineSettings<'a> {
+    vertex_shader: &'a ShaderModule,
+    /// The layouts of the vertex buffers read by the vertex shader.
+    vertex_buffers: &'a [VertexBufferLayout<'a>],
+    /// The fragment shader, or `None` for a pipeline that writes only depth.
+    fragment_shader: Option<&'a ShaderModule>,
+    /// The formats of the color targets, one per output of the fragment shader.
+    color_formats: &'a [TextureFormat],
+    options: &'a PipelineOptions,
+    /// The sample count and format of the depth texture, if not those of the renderer.
+    depth_target: Option<(u32, TextureFormat)>,
+}
+
 /// The cause of a failure during [`Renderer` creation](Renderer::new).
 #[derive(Debug)]
 pub enum Error {
@@ -29,18 +164,128 @@ pub enum Error {
     /// This could be for a few reasons:
     /// 1. instance creation failed due to unavailable backends;
     /// 2. the rendering surface produced from the given window was invalid;
-    /// 3. the given power preference did not match any available graphics adapters; or
+    /// 3. the given power preference did not match any available graphics adapters, and a
+    ///    [software fallback](RendererBuilder::allow_software_fallback) was either disallowed or
+    ///    also unavailable; or
     /// 4. *wgpu*, your OS, or your graphics drivers failed.
     NoCompatibleAdapterFound,
     /// A handle to a graphics device was requested but none was returned.
     ///
     /// This error is likely rare and may represent a problem outside the control of Pylon.
     NoCompatibleDeviceFound,
+    /// The adapter cannot render to a depth texture of the
+    /// [requested format](RendererBuilder::depth_format).
+    UnsupportedDepthFormat {
+        /// The texture format of the requested depth texture.
+        format: TextureFormat,
+    },
+    /// The window surface does not support the surface format
+    /// [requested](RendererBuilder::srgb).
+    UnsupportedSurfaceFormat {
+        /// The requested surface format.
+        requested: TextureFormat,
+        /// The formats that the surface does support, in order of preference.
+        supported: Vec<TextureFormat>,
+    },
+}
+
+/// The cause of a failure while [drawing an object](render::Pass::draw_object).
+#[derive(Debug)]
+pub enum RenderError {
+    /// An object assigned to one of the bind group slots reserved by Pylon.
+    ///
+    /// Slot 0 holds the camera transformation matrix and slot 1 holds the object transformation
+    /// matrix.
+    ReservedBindGroupSlot {
+        /// The index of the offending slot.
+        index: u32,
+    },
+    /// An object assigned to a bind group slot beyond those supported by the device.
+    BindGroupSlotOutOfRange {
+        /// The index of the offending slot.
+        index: u32,
+        /// The number of bind group slots supported by the device.
+        max_bind_groups: u32,
+    },
+    /// An object referred to a mesh beyond those in the batch it was drawn from.
+    MeshIndexOutOfRange {
+        /// The offending mesh index.
+        index: usize,
+        /// The number of meshes in the batch.
+        mesh_count: usize,
+    },
+    /// A viewport was empty or extended beyond the surface.
+    ViewportOutOfBounds {
+        /// The offending viewport.
+        viewport: Rect,
+        /// The size of the surface.
+        surface_size: SurfaceSize,
+    },
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ReservedBindGroupSlot { index } => {
+                write!(
+                    f,
+                    "bind group slot {} is reserved by Pylon; slots 0 and 1 cannot be overwritten",
+                    index,
+                )
+            }
+            Self::BindGroupSlotOutOfRange { index, max_bind_groups } => {
+                write!(
+                    f,
+                    "bind group slot {} is out of range; the device supports only {} slots",
+                    index,
+                    max_bind_groups,
+                )
+            }
+            Self::MeshIndexOutOfRange { index, mesh_count } => {
+                write!(
+                    f,
+                    "mesh index {} is out of range; the batch contains only {} meshes",
+                    index,
+                    mesh_count,
+                )
+            }
+            Self::ViewportOutOfBounds { viewport, surface_size } => {
+                write!(
+                    f,
+                    "viewport of {}x{} pixels at ({}, {}) is empty or exceeds the {}x{} surface",
+                    viewport.width,
+                    viewport.height,
+                    viewport.x,
+                    viewport.y,
+                    surface_size.width,
+                    surface_size.height,
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+/// The cause of a failure during [pipeline creation](Renderer::create_pipeline).
+#[derive(Debug)]
+pub struct PipelineError {
+    /// The diagnostic produced by *wgpu*, which includes any WGSL compilation errors.
+    pub message: String,
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pipeline creation failed: {}", self.message)
+    }
 }
 
+impl std::error::Error for PipelineError {}
+
 /// The physical dimensions of a rendering surface.
 ///
 /// [`Renderer::configure_surface`] consumes an argument of this type.
+#[derive(Clone, Copy, Debug)]
 pub struct SurfaceSize {
     /// The width, in pixels, of the surface.
     pub width: u32,
@@ -48,6 +293,148 @@ pub struct SurfaceSize {
     pub height: u32,
 }
 
+/// A rectangular region of a rendering surface, in pixels.
+///
+/// The origin is the top-left corner of the surface.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rect {
+    /// The distance, in pixels, from the left edge of the surface to the left edge of the region.
+    pub x: u32,
+    /// The distance, in pixels, from the top edge of the surface to the top edge of the region.
+    pub y: u32,
+    /// The width, in pixels, of the region.
+    pub width: u32,
+    /// The height, in pixels, of the region.
+    pub height: u32,
+}
+
+impl Rect {
+    /// Whether or not this region is nonempty and lies entirely within a surface of the given
+    /// size.
+    pub fn fits_within(&self, surface_size: SurfaceSize) -> bool {
+        self.width > 0
+            && self.height > 0
+            && u64::from(self.x) + u64::from(self.width) <= u64::from(surface_size.width)
+            && u64::from(self.y) + u64::from(self.height) <= u64::from(surface_size.height)
+    }
+}
+
+/// The presentation modes and texture formats supported by a rendering surface.
+///
+/// This is produced by [`Renderer::surface_capabilities`].
+#[derive(Clone, Debug)]
+pub struct SurfaceCapabilities {
+    /// The supported texture formats.
+    pub formats: Vec<TextureFormat>,
+    /// The supported presentation modes.
+    pub present_modes: Vec<PresentMode>,
+}
+
+/// Options for [pipeline creation](Renderer::create_pipeline_with_options).
+///
+/// The [default](Self::default) options produce an opaque pipeline.
+#[derive(Clone, Debug)]
+pub struct PipelineOptions {
+    /// The blending to apply when the fragment shader writes to the surface.
+    ///
+    /// `None` replaces the existing color outright.
+    pub blend: Option<BlendState>,
+    /// Whether or not fragments write their depth to the depth texture.
+    pub depth_write_enabled: bool,
+    /// The comparison between a fragment's depth and the depth texture that decides whether the
+    /// fragment is kept.
+    ///
+    /// The default, [`Less`](CompareFunction::Less), keeps fragments nearer than those already
+    /// rendered. Backgrounds such as skyboxes typically want
+    /// [`LessEqual`](CompareFunction::LessEqual) without depth writes, and overlays want
+    /// [`Always`](CompareFunction::Always).
+    pub depth_compare: CompareFunction,
+    /// The primitive topology of the index buffers drawn with this pipeline.
+    ///
+    /// As [`Job`] draws `3 * triangle_count` indices per object, index buffers for topologies other
+    /// than [`TriangleList`](PrimitiveTopology::TriangleList) must be sized accordingly.
+    /// [`PointList`](PrimitiveTopology::PointList) pipelines are instead drawn without indices by
+    /// [`Pass::draw_points`].
+    pub topology: PrimitiveTopology,
+    /// How polygons are rasterized.
+    ///
+    /// Modes other than [`Fill`](PolygonMode::Fill) require a device feature, which may be
+    /// requested with [`RendererBuilder::features`].
+    pub polygon_mode: PolygonMode,
+    /// The stencil test and the operations applied to the stencil buffer.
+    ///
+    /// The default disables stencil testing. Any other state requires a renderer created with a
+    /// [stencil buffer](RendererBuilder::stencil). The reference value that the test compares
+    /// against is set per pass with [`Pass::set_stencil_reference`].
+    pub stencil: StencilState,
+    /// The bias added to the depth of each fragment before the depth test and write.
+    ///
+    /// The bias is `constant` units of the depth format's precision plus `slope_scale` times the
+    /// depth slope of the polygon, limited in magnitude to `clamp` unless `clamp` is zero. The
+    /// default is no bias.
+    ///
+    /// Shadow-map passes typically use a small positive bias, such as a `constant` of 2 and a
+    /// `slope_scale` of 2.0, to avoid shadow acne; steeper surfaces need more. Decals drawn on
+    /// coplanar geometry instead use a negative `constant`, such as -1 or -2, so that they win the
+    /// [`LessEqual`](CompareFunction::LessEqual) depth test against the surface beneath them
+    /// without z-fighting.
+    pub depth_bias: DepthBiasState,
+}
+
+impl Default for PipelineOptions {
+    fn default() -> Self {
+        Self {
+            blend: None,
+            depth_write_enabled: true,
+            depth_compare: CompareFunction::Less,
+            topology: PrimitiveTopology::TriangleList,
+            polygon_mode: PolygonMode::Fill,
+            stencil: StencilState::default(),
+            depth_bias: DepthBiasState::default(),
+        }
+    }
+}
+
+impl PipelineOptions {
+    /// Options for a pipeline that renders translucent objects.
+    ///
+    /// This enables standard alpha blending and disables depth writes so that translucent objects
+    /// don't occlude what lies behind them.
+    ///
+    /// Pylon does not sort objects, so blending is only correct when translucent objects are
+    /// rendered after all opaque objects and in back-to-front order (the painter's algorithm).
+    pub fn transparent() -> Self {
+        Self {
+            blend: Some(BlendState::ALPHA_BLENDING),
+            depth_write_enabled: false,
+            ..Default::default()
+        }
+    }
+
+    /// Options for a pipeline whose objects are drawn after a
+    /// [depth pre-pass](Renderer::render_with_depth_prepass).
+    ///
+    /// As the pre-pass has already written the depth of the nearest surface at each pixel, this
+    /// passes the depth test only for fragments at exactly that depth and doesn't write depth.
+    pub fn after_depth_prepass() -> Self {
+        Self {
+            depth_write_enabled: false,
+            depth_compare: CompareFunction::LessEqual,
+            ..Default::default()
+        }
+    }
+
+    /// Options for a pipeline that renders each vertex as a point, as for point clouds.
+    ///
+    /// Objects using such a pipeline must be drawn with [`Pass::draw_points`].
+    pub fn points() -> Self {
+        Self {
+            topology: PrimitiveTopology::PointList,
+            ..Default::default()
+        }
+    }
+}
+
 /// Layouts of Pylon's built-in bind groups.
 ///
 /// A [renderer](Renderer) creates this once and references it during pipeline creation.
@@ -66,21 +453,26 @@ impl BuiltinBindGroupLayouts {
             for_camera: Self::create_layout(
                 device,
                 "Pylon camera transformation matrix bind group layout",
+                false,
             ),
+            // Many objects may share one uniform buffer, each at its own dynamic offset.
             for_object: Self::create_layout(
                 device,
                 "Pylon object transformation matrix bind group layout",
+                true,
             ),
         }
     }
 
     /// Creates the layout of a built-in bind group.
     ///
-    /// As it happens that Pylon's built-in bind groups are identical in all but name, the `label`
-    /// field governs which layout this function produces.
+    /// As it happens that Pylon's built-in bind groups are identical in all but name and whether or
+    /// not they use dynamic offsets, the `label` and `has_dynamic_offset` fields govern which
+    /// layout this function produces.
     fn create_layout(
         device: &Device,
         label: &str,
+        has_dynamic_offset: bool,
     ) -> BindGroupLayout {
         device.create_bind_group_layout(&BindGroupLayoutDescriptor {
             label: Some(label),
@@ -92,7 +484,7 @@ impl BuiltinBindGroupLayouts {
                 visibility: ShaderStages::VERTEX,
                 ty: BindingType::Buffer {
                     ty: BufferBindingType::Uniform,
-                    has_dynamic_offset: false,
+                    has_dynamic_offset,
                     min_binding_size: None,
                 },
                 count: None,
@@ -101,11 +493,37 @@ impl BuiltinBindGroupLayouts {
     }
 }
 
+/// What a [`Renderer`] renders to.
+#[derive(Debug)]
+enum RenderTarget {
+    /// A window surface, whose frames are presented.
+    Surface(Surface),
+    /// A texture owned by the renderer, for headless rendering.
+    Texture(Texture),
+}
+
 /// Pylon's 3D renderer.
 ///
 /// From a data perspective, this type is the combination of a surface&mdash;upon which rendering
 /// takes place&mdash;and a handle to a GPU. In terms of functionality, a `Renderer` is created with
 /// [`new`](Self::new), and [`render`](Self::render) renders a scene to the aforementioned surface.
+///
+/// # Color Encoding
+///
+/// By default, the surface format is sRGB-encoded: fragment shaders are expected to output linear
+/// colors, which the GPU gamma-encodes as they are written. Shaders that output colors that are
+/// already sRGB-encoded, as sampled verbatim from an image, are encoded twice and appear washed
+/// out. Such shaders should either decode their colors to linear or run on a renderer with a
+/// [linear surface](RendererBuilder::srgb), which stores colors as-is.
+/// [`TONEMAPPING_WGSL`] provides helpers for either case.
+///
+/// # Device Loss
+///
+/// Pylon cannot notify callers when the GPU device is lost, as on a driver reset, because *wgpu*
+/// 0.13 has no device-lost callback to forward. Until *wgpu* is upgraded, errors caused by a lost
+/// device reach the handler registered with [`Device::on_uncaptured_error`] on
+/// [the device](Self::device), and a lost surface makes [`create_render`](Self::create_render)
+/// panic.
 #[derive(Debug)]
 pub struct Renderer {
     /// Layouts of Pylon's built-in bind groups.
@@ -114,14 +532,88 @@ pub struct Renderer {
     /// afterwards.
     builtin_bind_group_layouts: BuiltinBindGroupLayouts,
     depth: Texture,
+    /// The view of [`depth`](Self::depth) that every [job](Job) renders to.
+    ///
+    /// This is recreated along with the depth texture rather than once per job, which saves the
+    /// view's heap allocations on every frame.
+    depth_view: TextureView,
+    /// The format of [`depth`](Self::depth), as [chosen](RendererBuilder::depth_format), which
+    /// includes a stencil aspect if a stencil buffer was [requested](RendererBuilder::stencil).
+    depth_format: TextureFormat,
+    /// The multisampled color texture that is resolved to the surface frame.
+    ///
+    /// This is `None` if [`sample_count`](Self::sample_count) is 1.
+    multisampled_frame: Option<Texture>,
+    /// The number of samples per pixel.
+    sample_count: u32,
+    /// The format of the surface frame, which is either [`SURFACE_FORMAT`] or
+    /// [`LINEAR_SURFACE_FORMAT`].
+    surface_format: TextureFormat,
+    /// The pipeline used by [`render_depth`](Self::render_depth).
+    ///
+    /// This is created upon first use.
+    depth_only_pipeline: OnceLock<RenderPipeline>,
+    /// The pipeline used by [`render_with_depth_prepass`](Self::render_with_depth_prepass) for the
+    /// pre-pass.
+    ///
+    /// This is created upon first use.
+    depth_prepass_pipeline: OnceLock<RenderPipeline>,
+    /// The pipeline used by [`render_debug`](Self::render_debug) to draw wireframes, or `None` if
+    /// the device lacks [`Features::POLYGON_MODE_LINE`].
+    ///
+    /// This is created upon first use.
+    wireframe_pipeline: OnceLock<Option<RenderPipeline>>,
+    /// The pipeline used by [`render_debug`](Self::render_debug) to draw vertex normals.
+    ///
+    /// This is created upon first use.
+    normals_pipeline: OnceLock<RenderPipeline>,
+    /// The pipeline used by [`draw_lines`](Self::draw_lines).
+    ///
+    /// This is created upon first use.
+    lines_pipeline: OnceLock<RenderPipeline>,
+    /// The pipeline, bind group layout, and sampler used by
+    /// [`render_skybox`](Self::render_skybox).
+    ///
+    /// These are created upon first use.
+    skybox_resources: OnceLock<skybox::SkyboxResources>,
+    /// The pipelines created by [`create_pipeline_cached`](Self::create_pipeline_cached).
+    pipeline_cache: pipeline_cache::PipelineCache,
+    /// The resources for GPU frame timing.
+    ///
+    /// This is `None` unless the device was created with [`Features::TIMESTAMP_QUERY`].
+    timestamps: Option<timing::Timestamps>,
+    /// Information about the graphics adapter that the device was created from.
+    adapter_info: AdapterInfo,
+    /// Whether or not the adapter is a
+    /// [software fallback](RendererBuilder::allow_software_fallback).
+    is_fallback_adapter: bool,
+    /// Whether or not *wgpu* validation errors are
+    /// [logged](RendererBuilder::log_validation_errors).
+    log_validation_errors: bool,
+    /// The color that each [job](Job) clears the surface frame to, if any.
+    clear_color: Option<Color>,
+    /// The size of the surface as of the last call to
+    /// [`configure_surface`](Self::configure_surface).
+    surface_size: SurfaceSize,
+    /// The presentation mode of the surface as of the last call to
+    /// [`configure_surface`](Self::configure_surface).
+    present_mode: PresentMode,
+    /// The presentation modes supported by the surface, which is empty if this renderer is
+    /// [headless](Self::new_headless).
+    supported_present_modes: Vec<PresentMode>,
     device: Device,
     queue: Queue,
-    surface: Surface,
+    /// The surface or, if this renderer is [headless](Self::new_headless), the texture that is
+    /// rendered to.
+    target: RenderTarget,
 }
 
 impl Renderer {
     /// Creates a new `Renderer`.
     ///
+    /// This is a shorthand for [`Renderer::builder`] for the most common options; the builder
+    /// should be preferred for anything more involved.
+    ///
     /// # Safety
     ///
     /// `window` must be valid and must live for as long as the returned renderer.
@@ -132,76 +624,134 @@ impl Renderer {
         surface_size: SurfaceSize,
         present_mode: PresentMode,
     ) -> Result<Self, Error> {
-        let (adapter, surface) = Self::create_adapter_and_surface(
+        Self::builder(surface_size)
+            .backends(backends)
+            .power_preference(adapter_power_pref)
+            .present_mode(present_mode)
+            .build(window)
+            .await
+    }
+
+    /// Creates a [builder](RendererBuilder) for a `Renderer` whose surface has the given size.
+    pub fn builder(surface_size: SurfaceSize) -> RendererBuilder {
+        RendererBuilder::new(surface_size)
+    }
+
+    /// Queries the capabilities of the surface that a `Renderer` would create for `window`.
+    ///
+    /// This is useful for presenting the supported presentation modes in a settings menu before a
+    /// renderer exists. A temporary instance, surface, and adapter are created and then dropped,
+    /// so this is relatively heavyweight and should not be called every frame.
+    ///
+    /// # Safety
+    ///
+    /// `window` must be valid for the duration of this call.
+    pub async unsafe fn surface_capabilities(
+        window: &impl HasRawWindowHandle,
+        backends: Backends,
+        adapter_power_pref: PowerPreference,
+    ) -> Result<SurfaceCapabilities, Error> {
+        let (adapter, surface, _) = Self::create_adapter_and_surface(
             window,
             backends,
             adapter_power_pref,
+            false,
         )
         .await?;
 
-        let surface_formats = surface.get_supported_formats(&adapter);
-        // Pipeline creation will probably panic later if the hardcoded surface format is
-        // unsupported.
-        if !surface_formats.contains(&SURFACE_FORMAT) {
-            // TODO: We should support a few other formats to fall-back on.
-            todo!(
-                "Unsupported surface format; available are: {}",
-                surface_formats
-                    .iter()
-                    .map(|format| format!("{:?}", format))
-                    .collect::<Vec<String>>()
-                    .join(", "),
-            );
-        }
-
-        let (device, queue) = Self::create_device_and_queue(&adapter).await?;
-        let builtin_bind_group_layouts = BuiltinBindGroupLayouts::new(&device);
-        let depth = Self::create_depth(
-            &device,
-            surface_size.width,
-            surface_size.height,
-        );
-
-        let mut this = Self {
-            builtin_bind_group_layouts,
-            depth,
-            device,
-            queue,
-            surface,
-        };
-        // The surface must be configured before it is usable.
-        this.configure_surface(surface_size, present_mode);
+        Ok(SurfaceCapabilities {
+            formats: surface.get_supported_formats(&adapter),
+            present_modes: surface.get_supported_modes(&adapter),
+        })
+    }
 
-        Ok(this)
+    /// Creates a handle to a graphics adapter without regard for surface compatibility.
+    ///
+    /// The returned flag is whether or not the adapter is a fallback adapter.
+    async fn create_headless_adapter(
+        backends: Backends,
+        adapter_power_pref: PowerPreference,
+        allow_software_fallback: bool,
+    ) -> Result<(Adapter, bool), Error> {
+        Self::request_adapter(
+            &Instance::new(backends),
+            None,
+            adapter_power_pref,
+            allow_software_fallback,
+        )
+        .await
     }
 
     /// Creates handles to the graphics backend as well as the surface upon which rendering will
     /// take place.
+    ///
+    /// The returned flag is whether or not the adapter is a fallback adapter.
     async fn create_adapter_and_surface(
         window: &impl HasRawWindowHandle,
         backends: Backends,
         adapter_power_pref: PowerPreference,
-    ) -> Result<(Adapter, Surface), Error> {
+        allow_software_fallback: bool,
+    ) -> Result<(Adapter, Surface, bool), Error> {
         let instance = Instance::new(backends);
 
-        // SAFETY: [`Renderer::new`]'s safety contract promises that `window` is valid and will live
-        // for as long as `surface`.
+        // SAFETY: The safety contracts of [`RendererBuilder::build`] and
+        // [`Renderer::surface_capabilities`] promise that `window` is valid and will live for as
+        // long as `surface`.
         let surface = unsafe { instance.create_surface(window) };
 
-        instance.request_adapter(&RequestAdapterOptions {
-            compatible_surface: Some(&surface),
-            power_preference: adapter_power_pref,
-            ..Default::default()
-        })
-        .await
-        .ok_or_else(|| Error::NoCompatibleAdapterFound)
-        .map(|adapter| (adapter, surface))
+        let (adapter, is_fallback) = Self::request_adapter(
+            &instance,
+            Some(&surface),
+            adapter_power_pref,
+            allow_software_fallback,
+        )
+        .await?;
+
+        Ok((adapter, surface, is_fallback))
+    }
+
+    /// Requests an adapter with the given power preference, retrying with a fallback adapter if
+    /// none is found and `allow_software_fallback` is set.
+    ///
+    /// The returned flag is whether or not the adapter is a fallback adapter.
+    async fn request_adapter(
+        instance: &Instance,
+        compatible_surface: Option<&Surface>,
+        adapter_power_pref: PowerPreference,
+        allow_software_fallback: bool,
+    ) -> Result<(Adapter, bool), Error> {
+        let request = |force_fallback_adapter| {
+            instance.request_adapter(&RequestAdapterOptions {
+                power_preference: adapter_power_pref,
+                force_fallback_adapter,
+                compatible_surface,
+            })
+        };
+
+        if let Some(adapter) = request(false).await {
+            return Ok((adapter, false));
+        }
+        if allow_software_fallback {
+            if let Some(adapter) = request(true).await {
+                tracing::warn!(
+                    "no hardware adapter found; falling back to {}",
+                    adapter.get_info().name,
+                );
+                return Ok((adapter, true));
+            }
+        }
+
+        Err(Error::NoCompatibleAdapterFound)
     }
 
     /// Creates handles to the logical graphics device as well as the command buffer queue.
-    async fn create_device_and_queue(adapter: &Adapter) -> Result<(Device, Queue), Error> {
+    async fn create_device_and_queue(
+        adapter: &Adapter,
+        features: Features,
+    ) -> Result<(Device, Queue), Error> {
         adapter.request_device(
             &DeviceDescriptor {
+                features,
                 limits: adapter.limits(),
                 ..Default::default()
             },
@@ -211,34 +761,209 @@ impl Renderer {
         .map_err(|_| Error::NoCompatibleDeviceFound)
     }
 
-    fn create_depth(device: &Device, width: u32, height: u32) -> Texture {
+    fn create_depth(
+        device: &Device,
+        width: u32,
+        height: u32,
+        sample_count: u32,
+        format: TextureFormat,
+    ) -> Texture {
         device.create_texture(&TextureDescriptor {
             label: Some("Pylon depth texture"),
             size: Extent3d { width, height, depth_or_array_layers: 1 },
             mip_level_count: 1,
-            sample_count: 1,
+            sample_count,
             dimension: TextureDimension::D2,
-            format: DEPTH_FORMAT,
+            format,
             usage: TextureUsages::RENDER_ATTACHMENT,
         })
     }
 
+    fn create_depth_view(depth: &Texture) -> TextureView {
+        depth.create_view(&TextureViewDescriptor {
+            label: Some("Pylon depth view"),
+            // Attachments must include the stencil aspect, if any.
+            aspect: TextureAspect::All,
+            ..Default::default()
+        })
+    }
+
+    /// Creates the multisampled color texture if multisampling is enabled.
+    fn create_multisampled_frame(
+        device: &Device,
+        width: u32,
+        height: u32,
+        sample_count: u32,
+        format: TextureFormat,
+    ) -> Option<Texture> {
+        (sample_count > 1).then(|| device.create_texture(&TextureDescriptor {
+            label: Some("Pylon multisampled frame texture"),
+            size: Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count,
+            dimension: TextureDimension::D2,
+            format,
+            usage: TextureUsages::RENDER_ATTACHMENT,
+        }))
+    }
+
     /// Configures the rendering surface.
     ///
     /// This is automatically called during [`new`](Self::new). It may be called again to resize the
-    /// surface or modify the presentation mode.
+    /// surface or modify the presentation mode, though [`resize`](Self::resize) and
+    /// [`set_present_mode`](Self::set_present_mode) are more convenient when changing only one.
+    ///
+    /// The given size and presentation mode are remembered, so calling this again with
+    /// [`surface_size`](Self::surface_size) and [`present_mode`](Self::present_mode) restores the
+    /// surface after it is lost.
+    ///
+    /// If the surface doesn't support the given presentation mode, a warning is logged and
+    /// [`PresentMode::Fifo`], which every surface supports, is used instead.
+    /// [`PresentMode::AutoVsync`] and [`PresentMode::AutoNoVsync`] are always accepted, as *wgpu*
+    /// resolves them to a supported mode itself.
+    ///
+    /// For [headless](Self::new_headless) renderers, the target texture is recreated with the given
+    /// size, and the presentation mode has no effect.
     pub fn configure_surface(&mut self, size: SurfaceSize, present_mode: PresentMode) {
-        self.surface.configure(
+        let present_mode = self.validate_present_mode(present_mode);
+        self.surface_size = size;
+        self.present_mode = present_mode;
+        match self.target {
+            RenderTarget::Surface(ref surface) => {
+                surface.configure(
+                    &self.device,
+                    &SurfaceConfiguration {
+                        usage: TextureUsages::RENDER_ATTACHMENT,
+                        format: self.surface_format,
+                        width: size.width,
+                        height: size.height,
+                        present_mode,
+                    },
+                );
+            }
+            RenderTarget::Texture(ref mut texture) => {
+                *texture = Self::create_target_texture(&self.device, size, self.surface_format);
+            }
+        }
+        self.depth = Self::create_depth(
             &self.device,
-            &SurfaceConfiguration {
-                usage: TextureUsages::RENDER_ATTACHMENT,
-                format: SURFACE_FORMAT,
-                width: size.width,
-                height: size.height,
-                present_mode,
-            },
+            size.width,
+            size.height,
+            self.sample_count,
+            self.depth_format,
+        );
+        self.depth_view = Self::create_depth_view(&self.depth);
+        self.multisampled_frame = Self::create_multisampled_frame(
+            &self.device,
+            size.width,
+            size.height,
+            self.sample_count,
+            self.surface_format,
+        );
+    }
+
+    /// Returns the given presentation mode if the surface supports it, or [`PresentMode::Fifo`]
+    /// otherwise.
+    fn validate_present_mode(&self, present_mode: PresentMode) -> PresentMode {
+        let is_supported = match present_mode {
+            PresentMode::AutoVsync | PresentMode::AutoNoVsync => true,
+            _ => self.supported_present_modes.contains(&present_mode),
+        };
+        if is_supported || matches!(self.target, RenderTarget::Texture(_)) {
+            return present_mode;
+        }
+
+        tracing::warn!(
+            "Presentation mode {:?} is unsupported by the surface; falling back to {:?}",
+            present_mode,
+            PresentMode::Fifo,
         );
-        self.depth = Self::create_depth(&self.device, size.width, size.height);
+
+        PresentMode::Fifo
+    }
+}
+
+impl Renderer {
+    /// Whether or not the depth texture includes a stencil buffer.
+    ///
+    /// This is requested with [`RendererBuilder::stencil`].
+    pub fn has_stencil(&self) -> bool {
+        matches!(
+            self.depth_format,
+            TextureFormat::Depth24PlusStencil8 | TextureFormat::Depth32FloatStencil8,
+        )
+    }
+
+    /// The format of the depth texture.
+    ///
+    /// This is chosen with [`RendererBuilder::depth_format`] and includes a stencil aspect if
+    /// [`has_stencil`](Self::has_stencil) returns `true`.
+    pub fn depth_format(&self) -> TextureFormat {
+        self.depth_format
+    }
+
+    /// Information about the graphics adapter, such as its name, backend, and device type.
+    pub fn adapter_info(&self) -> &AdapterInfo {
+        &self.adapter_info
+    }
+
+    /// Whether or not the adapter was chosen as a
+    /// [software fallback](RendererBuilder::allow_software_fallback) because no other adapter was
+    /// found.
+    pub fn is_fallback_adapter(&self) -> bool {
+        self.is_fallback_adapter
+    }
+
+    /// The format of the surface frame, which is sRGB-encoded unless a linear surface was
+    /// [requested](RendererBuilder::srgb).
+    ///
+    /// Pipelines that render to the surface frame must use this format for their color target.
+    pub fn surface_format(&self) -> TextureFormat {
+        self.surface_format
+    }
+
+    /// The size of the surface.
+    pub fn surface_size(&self) -> SurfaceSize {
+        self.surface_size
+    }
+
+    /// The presentation mode of the surface.
+    ///
+    /// This is [`PresentMode::Fifo`] if the requested mode was unsupported.
+    pub fn present_mode(&self) -> PresentMode {
+        self.present_mode
+    }
+
+    /// The presentation modes supported by the surface.
+    ///
+    /// This is empty for [headless](Self::new_headless) renderers.
+    pub fn supported_present_modes(&self) -> &[PresentMode] {
+        &self.supported_present_modes
+    }
+
+    /// The color that each [job](Job) clears the surface frame to before its first pass.
+    ///
+    /// If this is `None`, which is the default, the frame is not cleared and passes draw over the
+    /// existing contents of the surface.
+    pub fn clear_color(&self) -> Option<Color> {
+        self.clear_color
+    }
+
+    /// Sets the [clear color](Self::clear_color).
+    pub fn set_clear_color(&mut self, color: Option<Color>) {
+        self.clear_color = color;
+    }
+
+    /// Resizes the surface, keeping the current presentation mode.
+    ///
+    /// This is typically called in response to the window being resized.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.configure_surface(SurfaceSize { width, height }, self.present_mode);
+    }
+
+    /// Changes the presentation mode of the surface, keeping the current size.
+    pub fn set_present_mode(&mut self, present_mode: PresentMode) {
+        self.configure_surface(self.surface_size, present_mode);
     }
 }
 
@@ -251,56 +976,273 @@ macro_rules! create_wgsl_module_from_path {
 
 impl Renderer {
     /// Creates a render pipeline for [an object](Object).
-    pub fn create_pipeline(
+    ///
+    /// This is equivalent to [`create_pipeline_with_options`](Self::create_pipeline_with_options)
+    /// with the [default options](PipelineOptions::default).
+    pub async fn create_pipeline(
+        &self,
+        fragment_source: ShaderSource<'_>,
+    ) -> Result<RenderPipeline, PipelineError> {
+        self.create_pipeline_with_options(fragment_source, &PipelineOptions::default()).await
+    }
+
+    /// Creates a render pipeline for [an object](Object) with the given options.
+    ///
+    /// WGSL compilation and pipeline validation errors are returned rather than causing a panic.
+    /// This makes it suitable for hot-reloading fragment shaders during development: every
+    /// pipeline shares the built-in bind group layouts and vertex shader, so a pipeline created
+    /// from an edited shader may simply replace the previous one, and on failure, the caller may
+    /// keep using the previous pipeline.
+    pub async fn create_pipeline_with_options(
+        &self,
+        fragment_source: ShaderSource<'_>,
+        options: &PipelineOptions,
+    ) -> Result<RenderPipeline, PipelineError> {
+        self.capture_pipeline_errors(|| {
+            self.create_pipeline_with_modules(
+                &create_wgsl_module_from_path!(self.device, "shaders/vertex.wgsl"),
+                Some(&self.create_fragment_module(fragment_source)),
+                &[self.surface_format],
+                options,
+            )
+        })
+        .await
+    }
+
+    /// Creates a render pipeline for [an object](Object) with a custom vertex shader.
+    ///
+    /// To remain compatible with Pylon, the vertex shader must:
+    /// - have an entry point named `main`;
+    /// - accept the vertex position, a `vec3<f32>`, at `@location(0)`;
+    /// - optionally accept the vertex normal, a `vec3<f32>`, at `@location(1)`;
+    /// - optionally accept the vertex color, a `vec4<f32>`, at `@location(2)`;
+    /// - declare the camera transformation matrix, a `mat4x4<f32>` uniform, at
+    ///   `@group(0) @binding(0)`; and
+    /// - declare the object transforms, a uniform struct of two `mat4x4<f32>` members (the
+    ///   transformation matrix followed by the normal matrix) that mirrors [`ObjectTransforms`],
+    ///   at `@group(1) @binding(0)`.
+    ///
+    /// The built-in vertex shader at `src/shaders/vertex.wgsl` is a good starting point.
+    pub async fn create_pipeline_with_vertex_shader(
+        &self,
+        vertex_source: ShaderSource<'_>,
+        fragment_source: ShaderSource<'_>,
+        options: &PipelineOptions,
+    ) -> Result<RenderPipeline, PipelineError> {
+        self.capture_pipeline_errors(|| {
+            self.create_pipeline_with_modules(
+                &self.device.create_shader_module(ShaderModuleDescriptor {
+                    label: Some("Pylon vertex shader"),
+                    source: vertex_source,
+                }),
+                Some(&self.create_fragment_module(fragment_source)),
+                &[self.surface_format],
+                options,
+            )
+        })
+        .await
+    }
+
+    /// Creates a render pipeline for [an object](Object) whose fragment shader receives the
+    /// [colors of its vertices](MeshVertex::color).
+    ///
+    /// The vertex shader at `src/shaders/vertex_colors.wgsl` is the same as the built-in vertex
+    /// shader, but it also passes the color of each vertex, interpolated across triangles, to the
+    /// fragment shader at `@location(1)` as a `vec4<f32>`, after the world-space normal at
+    /// `@location(0)`. *wgpu* requires fragment shaders to declare every input that the vertex
+    /// shader provides, so pipelines created with [`create_pipeline`](Self::create_pipeline) don't
+    /// receive colors.
+    pub async fn create_vertex_colored_pipeline(
+        &self,
+        fragment_source: ShaderSource<'_>,
+        options: &PipelineOptions,
+    ) -> Result<RenderPipeline, PipelineError> {
+        self.capture_pipeline_errors(|| {
+            self.create_pipeline_with_modules(
+                &create_wgsl_module_from_path!(self.device, "shaders/vertex_colors.wgsl"),
+                Some(&self.create_fragment_module(fragment_source)),
+                &[self.surface_format],
+                options,
+            )
+        })
+        .await
+    }
+
+    /// Creates a render pipeline for [an object](Object) whose fragment shader writes to multiple
+    /// render targets (MRT), as for the G-buffer of a deferred renderer.
+    ///
+    /// The fragment shader must declare one output per format in `color_formats`, with the output
+    /// for `color_formats[i]` at `@location(i)`. [`options.blend`](PipelineOptions::blend) applies
+    /// to every target. Objects using the returned pipeline must be drawn in a pass created by
+    /// [`Job::add_pass_with_targets`] with attachments of matching formats, in the same order.
+    pub async fn create_pipeline_with_targets(
         &self,
-        fragment_shader: &ShaderModule,
+        fragment_source: ShaderSource<'_>,
+        color_formats: &[TextureFormat],
+        options: &PipelineOptions,
+    ) -> Result<RenderPipeline, PipelineError> {
+        self.capture_pipeline_errors(|| {
+            self.create_pipeline_with_modules(
+                &create_wgsl_module_from_path!(self.device, "shaders/vertex.wgsl"),
+                Some(&self.create_fragment_module(fragment_source)),
+                color_formats,
+                options,
+            )
+        })
+        .await
+    }
+
+    /// Creates a render pipeline for [an object](Object), panicking if the fragment shader fails
+    /// to compile or the pipeline is invalid.
+    ///
+    /// This is for users who would rather [`expect`](Result::expect) a pipeline than handle
+    /// [`PipelineError`]s; the panic message is *wgpu*'s diagnostic.
+    pub fn create_pipeline_or_panic(
+        &self,
+        fragment_source: ShaderSource<'_>,
+        options: &PipelineOptions,
     ) -> RenderPipeline {
+        // Without an error scope, *wgpu* reports errors to the uncaptured error handler, which
+        // panics.
+        self.create_pipeline_with_modules(
+            &create_wgsl_module_from_path!(self.device, "shaders/vertex.wgsl"),
+            Some(&self.create_fragment_module(fragment_source)),
+            &[self.surface_format],
+            options,
+        )
+    }
+
+    fn create_fragment_module(&self, source: ShaderSource<'_>) -> ShaderModule {
+        self.device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Pylon fragment shader"),
+            source,
+        })
+    }
+
+    /// Runs `create` and returns its result unless *wgpu* raised a validation error in the
+    /// meantime.
+    async fn capture_pipeline_errors(
+        &self,
+        create: impl FnOnce() -> RenderPipeline,
+    ) -> Result<RenderPipeline, PipelineError> {
+        // Errors raised between pushing and popping this scope are captured rather than reported
+        // to the uncaptured error handler, which panics.
+        self.device.push_error_scope(ErrorFilter::Validation);
+        let pipeline = create();
+
+        match self.device.pop_error_scope().await {
+            Some(error) => {
+                if self.log_validation_errors {
+                    tracing::error!("wgpu validation error during pipeline creation: {}", error);
+                }
+
+                Err(PipelineError { message: error.to_string() })
+            }
+            None => Ok(pipeline),
+        }
+    }
+
+    /// Creates a render pipeline from the given shader modules.
+    ///
+    /// The fragment shader writes one color target per format in `color_formats`. If
+    /// `fragment_shader` is `None`, the pipeline has no color targets and writes only depth.
+    fn create_pipeline_with_modules(
+        &self,
+        vertex_shader: &ShaderModule,
+        fragment_shader: Option<&ShaderModule>,
+        color_formats: &[TextureFormat],
+        options: &PipelineOptions,
+    ) -> RenderPipeline {
+        self.create_pipeline_with_settings(&PipelineSettings {
+            vertex_shader,
+            vertex_buffers: &[Self::mesh_vertex_buffer_layout(VertexStepMode::Vertex)],
+            fragment_shader,
+            color_formats,
+            options,
+            depth_target: None,
+        })
+    }
+
+    /// Creates a render pipeline with the given settings.
+    ///
+    /// This is [`create_pipeline_with_modules`](Self::create_pipeline_with_modules) for pipelines
+    /// whose vertex shader reads buffers other than a single buffer of [`MeshVertex`]s or that
+    /// render to a depth texture other than the renderer's.
+    fn create_pipeline_with_settings(&self, settings: &PipelineSettings<'_>) -> RenderPipeline {
+        let PipelineSettings {
+            vertex_shader,
+            vertex_buffers,
+            fragment_shader,
+            color_formats,
+            options,
+            depth_target,
+        } = *settings;
+        let (sample_count, depth_format) =
+            depth_target.unwrap_or((self.sample_count, self.depth_format));
+
+        let color_targets: Vec<Option<ColorTargetState>> = color_formats
+            .iter()
+            .map(|&format| Some(ColorTargetState {
+                // The output of the fragment shader must be compatible with this format.
+                format,
+                blend: options.blend,
+                write_mask: ColorWrites::ALL,
+            }))
+            .collect();
+
         self.device.create_render_pipeline(&RenderPipelineDescriptor {
             label: Some("Pylon pipeline"),
-            layout: Some(&self.device.create_pipeline_layout(&PipelineLayoutDescriptor {
-                label: Some("Pylon pipeline layout"),
-                bind_group_layouts: &[
-                    &self.builtin_bind_group_layouts.for_camera,
-                    &self.builtin_bind_group_layouts.for_object,
-                ],
-                push_constant_ranges: &[],
-            })),
+            layout: Some(&self.create_pipeline_layout()),
             vertex: VertexState {
-                module: &create_wgsl_module_from_path!(self.device, "shaders/vertex.wgsl"),
+                module: vertex_shader,
                 entry_point: "main",
-                buffers: &[VertexBufferLayout {
-                    array_stride: std::mem::size_of::<MeshVertex>() as BufferAddress,
-                    step_mode: VertexStepMode::Vertex,
-                    attributes: &vertex_attr_array![0 => Float32x3],
-                }],
+                buffers: vertex_buffers,
             },
-            fragment: Some(FragmentState {
-                module: fragment_shader,
+            fragment: fragment_shader.map(|module| FragmentState {
+                module,
                 entry_point: "main",
-                targets: &[Some(wgpu::ColorTargetState {
-                    // The output of the fragment shader must be compatible with this format.
-                    format: SURFACE_FORMAT,
-                    blend: None,
-                    write_mask: ColorWrites::ALL,
-                })],
+                targets: &color_targets,
             }),
             primitive: PrimitiveState {
-                topology: PrimitiveTopology::TriangleList,
-                polygon_mode: PolygonMode::Fill,
+                topology: options.topology,
+                // Strips must know which index value restarts the strip, and this depends on the
+                // index format. Pylon always uses 32-bit indices.
+                strip_index_format: options.topology.is_strip().then_some(INDEX_FORMAT),
+                polygon_mode: options.polygon_mode,
                 ..Default::default()
             },
             depth_stencil: Some(DepthStencilState {
-                format: DEPTH_FORMAT,
-                depth_write_enabled: true,
-                depth_compare: CompareFunction::Less,
-                stencil: StencilState::default(),
-                bias: DepthBiasState::default(),
+                format: depth_format,
+                depth_write_enabled: options.depth_write_enabled,
+                depth_compare: options.depth_compare,
+                stencil: options.stencil.clone(),
+                bias: options.depth_bias,
             }),
-            multisample: MultisampleState::default(),
+            multisample: MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
             multiview: None,
         })
     }
 
+    /// Creates the layout shared by all pipelines, which consists of the built-in bind groups.
+    fn create_pipeline_layout(&self) -> PipelineLayout {
+        self.device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Pylon pipeline layout"),
+            bind_group_layouts: &[
+                &self.builtin_bind_group_layouts.for_camera,
+                &self.builtin_bind_group_layouts.for_object,
+            ],
+            push_constant_ranges: &[],
+        })
+    }
+
+    /// The layout of a buffer of [`MeshVertex`]s, which advances at the given rate.
+    fn mesh_vertex_buffer_layout(step_mode: VertexStepMode) -> VertexBufferLayout<'static> {
+        VertexBufferLayout { step_mode, ..VERTEX_BUFFER_LAYOUT }
+    }
+
     pub fn device(&self) -> &Device {
         &self.device
     }
@@ -309,6 +1251,80 @@ impl Renderer {
         &self.queue
     }
 
+    /// Writes `bytes` to the given range of a uniform buffer and waits for the write to complete.
+    ///
+    /// This replaces the ritual of mapping a staging buffer, polling the device, writing and
+    /// flushing, unmapping, and submitting. The length of `range` must equal the length of `bytes`.
+    ///
+    /// This blocks the calling thread on [`Maintain::Wait`] until the GPU has received the new
+    /// contents, so it is best suited to infrequent or small updates.
+    pub fn update_uniform(&self, buffer: &Buffer, range: Range<BufferAddress>, bytes: &[u8]) {
+        assert_eq!(
+            range.end - range.start,
+            bytes.len() as BufferAddress,
+            "uniform range and contents differ in length",
+        );
+
+        self.queue.write_buffer(buffer, range.start, bytes);
+        // Writes are staged until the next submission, so we submit an empty command buffer to
+        // flush them.
+        self.queue.submit(None);
+        self.device.poll(Maintain::Wait);
+    }
+
+    /// Writes the [`ObjectTransforms`] for the given transformation matrix to the
+    /// [transforms buffer](Object::transforms_buffer) of `object`.
+    ///
+    /// The write is staged by the queue and lands before the next submission, such as that of the
+    /// next [job](Job), so this doesn't block.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `object` doesn't expose its transforms buffer.
+    pub fn update_object_transform(
+        &self,
+        object: &(impl Object + ?Sized),
+        transformation_matrix: &Matrix,
+    ) {
+        let (buffer, offset) = object
+            .transforms_buffer()
+            .expect("object doesn't expose its transforms buffer");
+        let transforms = ObjectTransforms::new(transformation_matrix);
+
+        self.queue.write_buffer(buffer, offset, bytemuck::bytes_of(&transforms));
+    }
+
+    /// Creates a uniform buffer initialized with `contents`.
+    ///
+    /// The buffer is padded with zeros to a whole number of 16-byte rows, which is the alignment of
+    /// the uniform address space, and to at least one row. It may be rewritten with
+    /// [`Queue::write_buffer`], and its [entire binding](Buffer::as_entire_buffer_binding) is
+    /// suitable for [`create_camera_transforms_uniform`](Self::create_camera_transforms_uniform)
+    /// and [`create_object_transforms_uniform`](Self::create_object_transforms_uniform):
+    ///
+    /// ```ignore
+    /// let buffer = renderer.create_uniform_buffer(bytemuck::bytes_of(&transforms));
+    /// let uniform = renderer.create_object_transforms_uniform(buffer.as_entire_buffer_binding());
+    /// ```
+    ///
+    /// Many uniforms may instead be suballocated from one large buffer by the caller, as long as
+    /// each binding's offset is a multiple of the `min_uniform_buffer_offset_alignment` limit.
+    pub fn create_uniform_buffer(&self, contents: &[u8]) -> Buffer {
+        const ROW_SIZE: usize = 16;
+
+        let padded_len = contents.len().max(1) + ROW_SIZE - 1;
+        let mut padded = vec![0; padded_len - (padded_len % ROW_SIZE)];
+        padded[..contents.len()].copy_from_slice(contents);
+
+        self.log_validation_errors("uniform buffer creation", || {
+            self.device.create_buffer_init(&util::BufferInitDescriptor {
+                label: Some("Pylon uniform buffer"),
+                contents: &padded,
+                usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            })
+        })
+    }
+
     /// Creates a new `CameraTransformsUniform` with the given buffer binding.
     ///
     /// If the backing storage for the returned uniform changes, it *must* be recreated by calling
@@ -330,6 +1346,11 @@ impl Renderer {
     ///
     /// If the backing storage for the returned uniform changes, it *must* be recreated by calling
     /// this function again with the new buffer binding.
+    ///
+    /// The object bind group uses a dynamic offset, so a single uniform may be shared between many
+    /// objects that each report their own [offset](crate::Object::transforms_offset) into the
+    /// buffer. In that case, `binding` should cover a single [`ObjectTransforms`] starting at the
+    /// first object's, and the binding size must be given explicitly.
     pub fn create_object_transforms_uniform(
         &self,
         binding: BufferBinding,
@@ -365,7 +1386,62 @@ impl Renderer {
         }
     }
 
+    /// Blocks until the GPU has finished the [submitted job](Job::submit) with the given index and
+    /// all submissions before it.
+    ///
+    /// Like [`Job::submit_and_wait`], this stalls the CPU and is not meant for every frame.
+    pub fn wait_for_submission(&self, index: SubmissionIndex) {
+        self.device.poll(Maintain::WaitForSubmissionIndex(index));
+    }
+
+    /// Blocks the calling thread until the GPU has finished all submitted work.
+    ///
+    /// This is intended for clean shutdown and for tests: once it returns, resources may be
+    /// dropped without the GPU still using them. Work submitted from other threads while waiting is
+    /// waited for as well. Like [`wait_for_submission`](Self::wait_for_submission), this stalls the
+    /// CPU and is not meant for every frame.
+    pub fn wait_idle(&self) {
+        // Polling reports whether the queue is empty, which it may not be if another thread
+        // submitted work in the meantime.
+        while !self.device.poll(Maintain::Wait) {}
+    }
+
     pub fn create_render<'a>(&'a self) -> Job<'a> {
-        Job::new(&self.surface, &self.depth, &self.device, &self.queue)
+        let settings = render::JobSettings {
+            depth_view: &self.depth_view,
+            has_stencil: self.has_stencil(),
+            multisampled_frame: self.multisampled_frame.as_ref(),
+            clear_color: self.clear_color,
+            timestamps: self.timestamps.as_ref(),
+            log_validation_errors: self.log_validation_errors,
+        };
+
+        Job::new(&self.target, &settings, &self.device, &self.queue)
+    }
+
+    /// Clears the surface frame to the given color and presents it without drawing anything.
+    ///
+    /// This clears the depth texture as well, and it ignores the renderer's
+    /// [clear color](Self::clear_color).
+    pub fn clear(&self, color: Color) -> SubmissionIndex {
+        let mut job = self.create_render();
+        job.add_pass_with_loads(ColorLoad::Clear(color), DepthLoad::Clear(DepthLoad::FAR));
+
+        job.submit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vertex_buffer_layout_matches_mesh_vertex() {
+        let stride = std::mem::size_of::<MeshVertex>() as BufferAddress;
+        assert_eq!(VERTEX_BUFFER_LAYOUT.array_stride, stride);
+        assert_eq!(stride, 48);
+        for attribute in VERTEX_BUFFER_LAYOUT.attributes {
+            assert!(attribute.offset + attribute.format.size() <= stride);
+        }
     }
 }
@@ -0,0 +1,177 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Shared vertex and index buffers for many meshes.
+
+use std::ops::Range;
+
+use wgpu::util::DeviceExt as _;
+
+use super::{render::Pass, RenderError, Renderer, INDEX_FORMAT};
+use crate::{BindGroupSlot, Mesh, MeshTriangle, ObjectTransformsUniform};
+
+impl Renderer {
+    /// Uploads the given meshes into a single pair of vertex and index buffers.
+    ///
+    /// Meshes are numbered by iteration order; [`BatchedObject::mesh_index`] refers to a mesh by
+    /// this number.
+    pub fn create_mesh_batch<'a>(&self, meshes: impl IntoIterator<Item = &'a Mesh>) -> MeshBatch {
+        let mut vertices = Vec::new();
+        let mut triangles: Vec<MeshTriangle> = Vec::new();
+        let mut ranges = Vec::new();
+        for mesh in meshes {
+            let first_index = 3 * triangles.len() as u32;
+            ranges.push(MeshRange {
+                base_vertex: vertices.len() as i32,
+                indices: first_index..(first_index + 3 * mesh.triangle_count()),
+            });
+            vertices.extend_from_slice(mesh.vertex_pool());
+            // Indices are copied verbatim; they remain relative to the mesh's own vertex pool.
+            triangles.extend_from_slice(mesh.triangles());
+        }
+
+        MeshBatch {
+            vertex_buffer: self.log_validation_errors("mesh batch buffer creation", || {
+                self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Pylon mesh batch vertex buffer"),
+                    contents: bytemuck::cast_slice(&vertices),
+                    usage: wgpu::BufferUsages::VERTEX,
+                })
+            }),
+            index_buffer: self.log_validation_errors("mesh batch buffer creation", || {
+                self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Pylon mesh batch index buffer"),
+                    contents: bytemuck::cast_slice(&triangles),
+                    usage: wgpu::BufferUsages::INDEX,
+                })
+            }),
+            is_empty: triangles.is_empty(),
+            ranges,
+        }
+    }
+}
+
+/// Many meshes packed into shared GPU buffers.
+///
+/// Rendering a scene of many small meshes from separate buffers means binding a vertex and index
+/// buffer per object. A `MeshBatch` instead binds its buffers once per
+/// [draw](Pass::draw_batch) and selects each mesh by offset.
+///
+/// This type may be created via [`Renderer::create_mesh_batch`].
+#[derive(Debug)]
+pub struct MeshBatch {
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    /// Whether or not the batch contains no triangles, in which case its buffers are empty and
+    /// cannot be bound.
+    is_empty: bool,
+    ranges: Vec<MeshRange>,
+}
+
+impl MeshBatch {
+    /// The number of meshes in this batch.
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Whether or not this batch contains no meshes.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The location of the mesh at the given index within this batch's buffers, or `None` if the
+    /// index is out of range.
+    pub fn range(&self, index: usize) -> Option<&MeshRange> {
+        self.ranges.get(index)
+    }
+
+    /// The locations of every mesh within this batch's buffers, in order.
+    pub fn ranges(&self) -> &[MeshRange] {
+        &self.ranges
+    }
+}
+
+/// The location of a mesh within the buffers of a [`MeshBatch`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MeshRange {
+    /// The index of the first vertex of this mesh within the batch's vertex buffer.
+    pub base_vertex: i32,
+    /// The indices of this mesh within the batch's index buffer.
+    pub indices: Range<u32>,
+}
+
+/// The interface to user-managed resources for an object whose geometry lives in a [`MeshBatch`].
+///
+/// This is [`Object`](crate::Object) without the buffers, which are instead supplied by the batch.
+pub trait BatchedObject {
+    /// The index of this object's mesh within the batch it is drawn from.
+    fn mesh_index(&self) -> usize;
+
+    /// The [pipeline](wgpu::RenderPipeline) to be used during rendering of this object.
+    fn render_pipeline(&self) -> &wgpu::RenderPipeline;
+
+    fn transforms_uniform(&self) -> &ObjectTransformsUniform;
+
+    /// The dynamic offset, in bytes, of this object's transformation matrix relative to the binding
+    /// of [its transforms uniform](Self::transforms_uniform).
+    ///
+    /// See [`Object::transforms_offset`](crate::Object::transforms_offset).
+    fn transforms_offset(&self) -> u32 {
+        0
+    }
+
+    /// Whether or not this object is drawn.
+    ///
+    /// See [`Object::visible`](crate::Object::visible).
+    fn visible(&self) -> bool {
+        true
+    }
+
+    /// The [bind group slots](BindGroupSlot) to be assigned for rendering this object.
+    ///
+    /// The default implementation assigns none.
+    fn bind_group_slots<'a>(&'a self) -> &'a [BindGroupSlot<'a>] {
+        &[]
+    }
+}
+
+impl<'a> Pass<'a> {
+    /// Draws the given objects from the shared buffers of `batch`.
+    ///
+    /// The batch's buffers are bound once; each [visible](BatchedObject::visible) object is then
+    /// drawn with the index range and base vertex of its mesh.
+    pub fn draw_batch<O: BatchedObject + 'a>(
+        &mut self,
+        batch: &'a MeshBatch,
+        objects: impl IntoIterator<Item = &'a O>,
+    ) -> Result<&mut Self, RenderError> {
+        if batch.is_empty {
+            return Ok(self);
+        }
+
+        self.render_pass.set_vertex_buffer(0, batch.vertex_buffer.slice(..));
+        self.render_pass.set_index_buffer(batch.index_buffer.slice(..), INDEX_FORMAT);
+
+        for object in objects.into_iter().filter(|object| object.visible()) {
+            let index = object.mesh_index();
+            let range = batch.range(index).ok_or(RenderError::MeshIndexOutOfRange {
+                index,
+                mesh_count: batch.len(),
+            })?;
+            let slots = object.bind_group_slots();
+            self.check_bind_group_slots(slots)?;
+
+            self.set_pipeline(object.render_pipeline());
+            self.set_object_bind_groups(
+                object.transforms_uniform(),
+                object.transforms_offset(),
+                slots,
+            );
+            self.record_draw(range.indices.len() as u32 / 3);
+            // Each mesh's indices are relative to its own vertex pool, so the GPU adds the base
+            // vertex to every index to find the mesh's vertices within the shared buffer.
+            self.render_pass.draw_indexed(range.indices.clone(), range.base_vertex, 0..1);
+        }
+
+        Ok(self)
+    }
+}
@@ -0,0 +1,322 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The [`RendererBuilder`].
+
+use std::sync::OnceLock;
+
+use raw_window_handle::HasRawWindowHandle;
+use wgpu::*;
+
+use super::{
+    timing::Timestamps,
+    BuiltinBindGroupLayouts,
+    DepthFormat,
+    Error,
+    RenderTarget,
+    Renderer,
+    SurfaceSize,
+    LINEAR_SURFACE_FORMAT,
+    SURFACE_FORMAT,
+};
+
+/// A builder for [`Renderer`]s.
+///
+/// This is created with [`Renderer::builder`]. Options that are not explicitly set take on the
+/// defaults documented on each method.
+#[derive(Debug)]
+pub struct RendererBuilder {
+    backends: Backends,
+    power_preference: PowerPreference,
+    surface_size: SurfaceSize,
+    present_mode: PresentMode,
+    features: Features,
+    sample_count: u32,
+    depth_format: DepthFormat,
+    stencil: bool,
+    srgb: bool,
+    allow_software_fallback: bool,
+    log_validation_errors: bool,
+}
+
+impl RendererBuilder {
+    /// Creates a new `RendererBuilder` for a surface of the given size.
+    pub fn new(surface_size: SurfaceSize) -> Self {
+        Self {
+            backends: Backends::all(),
+            power_preference: PowerPreference::default(),
+            surface_size,
+            present_mode: PresentMode::Fifo,
+            features: Features::empty(),
+            sample_count: 1,
+            depth_format: DepthFormat::default(),
+            stencil: false,
+            srgb: true,
+            allow_software_fallback: false,
+            log_validation_errors: false,
+        }
+    }
+
+    /// The graphics backends that may be used.
+    ///
+    /// The default is [`Backends::all`].
+    pub fn backends(mut self, backends: Backends) -> Self {
+        self.backends = backends;
+        self
+    }
+
+    /// The preferred power profile of the graphics adapter.
+    ///
+    /// The default is [`PowerPreference::default`].
+    pub fn power_preference(mut self, power_preference: PowerPreference) -> Self {
+        self.power_preference = power_preference;
+        self
+    }
+
+    /// The size of the rendering surface.
+    pub fn surface_size(mut self, surface_size: SurfaceSize) -> Self {
+        self.surface_size = surface_size;
+        self
+    }
+
+    /// The presentation mode of the rendering surface.
+    ///
+    /// The default is [`PresentMode::Fifo`], which is supported everywhere. If the surface doesn't
+    /// support the given mode, `Fifo` is used instead; see
+    /// [`Renderer::configure_surface`].
+    pub fn present_mode(mut self, present_mode: PresentMode) -> Self {
+        self.present_mode = present_mode;
+        self
+    }
+
+    /// The device features to request.
+    ///
+    /// The default is [`Features::empty`]. Device creation fails if the adapter doesn't support
+    /// every requested feature.
+    ///
+    /// Requesting [`Features::TIMESTAMP_QUERY`] enables
+    /// [`Renderer::last_frame_gpu_time`].
+    pub fn features(mut self, features: Features) -> Self {
+        self.features = features;
+        self
+    }
+
+    /// The number of samples per pixel for multisample anti-aliasing.
+    ///
+    /// The default is 1, which disables multisampling. 4 is supported by all adapters; other
+    /// counts may not be.
+    pub fn sample_count(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
+    /// The format of the depth texture.
+    ///
+    /// The default is [`DepthFormat::Depth24Plus`]; see [`DepthFormat`] for the tradeoffs of each
+    /// format. Building fails with [`Error::UnsupportedDepthFormat`] if the adapter cannot render
+    /// to the format, along with any [stencil buffer](Self::stencil), at the
+    /// [sample count](Self::sample_count).
+    pub fn depth_format(mut self, depth_format: DepthFormat) -> Self {
+        self.depth_format = depth_format;
+        self
+    }
+
+    /// Whether or not the depth texture includes an 8-bit stencil buffer.
+    ///
+    /// The default is `false`, which saves the memory of the stencil buffer. A stencil buffer is
+    /// required for [pipelines](super::PipelineOptions::stencil) that use stencil testing, as for
+    /// outlines and portals.
+    pub fn stencil(mut self, enabled: bool) -> Self {
+        self.stencil = enabled;
+        self
+    }
+
+    /// Whether or not the surface format is sRGB-encoded.
+    ///
+    /// The default is `true`, for which the surface format is [`TextureFormat::Bgra8UnormSrgb`]
+    /// and fragment shaders should output linear colors. If `false`, the format is
+    /// [`TextureFormat::Bgra8Unorm`], and colors are stored as-is, so fragment shaders are
+    /// responsible for gamma encoding. See the [color encoding](Renderer#color-encoding) notes.
+    ///
+    /// Building for a window fails with [`Error::UnsupportedSurfaceFormat`] if its surface does
+    /// not support the format.
+    pub fn srgb(mut self, enabled: bool) -> Self {
+        self.srgb = enabled;
+        self
+    }
+
+    /// Whether or not to fall back to a software adapter if no adapter matches the
+    /// [power preference](Self::power_preference).
+    ///
+    /// The default is `false`, for which building fails with
+    /// [`Error::NoCompatibleAdapterFound`] instead. Enabling this lets CI machines and other
+    /// environments without a GPU render at all, but the fallback adapter rasterizes on the CPU
+    /// and may be orders of magnitude slower; it is suited to tests and tools rather than
+    /// interactive use. Whether the fallback was taken is reported by
+    /// [`Renderer::is_fallback_adapter`], and [`Renderer::adapter_info`] describes the adapter.
+    ///
+    /// Not every platform and backend provides a fallback adapter.
+    pub fn allow_software_fallback(mut self, allowed: bool) -> Self {
+        self.allow_software_fallback = allowed;
+        self
+    }
+
+    /// Whether or not *wgpu* validation errors are logged through *tracing*.
+    ///
+    /// The default is `false`, for which validation errors reach the handler registered with
+    /// [`Device::on_uncaptured_error`], which panics by default; pipeline creation errors are
+    /// returned either way. If `true`, validation errors raised while creating pipelines and
+    /// buffers and while submitting jobs are captured and logged at the error level along with
+    /// the operation that raised them, and that operation carries on. This is intended for
+    /// debugging integration issues, as the wrapping error scopes add a little overhead, and a
+    /// failed operation yields an invalid resource rather than stopping the program.
+    ///
+    /// Only native platforms report validation errors in time for them to be logged; on the web,
+    /// this has no effect.
+    pub fn log_validation_errors(mut self, enabled: bool) -> Self {
+        self.log_validation_errors = enabled;
+        self
+    }
+
+    /// Creates the [`Renderer`].
+    ///
+    /// # Safety
+    ///
+    /// `window` must be valid and must live for as long as the returned renderer.
+    pub async unsafe fn build(self, window: &impl HasRawWindowHandle) -> Result<Renderer, Error> {
+        let (adapter, surface, is_fallback_adapter) = Renderer::create_adapter_and_surface(
+            window,
+            self.backends,
+            self.power_preference,
+            self.allow_software_fallback,
+        )
+        .await?;
+
+        let supported = surface.get_supported_formats(&adapter);
+        let requested = self.surface_format();
+        if !supported.contains(&requested) {
+            return Err(Error::UnsupportedSurfaceFormat { requested, supported });
+        }
+
+        self.finish(adapter, is_fallback_adapter, Some(surface)).await
+    }
+
+    /// Creates a headless [`Renderer`], which renders to an owned texture rather than a window
+    /// surface.
+    ///
+    /// See [`Renderer::new_headless`].
+    pub async fn build_headless(self) -> Result<Renderer, Error> {
+        let (adapter, is_fallback_adapter) = Renderer::create_headless_adapter(
+            self.backends,
+            self.power_preference,
+            self.allow_software_fallback,
+        )
+        .await?;
+
+        self.finish(adapter, is_fallback_adapter, None).await
+    }
+
+    /// The surface format chosen by [`srgb`](Self::srgb).
+    fn surface_format(&self) -> TextureFormat {
+        if self.srgb { SURFACE_FORMAT } else { LINEAR_SURFACE_FORMAT }
+    }
+
+    /// Checks that `adapter` can render to a depth texture of the given format and sample count.
+    fn validate_depth_format(
+        adapter: &Adapter,
+        format: TextureFormat,
+        sample_count: u32,
+    ) -> Result<(), Error> {
+        let required_features = format.describe().required_features;
+        let format_features = adapter.get_texture_format_features(format);
+        let is_supported = adapter.features().contains(required_features)
+            && format_features.allowed_usages.contains(TextureUsages::RENDER_ATTACHMENT)
+            && (sample_count == 1
+                || format_features.flags.contains(TextureFormatFeatureFlags::MULTISAMPLE));
+
+        if is_supported {
+            Ok(())
+        } else {
+            Err(Error::UnsupportedDepthFormat { format })
+        }
+    }
+
+    /// Creates the [`Renderer`] for the given adapter, rendering to `surface` if it is given or to
+    /// an owned texture otherwise.
+    async fn finish(
+        self,
+        adapter: Adapter,
+        is_fallback_adapter: bool,
+        surface: Option<Surface>,
+    ) -> Result<Renderer, Error> {
+        let depth_format = self.depth_format.texture_format(self.stencil);
+        Self::validate_depth_format(&adapter, depth_format, self.sample_count)?;
+        // Some depth formats, such as `Depth32FloatStencil8`, require a feature of their own.
+        let features = self.features | depth_format.describe().required_features;
+        let (device, queue) = Renderer::create_device_and_queue(&adapter, features).await?;
+        let builtin_bind_group_layouts = BuiltinBindGroupLayouts::new(&device);
+        let depth = Renderer::create_depth(
+            &device,
+            self.surface_size.width,
+            self.surface_size.height,
+            self.sample_count,
+            depth_format,
+        );
+        let depth_view = Renderer::create_depth_view(&depth);
+        let surface_format = self.surface_format();
+        let multisampled_frame = Renderer::create_multisampled_frame(
+            &device,
+            self.surface_size.width,
+            self.surface_size.height,
+            self.sample_count,
+            surface_format,
+        );
+
+        let timestamps = self
+            .features
+            .contains(Features::TIMESTAMP_QUERY)
+            .then(|| Timestamps::new(&device, &queue));
+
+        // Headless renderers have no surface, so no presentation mode is validated for them.
+        let supported_present_modes = surface
+            .as_ref()
+            .map_or_else(Vec::new, |surface| surface.get_supported_modes(&adapter));
+        let target = match surface {
+            Some(surface) => RenderTarget::Surface(surface),
+            None => RenderTarget::Texture(
+                Renderer::create_target_texture(&device, self.surface_size, surface_format),
+            ),
+        };
+
+        let mut renderer = Renderer {
+            builtin_bind_group_layouts,
+            depth,
+            depth_view,
+            depth_format,
+            multisampled_frame,
+            sample_count: self.sample_count,
+            surface_format,
+            depth_only_pipeline: OnceLock::new(),
+            depth_prepass_pipeline: OnceLock::new(),
+            wireframe_pipeline: OnceLock::new(),
+            normals_pipeline: OnceLock::new(),
+            lines_pipeline: OnceLock::new(),
+            skybox_resources: OnceLock::new(),
+            pipeline_cache: Default::default(),
+            timestamps,
+            adapter_info: adapter.get_info(),
+            is_fallback_adapter,
+            log_validation_errors: self.log_validation_errors,
+            clear_color: None,
+            surface_size: self.surface_size,
+            present_mode: self.present_mode,
+            supported_present_modes,
+            device,
+            queue,
+            target,
+        };
+        // The surface must be configured before it is usable.
+        renderer.configure_surface(self.surface_size, self.present_mode);
+
+        Ok(renderer)
+    }
+}
@@ -0,0 +1,278 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Debug overlays for visualizing geometry.
+//!
+//! Besides the per-object overlays of [`Renderer::render_debug`], arbitrary line segments may be
+//! drawn with [`Renderer::draw_lines`]. [`grid`] and [`axes`] produce line segments for common
+//! reference geometry.
+
+use wgpu::{util::DeviceExt as _, *};
+
+use super::{Job, PipelineOptions, Renderer, INDEX_FORMAT};
+use crate::{CameraTransformsUniform, Color, Object, Point};
+
+/// The vertex attributes of the line-drawing pipeline: a position and a color.
+const LINE_VERTEX_ATTRIBUTES: [VertexAttribute; 2] =
+    vertex_attr_array![0 => Float32x3, 1 => Float32x4];
+
+/// Creates the line segments of a square grid on the XZ plane, centered on the origin.
+///
+/// Lines are spaced `step` apart and extend `extent` from the origin in each direction. The
+/// returned points are suitable for [`Renderer::draw_lines`]. No lines are produced if `step` is
+/// not positive.
+pub fn grid(extent: f32, step: f32) -> Vec<Point> {
+    if step.is_nan() || step <= 0. {
+        return Vec::new();
+    }
+
+    let line_count = (extent / step).floor().max(0.) as i32;
+    let mut points = Vec::with_capacity(4 * (2 * line_count as usize + 1));
+    for i in -line_count..=line_count {
+        let offset = (i as f32) * step;
+        // A line parallel to the Z axis...
+        points.push(Point { x: offset, y: 0., z: -extent });
+        points.push(Point { x: offset, y: 0., z: extent });
+        // ...and one parallel to the X axis.
+        points.push(Point { x: -extent, y: 0., z: offset });
+        points.push(Point { x: extent, y: 0., z: offset });
+    }
+
+    points
+}
+
+/// Creates line segments of the given length from the origin along the positive X, Y, and Z axes,
+/// in that order.
+///
+/// Each pair of points is one axis, so the axes may be drawn in different colors by passing each
+/// pair to a separate [`Renderer::draw_lines`] call.
+pub fn axes(length: f32) -> [Point; 6] {
+    [
+        Point::ORIGIN,
+        Point { x: length, y: 0., z: 0. },
+        Point::ORIGIN,
+        Point { x: 0., y: length, z: 0. },
+        Point::ORIGIN,
+        Point { x: 0., y: 0., z: length },
+    ]
+}
+
+/// The geometry visualizations drawn by [`Renderer::render_debug`].
+///
+/// The default overlay draws nothing.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DebugOverlay {
+    /// Whether or not to draw the edges of each triangle.
+    ///
+    /// This requires the [`POLYGON_MODE_LINE`](Features::POLYGON_MODE_LINE) feature, which may be
+    /// requested with [`RendererBuilder::features`](super::RendererBuilder::features). Without it,
+    /// wireframes are skipped, and a warning is logged the first time one is requested.
+    pub wireframe: bool,
+    /// Whether or not to draw the [normal](crate::MeshVertex::normal) of each vertex as a short
+    /// line segment protruding from the vertex.
+    pub normals: bool,
+}
+
+impl Renderer {
+    /// Draws the given debug overlay for each object on top of what `job` has rendered so far.
+    ///
+    /// The overlay is depth-tested against the existing contents of the depth texture, so it
+    /// should be drawn after the passes it annotates. Object pipelines and
+    /// [bind group slots](Object::bind_group_slots) are ignored.
+    pub fn render_debug<'a, O: Object + ?Sized + 'a>(
+        &'a self,
+        job: &mut Job<'a>,
+        camera: &CameraTransformsUniform,
+        objects: impl IntoIterator<Item = &'a O>,
+        overlay: DebugOverlay,
+    ) {
+        if !(overlay.wireframe || overlay.normals) {
+            return;
+        }
+
+        let wireframe_pipeline = if overlay.wireframe { self.wireframe_pipeline() } else { None };
+
+        let mut pass = job.begin_overlay_pass();
+        pass.set_bind_group(0, &camera.0.bind_group, &[]);
+
+        for object in objects.into_iter().filter(|object| object.visible()) {
+            pass.set_bind_group(
+                1,
+                &object.transforms_uniform().0.bind_group,
+                &[object.transforms_offset()],
+            );
+
+            if let Some(pipeline) = wireframe_pipeline {
+                pass.set_pipeline(pipeline);
+                pass.set_vertex_buffer(0, object.vertex_buffer());
+                pass.set_index_buffer(object.index_buffer(), INDEX_FORMAT);
+                pass.draw_indexed(0..(3 * object.triangle_count()), 0, 0..1);
+            }
+            if overlay.normals {
+                pass.set_pipeline(self.normals_pipeline());
+                // Each vertex is an instance of a two-vertex line segment.
+                pass.set_vertex_buffer(0, object.vertex_buffer());
+                pass.draw(0..2, 0..object.vertex_count());
+            }
+        }
+    }
+
+    /// Draws line segments in world space on top of what `job` has rendered so far.
+    ///
+    /// Each consecutive pair of `vertices` is one line segment; a trailing unpaired vertex is
+    /// ignored. Like [`render_debug`](Self::render_debug), the lines are depth-tested against the
+    /// existing contents of the depth texture. This uploads a scratch vertex buffer on every call
+    /// and so is intended for debugging rather than hot loops.
+    pub fn draw_lines(
+        &self,
+        job: &mut Job<'_>,
+        camera: &CameraTransformsUniform,
+        vertices: &[Point],
+        color: Color,
+    ) {
+        let vertex_count = vertices.len() - (vertices.len() % 2);
+        if vertex_count == 0 {
+            return;
+        }
+
+        let Color { r, g, b, a } = color;
+        let line_vertices: Vec<[f32; 7]> = vertices[..vertex_count]
+            .iter()
+            .map(|Point { x, y, z }| [*x, *y, *z, r, g, b, a])
+            .collect();
+        let vertex_buffer = self.log_validation_errors("line buffer creation", || {
+            self.device.create_buffer_init(&util::BufferInitDescriptor {
+                label: Some("Pylon scratch line vertex buffer"),
+                contents: bytemuck::cast_slice(&line_vertices),
+                usage: BufferUsages::VERTEX,
+            })
+        });
+
+        let mut pass = job.begin_overlay_pass();
+        pass.set_pipeline(self.lines_pipeline());
+        pass.set_bind_group(0, &camera.0.bind_group, &[]);
+        pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+        pass.draw(0..(vertex_count as u32), 0..1);
+    }
+
+    /// The pipeline that draws wireframes, which is created if it doesn't yet exist.
+    ///
+    /// This is `None` if the device lacks [`Features::POLYGON_MODE_LINE`], without which the
+    /// pipeline is invalid.
+    fn wireframe_pipeline(&self) -> Option<&RenderPipeline> {
+        self.wireframe_pipeline.get_or_init(|| {
+            if !self.device.features().contains(Features::POLYGON_MODE_LINE) {
+                tracing::warn!("wireframes require the POLYGON_MODE_LINE feature; skipping them");
+                return None;
+            }
+
+            Some(self.create_pipeline_with_modules(
+                &self.device.create_shader_module(include_wgsl!("../shaders/vertex.wgsl")),
+                Some(&self.device.create_shader_module(include_wgsl!("../shaders/wireframe.wgsl"))),
+                &[self.surface_format],
+                &PipelineOptions {
+                    depth_write_enabled: false,
+                    // Edges lie at exactly the depth of the faces they bound.
+                    depth_compare: CompareFunction::LessEqual,
+                    polygon_mode: PolygonMode::Line,
+                    ..Default::default()
+                },
+            ))
+        })
+        .as_ref()
+    }
+
+    /// The pipeline that draws vertex normals, which is created if it doesn't yet exist.
+    fn normals_pipeline(&self) -> &RenderPipeline {
+        self.normals_pipeline.get_or_init(|| {
+            let module = self.device.create_shader_module(include_wgsl!("../shaders/normals.wgsl"));
+
+            self.device.create_render_pipeline(&RenderPipelineDescriptor {
+                label: Some("Pylon normals pipeline"),
+                layout: Some(&self.create_pipeline_layout()),
+                vertex: VertexState {
+                    module: &module,
+                    entry_point: "vertex_main",
+                    // Each vertex in the buffer produces one line segment.
+                    buffers: &[Self::mesh_vertex_buffer_layout(VertexStepMode::Instance)],
+                },
+                fragment: Some(FragmentState {
+                    module: &module,
+                    entry_point: "fragment_main",
+                    targets: &[Some(ColorTargetState {
+                        format: self.surface_format,
+                        blend: None,
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                primitive: PrimitiveState {
+                    topology: PrimitiveTopology::LineList,
+                    ..Default::default()
+                },
+                depth_stencil: Some(DepthStencilState {
+                    format: self.depth_format,
+                    depth_write_enabled: false,
+                    depth_compare: CompareFunction::LessEqual,
+                    stencil: StencilState::default(),
+                    bias: DepthBiasState::default(),
+                }),
+                multisample: MultisampleState {
+                    count: self.sample_count,
+                    ..Default::default()
+                },
+                multiview: None,
+            })
+        })
+    }
+
+    /// The pipeline that draws line segments, which is created if it doesn't yet exist.
+    fn lines_pipeline(&self) -> &RenderPipeline {
+        self.lines_pipeline.get_or_init(|| {
+            let module = self.device.create_shader_module(include_wgsl!("../shaders/lines.wgsl"));
+            // Lines are already in world space, so only the camera bind group is used.
+            let layout = self.device.create_pipeline_layout(&PipelineLayoutDescriptor {
+                label: Some("Pylon lines pipeline layout"),
+                bind_group_layouts: &[&self.builtin_bind_group_layouts.for_camera],
+                push_constant_ranges: &[],
+            });
+
+            self.device.create_render_pipeline(&RenderPipelineDescriptor {
+                label: Some("Pylon lines pipeline"),
+                layout: Some(&layout),
+                vertex: VertexState {
+                    module: &module,
+                    entry_point: "vertex_main",
+                    buffers: &[VertexBufferLayout {
+                        array_stride: std::mem::size_of::<[f32; 7]>() as BufferAddress,
+                        step_mode: VertexStepMode::Vertex,
+                        attributes: &LINE_VERTEX_ATTRIBUTES,
+                    }],
+                },
+                fragment: Some(FragmentState {
+                    module: &module,
+                    entry_point: "fragment_main",
+                    targets: &[Some(ColorTargetState {
+                        format: self.surface_format,
+                        blend: Some(BlendState::ALPHA_BLENDING),
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                primitive: PrimitiveState {
+                    topology: PrimitiveTopology::LineList,
+                    ..Default::default()
+                },
+                depth_stencil: Some(DepthStencilState {
+                    format: self.depth_format,
+                    depth_write_enabled: false,
+                    depth_compare: CompareFunction::LessEqual,
+                    stencil: StencilState::default(),
+                    bias: DepthBiasState::default(),
+                }),
+                multisample: MultisampleState {
+                    count: self.sample_count,
+                    ..Default::default()
+                },
+                multiview: None,
+            })
+        })
+    }
+}
@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Depth-only rendering, as for shadow maps.
+
+use wgpu::{util::DeviceExt as _, *};
+
+use super::{PipelineOptions, PipelineSettings, Renderer, SurfaceSize, DEPTH_FORMAT, INDEX_FORMAT};
+use crate::{Matrix, Object};
+
+impl Renderer {
+    /// Renders the depth of the given objects, as seen through `light_view_proj`, into a new depth
+    /// texture.
+    ///
+    /// `light_view_proj` takes the place of the camera transformation matrix and is typically the
+    /// product of a light's projection and view matrices. Only depth is written; object fragment
+    /// shaders and [bind group slots](Object::bind_group_slots) are ignored.
+    ///
+    /// The returned texture may be bound as a shadow map in fragment shaders.
+    pub fn render_depth<'a, O: Object + ?Sized + 'a>(
+        &self,
+        light_view_proj: &Matrix,
+        size: SurfaceSize,
+        objects: impl IntoIterator<Item = &'a O>,
+    ) -> Texture {
+        let texture = self.device.create_texture(&TextureDescriptor {
+            label: Some("Pylon shadow map"),
+            size: Extent3d {
+                width: size.width,
+                height: size.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: DEPTH_FORMAT,
+            // The texture is useless if it can't be sampled afterwards.
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+        });
+        let view = texture.create_view(&TextureViewDescriptor {
+            label: Some("Pylon shadow map view"),
+            aspect: TextureAspect::DepthOnly,
+            ..Default::default()
+        });
+
+        let light_buffer = self.log_validation_errors("light buffer creation", || {
+            self.device.create_buffer_init(&util::BufferInitDescriptor {
+                label: Some("Pylon light transformation matrix buffer"),
+                contents: bytemuck::bytes_of(&light_view_proj.to_array()),
+                usage: BufferUsages::UNIFORM,
+            })
+        });
+        let light_uniform = self.create_camera_transforms_uniform(
+            light_buffer.as_entire_buffer_binding(),
+        );
+
+        let mut encoder = self.device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("Pylon depth-only command encoder"),
+        });
+        {
+            let mut pass = encoder.begin_render_pass(&RenderPassDescriptor {
+                label: Some("Pylon depth-only render pass"),
+                color_attachments: &[],
+                depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                    view: &view,
+                    depth_ops: Some(Operations {
+                        load: LoadOp::Clear(1.0),
+                        store: true,
+                    }),
+                    stencil_ops: None,
+                }),
+            });
+            pass.set_pipeline(self.depth_only_pipeline());
+            pass.set_bind_group(0, &light_uniform.0.bind_group, &[]);
+
+            for object in objects.into_iter().filter(|object| object.visible()) {
+                pass.set_bind_group(
+                    1,
+                    &object.transforms_uniform().0.bind_group,
+                    &[object.transforms_offset()],
+                );
+                pass.set_vertex_buffer(0, object.vertex_buffer());
+                pass.set_index_buffer(object.index_buffer(), INDEX_FORMAT);
+                pass.draw_indexed(0..(3 * object.triangle_count()), 0, 0..1);
+            }
+        }
+        self.queue.submit(Some(encoder.finish()));
+
+        texture
+    }
+
+    /// The pipeline used by [`render_depth`](Self::render_depth), which is created if it doesn't
+    /// yet exist.
+    fn depth_only_pipeline(&self) -> &RenderPipeline {
+        self.depth_only_pipeline.get_or_init(|| {
+            self.create_pipeline_with_settings(&PipelineSettings {
+                vertex_shader: &self.device.create_shader_module(include_wgsl!(
+                    "../shaders/vertex.wgsl"
+                )),
+                vertex_buffers: &[Self::mesh_vertex_buffer_layout(VertexStepMode::Vertex)],
+                fragment_shader: None,
+                color_formats: &[],
+                options: &PipelineOptions::default(),
+                // Shadow maps are never multisampled and have no stencil aspect.
+                depth_target: Some((1, DEPTH_FORMAT)),
+            })
+        })
+    }
+}
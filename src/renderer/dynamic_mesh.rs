@@ -0,0 +1,196 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Meshes whose geometry changes from frame to frame.
+
+use wgpu::{Buffer, BufferAddress, BufferDescriptor, BufferSlice, BufferUsages};
+
+use super::Renderer;
+use crate::{Mesh, MeshTriangle, MeshVertex};
+
+impl Renderer {
+    /// Uploads the given mesh into buffers that may be rewritten later.
+    ///
+    /// The buffers initially have exactly the capacity needed for `mesh`.
+    pub fn create_dynamic_mesh(&self, mesh: &Mesh) -> DynamicMesh {
+        let mut dynamic_mesh = self.create_dynamic_mesh_with_capacity(
+            mesh.vertex_pool().len(),
+            mesh.triangles().len(),
+        );
+        dynamic_mesh.update(self, mesh);
+
+        dynamic_mesh
+    }
+
+    /// Creates an empty [`DynamicMesh`] with room for the given numbers of vertices and triangles.
+    pub fn create_dynamic_mesh_with_capacity(
+        &self,
+        vertex_capacity: usize,
+        triangle_capacity: usize,
+    ) -> DynamicMesh {
+        // Buffer slices cannot be empty, so the buffers always have room for at least one element.
+        let vertex_capacity = vertex_capacity.max(1);
+        let triangle_capacity = triangle_capacity.max(1);
+
+        DynamicMesh {
+            vertex_buffer: self.create_dynamic_vertex_buffer(vertex_capacity),
+            index_buffer: self.create_dynamic_index_buffer(triangle_capacity),
+            vertex_count: 0,
+            triangle_count: 0,
+            vertex_capacity,
+            triangle_capacity,
+        }
+    }
+
+    fn create_dynamic_vertex_buffer(&self, capacity: usize) -> Buffer {
+        self.log_validation_errors("dynamic mesh buffer creation", || {
+            self.device.create_buffer(&BufferDescriptor {
+                label: Some("Pylon dynamic mesh vertex buffer"),
+                size: DynamicMesh::vertex_bytes(capacity),
+                usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        })
+    }
+
+    fn create_dynamic_index_buffer(&self, capacity: usize) -> Buffer {
+        self.log_validation_errors("dynamic mesh buffer creation", || {
+            self.device.create_buffer(&BufferDescriptor {
+                label: Some("Pylon dynamic mesh index buffer"),
+                size: DynamicMesh::triangle_bytes(capacity),
+                usage: BufferUsages::INDEX | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        })
+    }
+}
+
+/// A mesh in GPU buffers that are rewritten in place as its geometry changes.
+///
+/// Re-uploading a morphing mesh into fresh buffers every frame allocates GPU memory every frame. A
+/// `DynamicMesh` instead keeps vertex and index buffers with a fixed capacity and overwrites their
+/// contents with [`update_vertices`](Self::update_vertices) and
+/// [`update_triangles`](Self::update_triangles). A buffer is only reallocated when an update
+/// exceeds its capacity, in which case the capacity at least doubles so that a steadily growing
+/// mesh reallocates a logarithmic number of times. Capacity never shrinks.
+///
+/// Updates within capacity create no buffers of their own. Each write is still staged by *wgpu*,
+/// which maps a transient staging buffer per [`write_buffer`](wgpu::Queue::write_buffer) call, so
+/// this saves creating and binding new buffers rather than every allocation.
+///
+/// The methods of this type mirror the buffer methods of [`Object`](crate::Object), to which an
+/// `Object` implementation may delegate.
+///
+/// This type may be created via [`Renderer::create_dynamic_mesh`].
+#[derive(Debug)]
+pub struct DynamicMesh {
+    vertex_buffer: Buffer,
+    index_buffer: Buffer,
+    vertex_count: u32,
+    triangle_count: u32,
+    vertex_capacity: usize,
+    triangle_capacity: usize,
+}
+
+impl DynamicMesh {
+    /// Replaces both the vertices and the triangles of this mesh.
+    pub fn update(&mut self, renderer: &Renderer, mesh: &Mesh) {
+        self.update_vertices(renderer, mesh.vertex_pool());
+        self.update_triangles(renderer, mesh.triangles());
+    }
+
+    /// Replaces the vertices of this mesh.
+    ///
+    /// The vertex buffer is written in place if `vertices` fits within its capacity and is
+    /// otherwise reallocated with at least double the capacity.
+    pub fn update_vertices(&mut self, renderer: &Renderer, vertices: &[MeshVertex]) {
+        if vertices.len() > self.vertex_capacity {
+            self.vertex_capacity = Self::grow(self.vertex_capacity, vertices.len());
+            self.vertex_buffer = renderer.create_dynamic_vertex_buffer(self.vertex_capacity);
+        }
+        if !vertices.is_empty() {
+            renderer.queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(vertices));
+        }
+        self.vertex_count = vertices.len() as u32;
+    }
+
+    /// Replaces the triangles of this mesh.
+    ///
+    /// The index buffer is written in place if `triangles` fits within its capacity and is
+    /// otherwise reallocated with at least double the capacity.
+    pub fn update_triangles(&mut self, renderer: &Renderer, triangles: &[MeshTriangle]) {
+        if triangles.len() > self.triangle_capacity {
+            self.triangle_capacity = Self::grow(self.triangle_capacity, triangles.len());
+            self.index_buffer = renderer.create_dynamic_index_buffer(self.triangle_capacity);
+        }
+        if !triangles.is_empty() {
+            renderer.queue.write_buffer(&self.index_buffer, 0, bytemuck::cast_slice(triangles));
+        }
+        self.triangle_count = triangles.len() as u32;
+    }
+
+    /// The number of triangles most recently written to this mesh.
+    ///
+    /// This is suitable for [`Object::triangle_count`](crate::Object::triangle_count).
+    pub fn triangle_count(&self) -> u32 {
+        self.triangle_count
+    }
+
+    /// The number of vertices most recently written to this mesh.
+    ///
+    /// This is suitable for [`Object::vertex_count`](crate::Object::vertex_count).
+    pub fn vertex_count(&self) -> u32 {
+        self.vertex_count
+    }
+
+    /// The number of triangles the index buffer has room for.
+    pub fn triangle_capacity(&self) -> usize {
+        self.triangle_capacity
+    }
+
+    /// The number of vertices the vertex buffer has room for.
+    pub fn vertex_capacity(&self) -> usize {
+        self.vertex_capacity
+    }
+
+    /// The entire index buffer, including unused capacity.
+    ///
+    /// This is suitable for [`Object::index_buffer`](crate::Object::index_buffer); only the first
+    /// [`triangle_count`](Self::triangle_count) triangles are drawn.
+    pub fn index_buffer(&self) -> BufferSlice<'_> {
+        self.index_buffer.slice(..)
+    }
+
+    /// The size, in bytes, of [the index buffer](Self::index_buffer).
+    ///
+    /// This is suitable for [`Object::index_buffer_size`](crate::Object::index_buffer_size).
+    pub fn index_buffer_size(&self) -> BufferAddress {
+        Self::triangle_bytes(self.triangle_capacity)
+    }
+
+    /// The entire vertex buffer, including unused capacity.
+    ///
+    /// This is suitable for [`Object::vertex_buffer`](crate::Object::vertex_buffer).
+    pub fn vertex_buffer(&self) -> BufferSlice<'_> {
+        self.vertex_buffer.slice(..)
+    }
+
+    /// The size, in bytes, of [the vertex buffer](Self::vertex_buffer).
+    ///
+    /// This is suitable for [`Object::vertex_buffer_size`](crate::Object::vertex_buffer_size).
+    pub fn vertex_buffer_size(&self) -> BufferAddress {
+        Self::vertex_bytes(self.vertex_capacity)
+    }
+
+    /// The capacity after growing from `capacity` to hold at least `required` elements.
+    fn grow(capacity: usize, required: usize) -> usize {
+        required.max(capacity.saturating_mul(2))
+    }
+
+    fn vertex_bytes(count: usize) -> BufferAddress {
+        (count * std::mem::size_of::<MeshVertex>()) as BufferAddress
+    }
+
+    fn triangle_bytes(count: usize) -> BufferAddress {
+        (count * std::mem::size_of::<MeshTriangle>()) as BufferAddress
+    }
+}
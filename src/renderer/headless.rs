@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Headless rendering, as for automated tests.
+
+use wgpu::*;
+
+use super::{Error, RenderTarget, Renderer, SurfaceSize};
+
+/// The number of bytes per pixel of the surface format, which is 8-bit BGRA either way.
+const BYTES_PER_PIXEL: u32 = 4;
+
+impl Renderer {
+    /// Creates a new `Renderer` that renders to an owned texture rather than a window surface.
+    ///
+    /// No window is needed, so this is suitable for automated tests in CI, where a software
+    /// adapter like LLVMpipe may be selected with [`Backends::GL`] or [`Backends::VULKAN`]. The
+    /// rendered pixels may be read back with [`read_pixels`](Self::read_pixels).
+    ///
+    /// Jobs created by a headless renderer are not presented; [`Job::submit`](super::Job::submit)
+    /// only submits their commands.
+    pub async fn new_headless(
+        backends: Backends,
+        size: SurfaceSize,
+        features: Features,
+    ) -> Result<Self, Error> {
+        Self::builder(size)
+            .backends(backends)
+            .features(features)
+            .build_headless()
+            .await
+    }
+
+    /// Whether or not this renderer renders to an owned texture rather than a window surface.
+    pub fn is_headless(&self) -> bool {
+        matches!(self.target, RenderTarget::Texture(_))
+    }
+
+    /// Creates the texture that a headless renderer renders to.
+    pub(super) fn create_target_texture(
+        device: &Device,
+        size: SurfaceSize,
+        format: TextureFormat,
+    ) -> Texture {
+        device.create_texture(&TextureDescriptor {
+            label: Some("Pylon headless target texture"),
+            size: Extent3d {
+                width: size.width,
+                height: size.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format,
+            // The texture must be copyable so that its pixels can be read back.
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+        })
+    }
+
+    /// Reads back the pixels of a headless renderer's target texture.
+    ///
+    /// Pixels are returned in row-major order, starting at the top-left corner, as four bytes each
+    /// in the blue, green, red, alpha order of the [surface format](Self::surface_format). This
+    /// blocks until all submitted jobs have finished.
+    ///
+    /// `None` is returned if this renderer is not [headless](Self::is_headless).
+    pub fn read_pixels(&self) -> Option<Vec<u8>> {
+        let texture = match self.target {
+            RenderTarget::Texture(ref texture) => texture,
+            RenderTarget::Surface(_) => return None,
+        };
+
+        let SurfaceSize { width, height } = self.surface_size;
+        let unpadded_bytes_per_row = width * BYTES_PER_PIXEL;
+        // Rows in texture-to-buffer copies must be aligned.
+        let padded_bytes_per_row = {
+            let alignment = COPY_BYTES_PER_ROW_ALIGNMENT;
+            unpadded_bytes_per_row.div_ceil(alignment) * alignment
+        };
+
+        let buffer = self.device.create_buffer(&BufferDescriptor {
+            label: Some("Pylon headless readback buffer"),
+            size: BufferAddress::from(padded_bytes_per_row * height),
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("Pylon headless readback command encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            ImageCopyBuffer {
+                buffer: &buffer,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(padded_bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        slice.map_async(MapMode::Read, |result| {
+            result.expect("failed to map headless readback buffer");
+        });
+        self.device.poll(Maintain::Wait);
+
+        let padded = slice.get_mapped_range();
+        let pixels = padded
+            .chunks_exact(padded_bytes_per_row as usize)
+            .flat_map(|row| &row[..(unpadded_bytes_per_row as usize)])
+            .copied()
+            .collect();
+
+        Some(pixels)
+    }
+}
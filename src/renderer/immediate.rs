@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Immediate-mode drawing without [objects](crate::Object).
+
+use wgpu::{util::DeviceExt as _, *};
+
+use super::{Job, Renderer, INDEX_FORMAT};
+use crate::{CameraTransformsUniform, Matrix, Mesh, ObjectTransforms};
+
+impl Renderer {
+    /// Draws `mesh`, transformed by `transform`, on top of what `job` has rendered so far.
+    ///
+    /// This is a shortcut for prototyping that sidesteps the [`Object`](crate::Object) trait:
+    /// vertex, index, and uniform buffers are created from scratch for every call and dropped
+    /// afterwards. As such, it is much slower than drawing an object and shouldn't be used in hot
+    /// loops.
+    ///
+    /// `transform` takes the place of the object transformation matrix, and `pipeline` is
+    /// typically created with [`create_pipeline`](Self::create_pipeline).
+    pub fn draw_mesh(
+        &self,
+        job: &mut Job<'_>,
+        camera: &CameraTransformsUniform,
+        mesh: &Mesh,
+        transform: &Matrix,
+        pipeline: &RenderPipeline,
+    ) {
+        // Empty buffers can't be sliced, and there would be nothing to draw anyway.
+        if mesh.triangles.is_empty() {
+            return;
+        }
+
+        let vertex_buffer = self.log_validation_errors("scratch buffer creation", || {
+            self.device.create_buffer_init(&util::BufferInitDescriptor {
+                label: Some("Pylon scratch vertex buffer"),
+                contents: bytemuck::cast_slice(&mesh.vertex_pool),
+                usage: BufferUsages::VERTEX,
+            })
+        });
+        let index_buffer = self.log_validation_errors("scratch buffer creation", || {
+            self.device.create_buffer_init(&util::BufferInitDescriptor {
+                label: Some("Pylon scratch index buffer"),
+                contents: bytemuck::cast_slice(&mesh.triangles),
+                usage: BufferUsages::INDEX,
+            })
+        });
+        let transform_buffer = self.log_validation_errors("scratch buffer creation", || {
+            self.device.create_buffer_init(&util::BufferInitDescriptor {
+                label: Some("Pylon scratch object transforms buffer"),
+                contents: bytemuck::bytes_of(&ObjectTransforms::new(transform)),
+                usage: BufferUsages::UNIFORM,
+            })
+        });
+        let transforms_uniform = self.create_object_transforms_uniform(
+            transform_buffer.as_entire_buffer_binding(),
+        );
+
+        let mut pass = job.begin_overlay_pass();
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &camera.0.bind_group, &[]);
+        pass.set_bind_group(1, &transforms_uniform.0.bind_group, &[0]);
+        pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+        pass.set_index_buffer(index_buffer.slice(..), INDEX_FORMAT);
+        pass.draw_indexed(0..(3 * mesh.triangles.len() as u32), 0, 0..1);
+    }
+}
@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Drawing many instances of an object that differ in transform and color.
+
+use wgpu::{
+    include_wgsl,
+    util::{BufferInitDescriptor, DeviceExt as _},
+    vertex_attr_array,
+    Buffer,
+    BufferAddress,
+    BufferUsages,
+    RenderPipeline,
+    ShaderSource,
+    VertexAttribute,
+    VertexBufferLayout,
+    VertexStepMode,
+};
+
+use super::{render::Pass, PipelineError, PipelineOptions, PipelineSettings, RenderError, Renderer};
+use crate::{Color, Matrix, Object};
+
+/// The vertex attributes of [`ObjectInstance`], in order of field.
+const INSTANCE_ATTRIBUTES: [VertexAttribute; 5] = vertex_attr_array![
+    // The columns of the transformation matrix.
+    4 => Float32x4,
+    5 => Float32x4,
+    6 => Float32x4,
+    7 => Float32x4,
+    // The color.
+    8 => Float32x4,
+];
+
+/// The layout of [instance buffers](InstanceBuffer), which contain [`ObjectInstance`]s.
+///
+/// Instanced pipelines read this layout from buffer 1, after the
+/// [mesh vertex buffer](super::VERTEX_BUFFER_LAYOUT), which occupies locations 0 through 3.
+/// Locations 4 through 7 receive the columns of the instance's transformation matrix and location
+/// 8 receives its color, each as a `vec4<f32>`; these locations are reserved for instancing.
+pub const INSTANCE_BUFFER_LAYOUT: VertexBufferLayout<'static> = VertexBufferLayout {
+    array_stride: std::mem::size_of::<ObjectInstance>() as BufferAddress,
+    step_mode: VertexStepMode::Instance,
+    attributes: &INSTANCE_ATTRIBUTES,
+};
+
+/// The attributes of one instance within an [`InstanceBuffer`].
+///
+/// This is 80 bytes: a 4x4 matrix of `f32`, in column-major order, followed by an RGBA color. The
+/// built-in instanced vertex shader declares the same layout.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct ObjectInstance {
+    /// The matrix that transforms points of the instance from mesh space to the space of the
+    /// object, before the object's own transformation matrix is applied.
+    pub transformation_matrix: [[f32; 4]; 4],
+    /// The linear RGBA color of the instance.
+    pub color: [f32; 4],
+}
+
+unsafe impl bytemuck::Pod for ObjectInstance {}
+unsafe impl bytemuck::Zeroable for ObjectInstance {}
+
+impl ObjectInstance {
+    /// Creates a new `ObjectInstance` from a transformation matrix and a color.
+    pub fn new(transformation_matrix: &Matrix, color: Color) -> Self {
+        Self {
+            transformation_matrix: transformation_matrix.to_array(),
+            color: [color.r, color.g, color.b, color.a],
+        }
+    }
+}
+
+/// A vertex buffer of [`ObjectInstance`]s, as created by
+/// [`Renderer::create_instance_buffer`].
+#[derive(Debug)]
+pub struct InstanceBuffer {
+    buffer: Buffer,
+    len: u32,
+}
+
+impl InstanceBuffer {
+    /// The buffer of [`ObjectInstance`]s.
+    ///
+    /// The buffer is never empty, as buffer slices cannot be; it has room for at least one
+    /// instance even if [`len`](Self::len) is 0.
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    /// The number of instances.
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// Whether or not there are no instances.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Renderer {
+    /// Uploads an instance for each transformation matrix and color in `instances`.
+    ///
+    /// The buffer may be rewritten with [`Queue::write_buffer`](wgpu::Queue::write_buffer) to move
+    /// or recolor instances, as long as the number of instances doesn't grow.
+    ///
+    /// # Panics
+    ///
+    /// This panics if there are more than [`u32::MAX`] instances.
+    pub fn create_instance_buffer(&self, instances: &[(Matrix, Color)]) -> InstanceBuffer {
+        let len = u32::try_from(instances.len()).expect("too many instances");
+        let mut contents: Vec<ObjectInstance> = instances
+            .iter()
+            .map(|(matrix, color)| ObjectInstance::new(matrix, *color))
+            .collect();
+        // Buffer slices cannot be empty.
+        if contents.is_empty() {
+            contents.push(bytemuck::Zeroable::zeroed());
+        }
+
+        InstanceBuffer {
+            buffer: self.log_validation_errors("instance buffer creation", || {
+                self.device.create_buffer_init(&BufferInitDescriptor {
+                    label: Some("Pylon instance buffer"),
+                    contents: bytemuck::cast_slice(&contents),
+                    usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+                })
+            }),
+            len,
+        }
+    }
+
+    /// Creates a render pipeline for instances of [an object](Object) with the built-in instanced
+    /// vertex shader.
+    ///
+    /// The vertex shader at `src/shaders/instanced.wgsl` applies the transformation matrix of each
+    /// instance before that of the object and passes the world-space normal to the fragment shader
+    /// at `@location(0)` and the instance's color, a `vec4<f32>`, at `@location(1)`. The instance
+    /// matrix is assumed to scale uniformly; under non-uniform scale, normals are skewed.
+    ///
+    /// Objects using the returned pipeline must be drawn with
+    /// [`Pass::draw_object_instanced`].
+    pub async fn create_instanced_pipeline(
+        &self,
+        fragment_source: ShaderSource<'_>,
+        options: &PipelineOptions,
+    ) -> Result<RenderPipeline, PipelineError> {
+        self.capture_pipeline_errors(|| {
+            self.create_pipeline_with_settings(&PipelineSettings {
+                vertex_shader: &self.device.create_shader_module(include_wgsl!(
+                    "../shaders/instanced.wgsl"
+                )),
+                vertex_buffers: &[
+                    Self::mesh_vertex_buffer_layout(VertexStepMode::Vertex),
+                    INSTANCE_BUFFER_LAYOUT,
+                ],
+                fragment_shader: Some(&self.create_fragment_module(fragment_source)),
+                color_formats: &[self.surface_format],
+                options,
+                depth_target: None,
+            })
+        })
+        .await
+    }
+}
+
+impl<'a> Pass<'a> {
+    /// Draws an instance of the given object for each instance in `instances`.
+    ///
+    /// The object's [pipeline](Object::render_pipeline) must have been created by
+    /// [`Renderer::create_instanced_pipeline`]. Apart from instancing, this is the same as
+    /// [`draw_object`](Self::draw_object); nothing is drawn if `instances` is empty.
+    pub fn draw_object_instanced(
+        &mut self,
+        object: &'a (impl Object + ?Sized),
+        instances: &'a InstanceBuffer,
+    ) -> Result<&mut Self, RenderError> {
+        if instances.is_empty() {
+            return Ok(self);
+        }
+
+        self.render_pass.set_vertex_buffer(1, instances.buffer.slice(..));
+        self.draw_object_instances(object, 0, instances.len)
+    }
+}
@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Sharing render pipelines between identical requests.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use wgpu::{
+    BlendState,
+    CompareFunction,
+    PolygonMode,
+    PrimitiveTopology,
+    RenderPipeline,
+    ShaderSource,
+    StencilState,
+};
+
+use super::{PipelineError, PipelineOptions, Renderer};
+
+/// The render pipelines created by [`Renderer::create_pipeline_cached`], by the requests that
+/// created them.
+#[derive(Debug, Default)]
+pub(super) struct PipelineCache {
+    pipelines: Mutex<HashMap<PipelineCacheKey, Arc<RenderPipeline>>>,
+}
+
+/// A request for a pipeline with the built-in vertex shader.
+#[derive(Debug, Eq, Hash, PartialEq)]
+struct PipelineCacheKey {
+    /// The WGSL source of the fragment shader.
+    fragment_source: String,
+    blend: Option<BlendState>,
+    depth_write_enabled: bool,
+    depth_compare: CompareFunction,
+    topology: PrimitiveTopology,
+    polygon_mode: PolygonMode,
+    stencil: StencilState,
+    /// The constant, slope scale, and clamp of the depth bias, with floats compared by their bits.
+    depth_bias: (i32, u32, u32),
+}
+
+impl PipelineCacheKey {
+    fn new(fragment_source: &str, options: &PipelineOptions) -> Self {
+        Self {
+            fragment_source: fragment_source.to_owned(),
+            blend: options.blend,
+            depth_write_enabled: options.depth_write_enabled,
+            depth_compare: options.depth_compare,
+            topology: options.topology,
+            polygon_mode: options.polygon_mode,
+            stencil: options.stencil.clone(),
+            depth_bias: (
+                options.depth_bias.constant,
+                options.depth_bias.slope_scale.to_bits(),
+                options.depth_bias.clamp.to_bits(),
+            ),
+        }
+    }
+}
+
+impl Renderer {
+    /// Creates a render pipeline for [an object](crate::Object) with the given options, or returns
+    /// the pipeline created by an earlier identical request.
+    ///
+    /// Requests are identical if they have the same fragment shader source and options.
+    /// Compiling a shader and creating a pipeline are slow, so objects that share a fragment
+    /// shader should share a pipeline, too; the pipeline is returned in an [`Arc`] for that
+    /// reason. Apart from caching, this is the same as
+    /// [`create_pipeline_with_options`](Self::create_pipeline_with_options).
+    ///
+    /// Only WGSL sources are cached; pipelines for other sources are created anew each time.
+    /// Failed requests are not cached either. The cache is unbounded: pipelines are never evicted
+    /// and stay alive for as long as the renderer unless the cache is
+    /// [cleared](Self::clear_pipeline_cache).
+    pub async fn create_pipeline_cached(
+        &self,
+        fragment_source: ShaderSource<'_>,
+        options: &PipelineOptions,
+    ) -> Result<Arc<RenderPipeline>, PipelineError> {
+        let key = match &fragment_source {
+            ShaderSource::Wgsl(source) => PipelineCacheKey::new(source, options),
+            _ => {
+                return self
+                    .create_pipeline_with_options(fragment_source, options)
+                    .await
+                    .map(Arc::new);
+            }
+        };
+        if let Some(pipeline) = self.pipeline_cache.pipelines.lock().unwrap().get(&key) {
+            return Ok(Arc::clone(pipeline));
+        }
+
+        // The lock isn't held while the pipeline is created, so concurrent identical requests may
+        // each create a pipeline; only the first to finish is cached and returned to both.
+        let pipeline = self.create_pipeline_with_options(fragment_source, options).await?;
+        let mut pipelines = self.pipeline_cache.pipelines.lock().unwrap();
+
+        Ok(Arc::clone(pipelines.entry(key).or_insert_with(|| Arc::new(pipeline))))
+    }
+
+    /// The number of pipelines in the cache of
+    /// [`create_pipeline_cached`](Self::create_pipeline_cached).
+    pub fn cached_pipeline_count(&self) -> usize {
+        self.pipeline_cache.pipelines.lock().unwrap().len()
+    }
+
+    /// Empties the cache of [`create_pipeline_cached`](Self::create_pipeline_cached).
+    ///
+    /// Pipelines that are still shared elsewhere stay alive until their last [`Arc`] is dropped,
+    /// but later requests create new pipelines.
+    pub fn clear_pipeline_cache(&self) {
+        self.pipeline_cache.pipelines.lock().unwrap().clear();
+    }
+}
@@ -1,18 +1,80 @@
+use std::ops::AddAssign;
+
+use super::{
+    timing::{Timestamps, TimestampsClaim},
+    validation,
+    RenderError,
+    RenderTarget,
+    INDEX_FORMAT,
+};
+use crate::{
+    BindGroupSlot,
+    CameraTransformsUniform,
+    Color,
+    MeshTriangle,
+    MeshVertex,
+    Object,
+    ObjectTransformsUniform,
+};
+
+/// The settings of a [`Renderer`](super::Renderer) with which each of its [jobs](Job) is
+/// created.
+pub(super) struct JobSettings<'a> {
+    /// The view of the renderer's depth texture.
+    pub(super) depth_view: &'a wgpu::TextureView,
+    /// Whether or not the depth texture has a stencil aspect.
+    pub(super) has_stencil: bool,
+    /// The multisampled color texture that is resolved to the frame, if multisampling is enabled.
+    pub(super) multisampled_frame: Option<&'a wgpu::Texture>,
+    /// The color that the frame is cleared to before the first pass, if any.
+    pub(super) clear_color: Option<Color>,
+    /// The resources for timing jobs, if timing is enabled.
+    pub(super) timestamps: Option<&'a Timestamps>,
+    /// Whether or not *wgpu* validation errors raised upon submission are logged.
+    pub(super) log_validation_errors: bool,
+}
+
 impl<'a> Job<'a> {
     pub(super) fn new(
-        surface: &wgpu::Surface,
-        depth: &wgpu::Texture,
-        device: &wgpu::Device,
+        target: &RenderTarget,
+        settings: &JobSettings<'a>,
+        device: &'a wgpu::Device,
         queue: &'a wgpu::Queue,
     ) -> Self {
-        let frame = surface.get_current_texture().unwrap();
+        let (frame, frame_view) = match target {
+            RenderTarget::Surface(surface) => {
+                let frame = tracing::trace_span!("acquire_frame")
+                    .in_scope(|| surface.get_current_texture().unwrap());
+                let frame_view = Self::create_frame_view(&frame.texture);
+
+                (Some(frame), frame_view)
+            }
+            RenderTarget::Texture(texture) => (None, Self::create_frame_view(texture)),
+        };
+        let mut encoder = Self::create_command_encoder(device);
+        // Only one job may be timed per readback.
+        let timestamps = settings.timestamps.and_then(Timestamps::try_claim);
+        if let Some(timestamps) = &timestamps {
+            timestamps.write_begin(&mut encoder);
+        }
 
         Job {
-            frame_view: Self::create_frame_view(&frame.texture),
             frame,
-            depth_view: Self::create_depth_view(depth),
-            encoder: Self::create_command_encoder(device),
-            queue: &queue,
+            frame_view,
+            multisampled_frame_view: settings
+                .multisampled_frame
+                .map(Self::create_multisampled_frame_view),
+            depth_view: settings.depth_view,
+            has_stencil: settings.has_stencil,
+            pending_clear_color: settings.clear_color,
+            encoder,
+            depth_is_cleared: false,
+            counts: RenderStats::default(),
+            max_bind_groups: device.limits().max_bind_groups,
+            timestamps,
+            log_validation_errors: settings.log_validation_errors,
+            device,
+            queue,
         }
     }
 
@@ -25,11 +87,12 @@ impl<'a> Job<'a> {
         )
     }
 
-    fn create_depth_view(depth: &wgpu::Texture) -> wgpu::TextureView {
+    /// Creates a texture view for the multisampled color texture.
+    fn create_multisampled_frame_view(texture: &wgpu::Texture) -> wgpu::TextureView {
         Self::create_texture_view(
-            depth,
-            "Pylon depth view",
-            wgpu::TextureAspect::DepthOnly,
+            texture,
+            "Pylon multisampled frame view",
+            wgpu::TextureAspect::All,
         )
     }
 
@@ -52,6 +115,13 @@ impl<'a> Job<'a> {
         })
     }
 
+    /// Creates the command encoder for a job.
+    ///
+    /// Encoders can't be pooled by the renderer: [`wgpu::CommandEncoder::finish`] consumes the
+    /// encoder, so there is nothing left to reclaim after [`submit`](Job::submit). *wgpu* already
+    /// recycles the backend command encoders, and with them their command memory, once the GPU is
+    /// done with them. What remains per encoder is *wgpu*'s own bookkeeping, a handful of small
+    /// host allocations that a pool on this side of the API could not avoid.
     fn create_command_encoder(device: &wgpu::Device) -> wgpu::CommandEncoder {
         device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("Pylon command encoder")
@@ -60,93 +130,741 @@ impl<'a> Job<'a> {
 }
 
 pub struct Job<'a> {
-    frame: wgpu::SurfaceTexture,
+    /// The surface frame to be presented upon submission.
+    ///
+    /// This is `None` for headless renderers, which render to their own texture.
+    frame: Option<wgpu::SurfaceTexture>,
     frame_view: wgpu::TextureView,
-    depth_view: wgpu::TextureView,
+    /// The view of the multisampled color texture, if multisampling is enabled.
+    ///
+    /// When present, rendering targets this view, which is then resolved to
+    /// [`frame_view`](Self::frame_view).
+    multisampled_frame_view: Option<wgpu::TextureView>,
+    /// The view of the renderer's depth texture, which outlives the job.
+    depth_view: &'a wgpu::TextureView,
+    /// Whether or not the depth texture includes a stencil aspect, which is cleared and loaded
+    /// along with depth.
+    has_stencil: bool,
+    /// The color that the next pass to the surface frame should clear it to.
+    ///
+    /// This is taken by the first such pass so that later passes don't clear what earlier passes
+    /// rendered.
+    pending_clear_color: Option<Color>,
+    /// Whether or not a pass in this job has cleared the depth texture.
+    depth_is_cleared: bool,
+    /// What the passes of this job have drawn so far.
+    counts: RenderStats,
     encoder: wgpu::CommandEncoder,
+    /// The number of bind group slots supported by the device.
+    max_bind_groups: u32,
+    /// The claim of this job on the resources for timing it, if it is timed.
+    timestamps: Option<TimestampsClaim<'a>>,
+    /// Whether or not *wgpu* validation errors raised upon submission are logged.
+    log_validation_errors: bool,
+    device: &'a wgpu::Device,
     queue: &'a wgpu::Queue,
 }
 
 impl Job<'_> {
-    pub fn add_pass<'this>(&'this mut self, camera: CameraTransformsUniform) -> Pass<'this> {
-        Pass(self.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("Pylon surface frame render pass"),
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &self.frame_view,
+    /// Adds a pass to the surface frame.
+    ///
+    /// The first pass of the job clears color to the renderer's
+    /// [clear color](super::Renderer::clear_color), and every pass clears depth. No camera is bound
+    /// yet; bind one with [`Pass::with_camera`] before drawing, as in
+    /// `job.add_pass().with_camera(camera).draw_object(&object)?`.
+    pub fn add_pass(&mut self) -> Pass<'_> {
+        self.begin_pass_with_loads(None, DepthLoad::Clear(DepthLoad::FAR), StoreOps::default())
+    }
+
+    /// Adds a pass to the surface frame that initializes the frame and the depth texture as given.
+    ///
+    /// [`add_pass`](Self::add_pass) is equivalent to clearing color to the renderer's
+    /// [clear color](super::Renderer::clear_color) in the first pass of the job, loading color in
+    /// later passes, and always clearing depth to [`DepthLoad::FAR`]. Multi-pass techniques may
+    /// instead load depth to keep earlier geometry, or clear color without clearing depth.
+    ///
+    /// Either way, only the first pass of a job clears to the renderer's clear color. Loading
+    /// depth before any pass of the job has cleared it yields the previous frame's depth.
+    pub fn add_pass_with_loads(&mut self, color: ColorLoad, depth: DepthLoad) -> Pass<'_> {
+        self.begin_pass_with_loads(Some(color), depth, StoreOps::default())
+    }
+
+    /// Adds a pass to the surface frame that initializes the frame and the depth texture as given
+    /// and stores only the attachments selected by `store`.
+    ///
+    /// This is otherwise the same as [`add_pass_with_loads`](Self::add_pass_with_loads), which
+    /// stores both.
+    pub fn add_pass_with_ops(
+        &mut self,
+        color: ColorLoad,
+        depth: DepthLoad,
+        store: StoreOps,
+    ) -> Pass<'_> {
+        self.begin_pass_with_loads(Some(color), depth, store)
+    }
+
+    /// Begins a pass to the surface frame, loading or clearing color according to
+    /// [`take_frame_load`](Self::take_frame_load) if `color` is `None`.
+    pub(super) fn begin_pass_with_loads(
+        &mut self,
+        color: Option<ColorLoad>,
+        depth: DepthLoad,
+        store: StoreOps,
+    ) -> Pass<'_> {
+        // The renderer's clear color applies only to the first pass, even if it is overridden.
+        let default_frame_load = self.take_frame_load();
+        let frame_load = match color {
+            Some(ColorLoad::Clear(color)) => wgpu::LoadOp::Clear(color.to_wgpu()),
+            Some(ColorLoad::Load) => wgpu::LoadOp::Load,
+            None => default_frame_load,
+        };
+        let depth_load = match depth {
+            DepthLoad::Clear(depth) => {
+                self.depth_is_cleared = true;
+                wgpu::LoadOp::Clear(depth)
+            }
+            DepthLoad::Load => wgpu::LoadOp::Load,
+        };
+        let render_pass = Self::begin_render_pass(
+            &mut self.encoder,
+            "Pylon surface frame render pass",
+            &[Some(Self::frame_attachment(
+                &self.frame_view,
+                self.multisampled_frame_view.as_ref(),
+                frame_load,
+                store.color,
+            ))],
+            self.depth_view,
+            self.has_stencil,
+            depth_load,
+            store.depth,
+        );
+
+        Pass::new(render_pass, self.max_bind_groups, &mut self.counts)
+    }
+
+    /// Adds a pass that renders to the given color attachments rather than to the surface frame.
+    ///
+    /// This is intended for multiple render targets (MRT), as for the G-buffer of a deferred
+    /// renderer. Objects drawn in this pass must use pipelines created by
+    /// [`Renderer::create_pipeline_with_targets`](super::Renderer::create_pipeline_with_targets)
+    /// with the formats of `targets`, in order.
+    ///
+    /// Each target is cleared to transparent black. As the depth texture is shared with surface
+    /// passes, every target must be the size of the surface and have the renderer's sample count.
+    pub fn add_pass_with_targets<'this>(
+        &'this mut self,
+        targets: &'this [wgpu::TextureView],
+    ) -> Pass<'this> {
+        self.depth_is_cleared = true;
+        let color_attachments: Vec<Option<wgpu::RenderPassColorAttachment>> = targets
+            .iter()
+            .map(|view| Some(wgpu::RenderPassColorAttachment {
+                view,
                 resolve_target: None,
                 ops: wgpu::Operations {
-                    // We can either clear or load here. Clearing wipes the frame with a given color
-                    // while loading initializes the frame with the current state of the surface.
-                    load: wgpu::LoadOp::Load,
-                    // The surface frame contains the final result of the render, so obviously we
-                    // need to write to it.
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
                     store: true,
                 },
-            })],
+            }))
+            .collect();
+        let render_pass = Self::begin_render_pass(
+            &mut self.encoder,
+            "Pylon multiple render target pass",
+            &color_attachments,
+            self.depth_view,
+            self.has_stencil,
+            wgpu::LoadOp::Clear(1.0),
+            true,
+        );
+
+        Pass::new(render_pass, self.max_bind_groups, &mut self.counts)
+    }
+
+    /// Begins a render pass that writes only depth, clearing the depth texture first.
+    ///
+    /// The pass has no color attachments, so it must be drawn with a pipeline that has no color
+    /// targets.
+    pub(super) fn begin_depth_prepass(&mut self) -> wgpu::RenderPass<'_> {
+        self.depth_is_cleared = true;
+
+        Self::begin_render_pass(
+            &mut self.encoder,
+            "Pylon depth pre-pass",
+            &[],
+            self.depth_view,
+            self.has_stencil,
+            wgpu::LoadOp::Clear(DepthLoad::FAR),
+            true,
+        )
+    }
+
+    /// Begins a render pass that draws on top of everything previously rendered in this job.
+    ///
+    /// Unlike [`add_pass`](Self::add_pass), the depth texture is loaded rather than cleared so
+    /// that the overlay is occluded by existing geometry. If no pass has cleared the depth texture
+    /// yet, this pass clears it instead, as it would otherwise contain the previous frame's depth.
+    pub(super) fn begin_overlay_pass(&mut self) -> wgpu::RenderPass<'_> {
+        let depth_load = if self.depth_is_cleared {
+            wgpu::LoadOp::Load
+        } else {
+            wgpu::LoadOp::Clear(1.0)
+        };
+        self.depth_is_cleared = true;
+        let frame_load = self.take_frame_load();
+
+        Self::begin_render_pass(
+            &mut self.encoder,
+            "Pylon overlay render pass",
+            &[Some(Self::frame_attachment(
+                &self.frame_view,
+                self.multisampled_frame_view.as_ref(),
+                frame_load,
+                true,
+            ))],
+            self.depth_view,
+            self.has_stencil,
+            depth_load,
+            true,
+        )
+    }
+
+    /// How the next pass to the surface frame should initialize it.
+    ///
+    /// We can either clear or load here. Clearing wipes the frame with a given color while loading
+    /// initializes the frame with the current state of the surface. Only the first pass clears, and
+    /// only if the renderer has a [clear color](super::Renderer::clear_color).
+    fn take_frame_load(&mut self) -> wgpu::LoadOp<wgpu::Color> {
+        match self.pending_clear_color.take() {
+            Some(color) => wgpu::LoadOp::Clear(color.to_wgpu()),
+            None => wgpu::LoadOp::Load,
+        }
+    }
+
+    /// The color attachment for the surface frame.
+    ///
+    /// `store` is typically `true`, as the surface frame contains the final result of the render.
+    fn frame_attachment<'this>(
+        frame_view: &'this wgpu::TextureView,
+        multisampled_frame_view: Option<&'this wgpu::TextureView>,
+        load: wgpu::LoadOp<wgpu::Color>,
+        store: bool,
+    ) -> wgpu::RenderPassColorAttachment<'this> {
+        // With multisampling, we render to the multisampled texture and resolve it to the frame.
+        let (view, resolve_target) = match multisampled_frame_view {
+            Some(view) => (view, Some(frame_view)),
+            None => (frame_view, None),
+        };
+
+        wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target,
+            ops: wgpu::Operations { load, store },
+        }
+    }
+
+    fn begin_render_pass<'this>(
+        encoder: &'this mut wgpu::CommandEncoder,
+        label: &str,
+        color_attachments: &[Option<wgpu::RenderPassColorAttachment<'this>>],
+        depth_view: &'this wgpu::TextureView,
+        has_stencil: bool,
+        depth_load: wgpu::LoadOp<f32>,
+        depth_store: bool,
+    ) -> wgpu::RenderPass<'this> {
+        // The stencil buffer is cleared whenever depth is so that both start each job fresh.
+        let stencil_ops = has_stencil.then_some(wgpu::Operations {
+            load: match depth_load {
+                wgpu::LoadOp::Clear(_) => wgpu::LoadOp::Clear(0),
+                wgpu::LoadOp::Load => wgpu::LoadOp::Load,
+            },
+            store: depth_store,
+        });
+
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some(label),
+            color_attachments,
             depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                view: &self.depth_view,
+                view: depth_view,
                 depth_ops: Some(wgpu::Operations {
-                    // In clip space, 1.0 is the maximmum depth.
-                    load: wgpu::LoadOp::Clear(1.0),
-                    store: true,
+                    load: depth_load,
+                    store: depth_store,
                 }),
-                stencil_ops: None,
+                stencil_ops,
             }),
-        }))
+        })
+    }
+
+    /// What the passes of this job have drawn so far.
+    ///
+    /// A pass's draws are added when the pass ends, so this should be called after the last pass
+    /// is dropped and before the job is [submitted](Self::submit).
+    pub fn stats(&self) -> RenderStats {
+        self.counts
+    }
+
+    /// Submits the commands of this job to the GPU and presents the surface frame, if any.
+    ///
+    /// If no pass has rendered to the surface frame and the renderer has a
+    /// [clear color](super::Renderer::clear_color), the frame is cleared to it first, so an empty
+    /// job presents a cleared frame rather than whatever the frame held before.
+    ///
+    /// This returns as soon as the commands are queued, typically before the GPU finishes them.
+    /// The returned index may be passed to
+    /// [`Renderer::wait_for_submission`](super::Renderer::wait_for_submission) to block until it
+    /// does.
+    pub fn submit(mut self) -> wgpu::SubmissionIndex {
+        if self.pending_clear_color.is_some() {
+            // The pass clears the frame as it begins and ends as soon as it is dropped.
+            self.add_pass();
+        }
+        if let Some(timestamps) = &self.timestamps {
+            timestamps.write_end(&mut self.encoder);
+        }
+        let index = tracing::debug_span!(
+            "submit",
+            objects = self.counts.objects,
+            triangles = self.counts.triangles,
+        )
+        .in_scope(|| {
+            // Errors in recorded passes are raised when the encoder is finished.
+            validation::log_validation_errors(
+                self.device,
+                self.log_validation_errors,
+                "job submission",
+                || self.queue.submit(Some(self.encoder.finish())),
+            )
+        });
+        if let Some(timestamps) = self.timestamps.take() {
+            timestamps.start_readback();
+        }
+        if let Some(frame) = self.frame {
+            frame.present();
+        }
+
+        index
     }
 
-    pub fn submit(self) {
-        self.queue.submit(Some(self.encoder.finish()));
-        self.frame.present();
+    /// [Submits](Self::submit) this job and then blocks until the GPU has finished it.
+    ///
+    /// This is intended for frame-perfect captures, benchmarks, and deterministic screenshot
+    /// tests. It serializes the CPU to the GPU, so the CPU can't prepare the next frame while the
+    /// GPU renders this one; it should not be used every frame of a real-time loop.
+    pub fn submit_and_wait(self) {
+        let device = self.device;
+        let index = self.submit();
+        device.poll(wgpu::Maintain::WaitForSubmissionIndex(index));
     }
 }
 
-pub struct Pass<'a>(wgpu::RenderPass<'a>);
+/// How a pass initializes the color of the surface frame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorLoad {
+    /// Clear the frame to the given color.
+    Clear(Color),
+    /// Keep what earlier passes rendered.
+    Load,
+}
 
-impl Pass<'_> {
-    pub fn with_camera(self, camera: CameraTransformsUniform) {
-        self.0.set_bind_group(
-            0,
-            &camera.0.bind_group,
-            &[],
-        );
+/// How a pass initializes the depth texture.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DepthLoad {
+    /// Clear the depth texture to the given depth, which is typically [`FAR`](Self::FAR).
+    ///
+    /// If the renderer has a stencil buffer, it is cleared to 0 as well.
+    Clear(f32),
+    /// Keep the depth, and any stencil values, that earlier passes wrote.
+    Load,
+}
+
+impl DepthLoad {
+    /// The depth of the far clipping plane, which is the maximum depth in clip space.
+    pub const FAR: f32 = 1.0;
+}
+
+/// Which attachments a pass writes back to memory when it ends.
+///
+/// Both are stored by default. An attachment that isn't stored is discarded, which saves memory
+/// bandwidth on tiled GPUs, such as those of mobile devices, when no later pass reads it. Its
+/// contents are undefined afterward, so the next pass must clear it rather than load it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StoreOps {
+    /// Whether or not the surface frame is stored.
+    ///
+    /// With multisampling, this applies to the multisampled color texture; the frame is still
+    /// resolved. Without multisampling, the last pass of a job must store the frame, or there is
+    /// nothing to present.
+    pub color: bool,
+    /// Whether or not the depth texture, along with any stencil values, is stored.
+    pub depth: bool,
+}
+
+impl Default for StoreOps {
+    fn default() -> Self {
+        Self { color: true, depth: true }
+    }
+}
+
+/// Running totals of what a pass or job has drawn, for profiling and tests.
+///
+/// Stats reflect geometry submitted to the GPU, not necessarily rendered: triangles that are
+/// culled, clipped, or occluded are still counted. Only draws made through [`Pass`] are counted.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RenderStats {
+    /// The number of objects drawn.
+    pub objects: u32,
+    /// The number of triangles submitted.
+    pub triangles: u32,
+    /// The number of draw calls issued.
+    pub draw_calls: u32,
+    /// The number of times the pipeline was set.
+    ///
+    /// Setting the pipeline that is already set isn't counted.
+    pub pipeline_switches: u32,
+}
+
+impl AddAssign for RenderStats {
+    fn add_assign(&mut self, other: Self) {
+        self.objects += other.objects;
+        self.triangles += other.triangles;
+        self.draw_calls += other.draw_calls;
+        self.pipeline_switches += other.pipeline_switches;
+    }
+}
+
+pub struct Pass<'a> {
+    pub(super) render_pass: wgpu::RenderPass<'a>,
+    /// The number of bind group slots supported by the device.
+    max_bind_groups: u32,
+    /// The pipeline that was most recently set, if any.
+    ///
+    /// Setting the same pipeline twice in a row is redundant, so we skip it.
+    pipeline: Option<&'a wgpu::RenderPipeline>,
+    /// The span covering the encoding of this pass.
+    ///
+    /// Its `objects` and `triangles` fields are recorded when the pass ends.
+    span: tracing::Span,
+    /// What this pass has drawn so far.
+    counts: RenderStats,
+    /// What the job that owns this pass has drawn, to which [`counts`](Self::counts) is added when
+    /// the pass ends.
+    job_counts: &'a mut RenderStats,
+}
+
+impl Drop for Pass<'_> {
+    fn drop(&mut self) {
+        self.span.record("objects", self.counts.objects);
+        self.span.record("triangles", self.counts.triangles);
+        *self.job_counts += self.counts;
+    }
+}
+
+impl<'a> Pass<'a> {
+    fn new(
+        render_pass: wgpu::RenderPass<'a>,
+        max_bind_groups: u32,
+        job_counts: &'a mut RenderStats,
+    ) -> Self {
+        Self {
+            render_pass,
+            max_bind_groups,
+            pipeline: None,
+            span: tracing::debug_span!(
+                "encode_pass",
+                objects = tracing::field::Empty,
+                triangles = tracing::field::Empty,
+            ),
+            counts: RenderStats::default(),
+            job_counts,
+        }
+    }
+
+    /// What this pass has drawn so far.
+    pub fn stats(&self) -> RenderStats {
+        self.counts
+    }
+
+    /// Binds the given camera uniform for the draws that follow and returns this pass.
+    ///
+    /// This is the chainable form of [`set_camera`](Self::set_camera).
+    pub fn with_camera(mut self, camera: &'a CameraTransformsUniform) -> Self {
+        self.set_camera(camera);
 
         self
     }
 
-    pub fn draw_object<'a>(
-        &self,
-        pipeline: &wgpu::RenderPipeline,
-        bind_group_slots: &[BindGroupSlot<'a>],
-        transforms_uniform: &ObjectTransformsUniform,
-        vertex_buffer: wgpu::BufferSlice,
-        index_buffer: wgpu::BufferSlice,
-    ) {
+    /// Binds the given camera uniform for the draws that follow.
+    ///
+    /// A pass may switch cameras between draws, as when rendering several viewports.
+    pub fn set_camera(&mut self, camera: &'a CameraTransformsUniform) {
+        self.render_pass.set_bind_group(0, &camera.0.bind_group, &[]);
+    }
+
+    /// Restricts the draws that follow to the given region of the target, in pixels.
+    ///
+    /// Clip space is mapped onto this region rather than onto the whole target, so geometry is
+    /// scaled to fit. The viewport covers the whole target at the start of each pass.
+    pub fn set_viewport(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.render_pass.set_viewport(x, y, width, height, 0., 1.);
+    }
+
+    /// Discards fragments of the draws that follow that lie outside the given region of the
+    /// target, in pixels.
+    ///
+    /// Unlike [`set_viewport`](Self::set_viewport), this doesn't scale geometry. The region must
+    /// lie within the target. The scissor rectangle covers the whole target at the start of each
+    /// pass.
+    pub fn set_scissor_rect(&mut self, x: u32, y: u32, width: u32, height: u32) {
+        self.render_pass.set_scissor_rect(x, y, width, height);
+    }
+
+    /// Sets the reference value that the [stencil test](super::PipelineOptions::stencil) compares
+    /// against and that [`StencilOperation::Replace`](wgpu::StencilOperation::Replace) writes.
+    ///
+    /// The reference value is 0 at the start of each pass.
+    pub fn set_stencil_reference(&mut self, reference: u32) {
+        self.render_pass.set_stencil_reference(reference);
+    }
+
+    /// Draws the given object.
+    ///
+    /// `3 * triangle_count` indices are drawn from the object's index buffer. For triangle lists,
+    /// this is exactly one [`MeshTriangle`](crate::MeshTriangle) per triangle; for other
+    /// topologies, the same number of indices is drawn but interpreted according to the topology
+    /// of [the object's pipeline](Object::render_pipeline).
+    ///
+    /// Nothing is drawn if the object is not [visible](Object::visible) or if any of its
+    /// [bind group slots](Object::bind_group_slots) is invalid.
+    ///
+    /// This pass is returned so that draws may be chained.
+    pub fn draw_object(
+        &mut self,
+        object: &'a (impl Object + ?Sized),
+    ) -> Result<&mut Self, RenderError> {
+        self.draw_object_instances(object, 0, 1)
+    }
+
+    /// Draws the given object with `base_vertex` added to each of its indices.
+    ///
+    /// This is otherwise the same as [`draw_object`](Self::draw_object). It lets objects share
+    /// both buffers: each object's [vertex buffer](Object::vertex_buffer) may be the same slice of
+    /// a buffer of many meshes' vertices, and its indices, which are relative to its own first
+    /// vertex, are offset to that vertex by `base_vertex`. The offset vertices must lie within the
+    /// vertex buffer slice.
+    pub fn draw_object_with_base_vertex(
+        &mut self,
+        object: &'a (impl Object + ?Sized),
+        base_vertex: i32,
+    ) -> Result<&mut Self, RenderError> {
+        self.draw_object_instances(object, base_vertex, 1)
+    }
+
+    /// Draws `instance_count` instances of the given object with `base_vertex` added to each of
+    /// its indices, as for [`draw_object_with_base_vertex`](Self::draw_object_with_base_vertex).
+    ///
+    /// The triangles of every instance are [recorded](RenderStats::triangles), but the instances
+    /// count as one object.
+    pub(super) fn draw_object_instances(
+        &mut self,
+        object: &'a (impl Object + ?Sized),
+        base_vertex: i32,
+        instance_count: u32,
+    ) -> Result<&mut Self, RenderError> {
+        if !object.visible() {
+            return Ok(self);
+        }
+
+        let slots = object.bind_group_slots();
+        self.check_bind_group_slots(slots)?;
+
         let triangle_count = object.triangle_count();
+        #[cfg(debug_assertions)]
+        Self::check_buffer_sizes(object, triangle_count);
+        self.record_draw(triangle_count.saturating_mul(instance_count));
 
-        tracing::debug!("Rendering {} triangles...", triangle_count);
+        self.set_pipeline(object.render_pipeline());
+        self.set_object_bind_groups(
+            object.transforms_uniform(),
+            object.transforms_offset(),
+            slots,
+        );
+        self.render_pass.set_vertex_buffer(0, object.vertex_buffer());
+        self.render_pass.set_index_buffer(object.index_buffer(), INDEX_FORMAT);
 
-        self.0.set_pipeline(pipeline);
-        self.0.set_bind_group(
-            1,
-            &object.transforms_uniform().0.bind_group,
-            &[],
+        let index_count = 3 * triangle_count;
+        self.render_pass.draw_indexed(0..index_count, base_vertex, 0..instance_count);
+
+        Ok(self)
+    }
+
+    /// Draws each vertex of the given object as a point, as for point clouds.
+    ///
+    /// The object's [pipeline](Object::render_pipeline) must have the
+    /// [`PointList`](wgpu::PrimitiveTopology::PointList) topology, as with
+    /// [`PipelineOptions::points`](super::PipelineOptions::points). All
+    /// [`vertex_count`](Object::vertex_count) vertices are drawn in order, and the object's
+    /// [index buffer](Object::index_buffer) is ignored. Points are always one pixel in size.
+    ///
+    /// Nothing is drawn if the object is not [visible](Object::visible) or if any of its
+    /// [bind group slots](Object::bind_group_slots) is invalid.
+    pub fn draw_points(
+        &mut self,
+        object: &'a (impl Object + ?Sized),
+    ) -> Result<&mut Self, RenderError> {
+        if !object.visible() {
+            return Ok(self);
+        }
+
+        let slots = object.bind_group_slots();
+        self.check_bind_group_slots(slots)?;
+        // Points aren't triangles.
+        self.record_draw(0);
+
+        self.set_pipeline(object.render_pipeline());
+        self.set_object_bind_groups(
+            object.transforms_uniform(),
+            object.transforms_offset(),
+            slots,
         );
-        for slot in bind_group_slots {
+        self.render_pass.set_vertex_buffer(0, object.vertex_buffer());
+        self.render_pass.draw(0..object.vertex_count(), 0..1);
+
+        Ok(self)
+    }
+
+    /// Draws the given objects, grouped by [pipeline](Object::render_pipeline).
+    ///
+    /// Switching pipelines is one of the more expensive GPU state changes, so this draws all
+    /// objects that share a pipeline before switching to the next. Pipelines are compared by
+    /// identity, not by contents. Within each group, objects are drawn in iteration order.
+    ///
+    /// For a scene of *N* objects using *P* distinct pipelines, [`draw_object`](Self::draw_object)
+    /// in iteration order sets the pipeline up to *N* times if the pipelines are interleaved,
+    /// whereas this sets it exactly *P* times.
+    pub fn draw_objects_batched<O: Object + ?Sized + 'a>(
+        &mut self,
+        objects: impl IntoIterator<Item = &'a O>,
+    ) -> Result<&mut Self, RenderError> {
+        let mut objects: Vec<&'a O> = objects.into_iter().collect();
+        // This sort is stable, so iteration order is preserved within each group.
+        objects.sort_by_key(|object| object.render_pipeline() as *const wgpu::RenderPipeline);
+
+        for object in objects {
+            self.draw_object(object)?;
+        }
+
+        Ok(self)
+    }
+
+    /// Draws the given objects of possibly different types, grouped by
+    /// [pipeline](Object::render_pipeline).
+    ///
+    /// This is [`draw_objects_batched`](Self::draw_objects_batched) for a heterogeneous list of
+    /// trait objects. When every object has the same concrete type, the generic method should be
+    /// preferred, as it avoids dynamic dispatch.
+    pub fn draw_objects_dyn(
+        &mut self,
+        objects: &[&'a dyn Object],
+    ) -> Result<&mut Self, RenderError> {
+        self.draw_objects_batched(objects.iter().copied())
+    }
+
+    /// Panics if the buffers of the given object don't match the index format and vertex stride
+    /// that Pylon's pipelines assume.
+    ///
+    /// Mismatched buffers otherwise produce garbage geometry or even hang the GPU, with no
+    /// diagnostic.
+    #[cfg(debug_assertions)]
+    fn check_buffer_sizes(object: &(impl Object + ?Sized), triangle_count: u32) {
+        let triangle_size = std::mem::size_of::<MeshTriangle>() as wgpu::BufferAddress;
+        let vertex_size = std::mem::size_of::<MeshVertex>() as wgpu::BufferAddress;
+
+        if let Some(size) = object.index_buffer_size() {
+            assert!(
+                size % triangle_size == 0,
+                "index buffer size ({} bytes) is not a multiple of the size of a triangle of \
+                 {:?} indices ({} bytes)",
+                size,
+                INDEX_FORMAT,
+                triangle_size,
+            );
+            assert!(
+                size >= triangle_size * wgpu::BufferAddress::from(triangle_count),
+                "index buffer size ({} bytes) is too small for the {} triangles reported by \
+                 `Object::triangle_count`",
+                size,
+                triangle_count,
+            );
+        }
+        if let Some(size) = object.vertex_buffer_size() {
+            assert!(
+                size % vertex_size == 0,
+                "vertex buffer size ({} bytes) is not a multiple of the vertex stride, \
+                 `size_of::<MeshVertex>()` ({} bytes)",
+                size,
+                vertex_size,
+            );
+        }
+    }
+
+    /// Checks that none of the given slots is reserved or beyond those supported by the device.
+    pub(super) fn check_bind_group_slots(
+        &self,
+        slots: &[BindGroupSlot],
+    ) -> Result<(), RenderError> {
+        for slot in slots {
             if slot.index < 2 {
-                panic!("slots 0 and 1 cannot be overwritten");
+                return Err(RenderError::ReservedBindGroupSlot { index: slot.index });
+            }
+            if slot.index >= self.max_bind_groups {
+                return Err(RenderError::BindGroupSlotOutOfRange {
+                    index: slot.index,
+                    max_bind_groups: self.max_bind_groups,
+                });
             }
+        }
+
+        Ok(())
+    }
 
-            self.0.set_bind_group(
+    /// Assigns the transforms uniform of an object to slot 1 and its bind group slots to theirs.
+    pub(super) fn set_object_bind_groups(
+        &mut self,
+        transforms_uniform: &'a ObjectTransformsUniform,
+        transforms_offset: u32,
+        slots: &[BindGroupSlot<'a>],
+    ) {
+        self.render_pass.set_bind_group(
+            1,
+            &transforms_uniform.0.bind_group,
+            &[transforms_offset],
+        );
+        for slot in slots {
+            self.render_pass.set_bind_group(
                 slot.index,
                 slot.bind_group,
                 &[],
             );
         }
-        self.0.set_vertex_buffer(0, vertex_buffer);
-        self.0.set_index_buffer(index_buffer, wgpu::IndexFormat::Uint32);
+    }
+
+    /// Counts an object of the given number of triangles as drawn by this pass.
+    pub(super) fn record_draw(&mut self, triangle_count: u32) {
+        let _guard = self.span.enter();
+        tracing::debug!("Rendering {} triangles...", triangle_count);
+        self.counts.objects += 1;
+        self.counts.triangles += triangle_count;
+        self.counts.draw_calls += 1;
+    }
+
+    /// Sets the current pipeline unless it is already set.
+    pub(super) fn set_pipeline(&mut self, pipeline: &'a wgpu::RenderPipeline) {
+        if self.pipeline.is_some_and(|current| std::ptr::eq(current, pipeline)) {
+            return;
+        }
 
-        let index_count = (3 * triangle_count) as u32;
-        self.0.draw_indexed(0..index_count, 0, 0..1);
+        self.render_pass.set_pipeline(pipeline);
+        self.pipeline = Some(pipeline);
+        self.counts.pipeline_switches += 1;
     }
 }
@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Texture sampler creation.
+
+use std::{fmt, num::NonZeroU8};
+
+use wgpu::*;
+
+use super::Renderer;
+
+/// The largest anisotropy level that *wgpu* accepts.
+const MAX_ANISOTROPY: u8 = 16;
+
+/// Options for [sampler creation](Renderer::create_sampler).
+///
+/// The [default](Self::default) options are those of [`linear`](Self::linear).
+#[derive(Clone, Copy, Debug)]
+pub struct SamplerOptions {
+    /// How texels are filtered when a texture is magnified.
+    pub mag_filter: FilterMode,
+    /// How texels are filtered when a texture is minified.
+    pub min_filter: FilterMode,
+    /// How texels are filtered between mipmap levels.
+    pub mipmap_filter: FilterMode,
+    /// How texture coordinates outside `[0, 1]` are resolved, in all dimensions.
+    pub address_mode: AddressMode,
+    /// The maximum number of samples taken along the axis of anisotropy.
+    ///
+    /// This must be 1, 2, 4, 8, or 16. Values other than 1 enable anisotropic filtering, which
+    /// requires every filter to be [`Linear`](FilterMode::Linear). Adapters that don't support
+    /// anisotropic filtering silently ignore this.
+    pub anisotropy: u8,
+}
+
+impl Default for SamplerOptions {
+    fn default() -> Self {
+        Self::linear()
+    }
+}
+
+impl SamplerOptions {
+    /// Options for a sampler that filters linearly within and between mipmap levels and repeats
+    /// textures, without anisotropic filtering.
+    pub fn linear() -> Self {
+        Self {
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            mipmap_filter: FilterMode::Linear,
+            address_mode: AddressMode::Repeat,
+            anisotropy: 1,
+        }
+    }
+
+    /// Options for a sampler that picks the nearest texel, as for pixel art, and repeats textures.
+    pub fn nearest() -> Self {
+        Self {
+            mag_filter: FilterMode::Nearest,
+            min_filter: FilterMode::Nearest,
+            mipmap_filter: FilterMode::Nearest,
+            ..Self::linear()
+        }
+    }
+
+    /// These options with the given [anisotropy level](Self::anisotropy).
+    pub fn with_anisotropy(self, anisotropy: u8) -> Self {
+        Self { anisotropy, ..self }
+    }
+}
+
+/// The cause of a failure during [sampler creation](Renderer::create_sampler).
+#[derive(Debug)]
+pub enum SamplerError {
+    /// The [anisotropy level](SamplerOptions::anisotropy) was not a power of two between 1 and
+    /// 16.
+    InvalidAnisotropy {
+        /// The offending anisotropy level.
+        anisotropy: u8,
+    },
+    /// Anisotropic filtering was requested with a filter other than
+    /// [`Linear`](FilterMode::Linear).
+    AnisotropyRequiresLinearFiltering,
+}
+
+impl fmt::Display for SamplerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidAnisotropy { anisotropy } => {
+                write!(
+                    f,
+                    "anisotropy level {} is invalid; it must be 1, 2, 4, 8, or 16",
+                    anisotropy,
+                )
+            }
+            Self::AnisotropyRequiresLinearFiltering => {
+                write!(f, "anisotropic filtering requires linear mag, min, and mipmap filters")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SamplerError {}
+
+impl Renderer {
+    /// Creates a texture sampler with the given options.
+    ///
+    /// The options are validated here rather than by *wgpu*, which would otherwise report invalid
+    /// anisotropy to the uncaptured error handler.
+    pub fn create_sampler(&self, options: SamplerOptions) -> Result<Sampler, SamplerError> {
+        let SamplerOptions { mag_filter, min_filter, mipmap_filter, address_mode, anisotropy } =
+            options;
+
+        if !anisotropy.is_power_of_two() || anisotropy > MAX_ANISOTROPY {
+            return Err(SamplerError::InvalidAnisotropy { anisotropy });
+        }
+        let is_linear = [mag_filter, min_filter, mipmap_filter]
+            .iter()
+            .all(|filter| *filter == FilterMode::Linear);
+        if anisotropy > 1 && !is_linear {
+            return Err(SamplerError::AnisotropyRequiresLinearFiltering);
+        }
+
+        Ok(self.device.create_sampler(&SamplerDescriptor {
+            label: Some("Pylon sampler"),
+            address_mode_u: address_mode,
+            address_mode_v: address_mode,
+            address_mode_w: address_mode,
+            mag_filter,
+            min_filter,
+            mipmap_filter,
+            // An anisotropy level of 1 is the same as none.
+            anisotropy_clamp: NonZeroU8::new(anisotropy).filter(|level| level.get() > 1),
+            ..Default::default()
+        }))
+    }
+}
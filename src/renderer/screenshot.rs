@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Saving frames to image files.
+
+use std::{fmt, path::Path};
+
+use super::{Renderer, SurfaceSize};
+
+/// The cause of a failure while [saving a frame](Renderer::save_frame_png).
+#[derive(Debug)]
+pub enum ScreenshotError {
+    /// The renderer renders to a window surface, whose frames can't be read back.
+    ///
+    /// Only [headless](Renderer::new_headless) renderers support screenshots.
+    NotHeadless,
+    /// The PNG could not be encoded or written.
+    Image(image::ImageError),
+}
+
+impl fmt::Display for ScreenshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotHeadless => write!(f, "only headless renderers can save frames"),
+            Self::Image(e) => write!(f, "failed to save frame: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ScreenshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::NotHeadless => None,
+            Self::Image(e) => Some(e),
+        }
+    }
+}
+
+impl From<image::ImageError> for ScreenshotError {
+    fn from(e: image::ImageError) -> Self {
+        Self::Image(e)
+    }
+}
+
+impl Renderer {
+    /// Saves the frame of a [headless](Self::new_headless) renderer to a PNG file at `path`.
+    ///
+    /// The frame is [read back](Self::read_pixels) from the GPU, which blocks until all submitted
+    /// jobs have finished. As the frame is already sRGB-encoded, which is what PNG viewers assume,
+    /// its colors are saved unchanged.
+    pub fn save_frame_png(&self, path: &Path) -> Result<(), ScreenshotError> {
+        let mut pixels = self.read_pixels().ok_or(ScreenshotError::NotHeadless)?;
+        // The frame is BGRA, but PNG expects RGBA.
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+
+        let SurfaceSize { width, height } = self.surface_size;
+        image::save_buffer_with_format(
+            path,
+            &pixels,
+            width,
+            height,
+            image::ColorType::Rgba8,
+            image::ImageFormat::Png,
+        )?;
+
+        Ok(())
+    }
+}
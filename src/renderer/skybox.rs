@@ -0,0 +1,239 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Drawing a cubemap behind the scene.
+
+use std::num::NonZeroU32;
+
+use wgpu::{util::DeviceExt as _, *};
+
+use super::{Job, Renderer};
+use crate::{Camera, CameraResources, Vector};
+
+/// The GPU resources shared by every [skybox](Renderer::render_skybox).
+#[derive(Debug)]
+pub(super) struct SkyboxResources {
+    /// The layout of the bind group of the camera matrix, cubemap, and sampler.
+    bind_group_layout: BindGroupLayout,
+    pipeline: RenderPipeline,
+    /// The sampler of every cubemap, which filters linearly and clamps to the edges of each face
+    /// so that no seams appear between faces.
+    sampler: Sampler,
+}
+
+impl Renderer {
+    /// Creates a cubemap for [`render_skybox`](Self::render_skybox) from six square faces.
+    ///
+    /// Each face is `face_length` by `face_length` texels of 8-bit sRGB-encoded RGBA, in
+    /// row-major order from the top-left corner. The faces are given in the order of *wgpu*
+    /// cubemap layers: the faces seen looking along the positive X, negative X, positive Y,
+    /// negative Y, positive Z, and negative Z axes of world space. As Pylon's positive Y axis
+    /// points to the bottom of the viewport, the positive Y face is the ground and the negative Y
+    /// face is the sky; see the [crate docs](crate#handedness).
+    ///
+    /// # Panics
+    ///
+    /// This panics if a face is not `4 * face_length * face_length` bytes long.
+    pub fn create_cubemap(&self, face_length: u32, faces: [&[u8]; 6]) -> TextureView {
+        let bytes_per_row = 4 * face_length;
+        let texture = self.device.create_texture(&TextureDescriptor {
+            label: Some("Pylon cubemap"),
+            size: Extent3d {
+                width: face_length,
+                height: face_length,
+                depth_or_array_layers: 6,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+        });
+
+        for (layer, face) in (0..).zip(faces) {
+            assert_eq!(
+                face.len(),
+                (bytes_per_row * face_length) as usize,
+                "cubemap face {} has the wrong length",
+                layer,
+            );
+            self.queue.write_texture(
+                ImageCopyTexture {
+                    texture: &texture,
+                    mip_level: 0,
+                    origin: Origin3d { x: 0, y: 0, z: layer },
+                    aspect: TextureAspect::All,
+                },
+                face,
+                ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: NonZeroU32::new(bytes_per_row),
+                    rows_per_image: None,
+                },
+                Extent3d {
+                    width: face_length,
+                    height: face_length,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+
+        texture.create_view(&TextureViewDescriptor {
+            label: Some("Pylon cubemap view"),
+            dimension: Some(TextureViewDimension::Cube),
+            ..Default::default()
+        })
+    }
+
+    /// Draws the given cubemap behind what `job` has rendered so far, as seen by `camera`.
+    ///
+    /// `cubemap` must be a [cube view](TextureViewDimension::Cube) of a texture with a filterable
+    /// float format, as created by [`create_cubemap`](Self::create_cubemap). Each pixel samples
+    /// the cubemap in the direction from the camera through that pixel; the camera's position is
+    /// ignored, so the sky seems infinitely far away.
+    ///
+    /// The sky is drawn on the far clipping plane and depth-tested against the existing contents
+    /// of the depth texture without writing depth, so it only covers pixels where nothing has been
+    /// drawn. It should be drawn after the passes of the scene; drawing it first also works, as
+    /// later passes [clear depth](Job::add_pass) without clearing color. Like
+    /// [`draw_lines`](Self::draw_lines), this uploads a scratch uniform buffer on every call.
+    ///
+    /// Nothing is drawn if the camera's [transformation matrix](Camera::transformation_matrix) is
+    /// not invertible.
+    pub fn render_skybox<R: CameraResources>(
+        &self,
+        job: &mut Job<'_>,
+        camera: &Camera<R>,
+        cubemap: &TextureView,
+    ) {
+        // Only the direction of view matters, so the camera's translation is removed.
+        let mut rotation = camera.view_matrix();
+        rotation.columns_mut()[3] = Vector::new(0., 0., 0., 1.);
+        let inverse = match (camera.projection_matrix() * rotation).inverse() {
+            Some(inverse) => inverse,
+            None => return,
+        };
+
+        let resources = self.skybox_resources();
+        let matrix_buffer = self.log_validation_errors("skybox buffer creation", || {
+            self.device.create_buffer_init(&util::BufferInitDescriptor {
+                label: Some("Pylon scratch skybox matrix buffer"),
+                contents: bytemuck::bytes_of(&inverse.to_array()),
+                usage: BufferUsages::UNIFORM,
+            })
+        });
+        let bind_group = self.device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Pylon skybox bind group"),
+            layout: &resources.bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: matrix_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(cubemap),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::Sampler(&resources.sampler),
+                },
+            ],
+        });
+
+        let mut pass = job.begin_overlay_pass();
+        pass.set_pipeline(&resources.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        // One triangle covers the viewport.
+        pass.draw(0..3, 0..1);
+    }
+
+    /// The resources used by [`render_skybox`](Self::render_skybox), which are created if they
+    /// don't yet exist.
+    fn skybox_resources(&self) -> &SkyboxResources {
+        self.skybox_resources.get_or_init(|| {
+            let module = self.device.create_shader_module(include_wgsl!("../shaders/skybox.wgsl"));
+            let bind_group_layout = self.device.create_bind_group_layout(
+                &BindGroupLayoutDescriptor {
+                    label: Some("Pylon skybox bind group layout"),
+                    entries: &[
+                        BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: ShaderStages::VERTEX,
+                            ty: BindingType::Buffer {
+                                ty: BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                        BindGroupLayoutEntry {
+                            binding: 1,
+                            visibility: ShaderStages::FRAGMENT,
+                            ty: BindingType::Texture {
+                                sample_type: TextureSampleType::Float { filterable: true },
+                                view_dimension: TextureViewDimension::Cube,
+                                multisampled: false,
+                            },
+                            count: None,
+                        },
+                        BindGroupLayoutEntry {
+                            binding: 2,
+                            visibility: ShaderStages::FRAGMENT,
+                            ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                            count: None,
+                        },
+                    ],
+                },
+            );
+            let layout = self.device.create_pipeline_layout(&PipelineLayoutDescriptor {
+                label: Some("Pylon skybox pipeline layout"),
+                bind_group_layouts: &[&bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+            let pipeline = self.device.create_render_pipeline(&RenderPipelineDescriptor {
+                label: Some("Pylon skybox pipeline"),
+                layout: Some(&layout),
+                vertex: VertexState {
+                    module: &module,
+                    entry_point: "vertex_main",
+                    buffers: &[],
+                },
+                fragment: Some(FragmentState {
+                    module: &module,
+                    entry_point: "fragment_main",
+                    targets: &[Some(ColorTargetState {
+                        format: self.surface_format,
+                        blend: None,
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                primitive: PrimitiveState::default(),
+                depth_stencil: Some(DepthStencilState {
+                    format: self.depth_format,
+                    depth_write_enabled: false,
+                    // The sky lies exactly on the far plane, where the depth texture is cleared.
+                    depth_compare: CompareFunction::LessEqual,
+                    stencil: StencilState::default(),
+                    bias: DepthBiasState::default(),
+                }),
+                multisample: MultisampleState {
+                    count: self.sample_count,
+                    ..Default::default()
+                },
+                multiview: None,
+            });
+            let sampler = self.device.create_sampler(&SamplerDescriptor {
+                label: Some("Pylon skybox sampler"),
+                address_mode_u: AddressMode::ClampToEdge,
+                address_mode_v: AddressMode::ClampToEdge,
+                address_mode_w: AddressMode::ClampToEdge,
+                mag_filter: FilterMode::Linear,
+                min_filter: FilterMode::Linear,
+                ..Default::default()
+            });
+
+            SkyboxResources { bind_group_layout, pipeline, sampler }
+        })
+    }
+}
@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! GPU frame timing via timestamp queries.
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc,
+        Mutex,
+    },
+};
+
+use wgpu::*;
+
+use super::Renderer;
+
+/// No job holds the timestamps and no readback is in flight; the next job may claim them.
+const IDLE: u8 = 0;
+/// A live job has [claimed](Timestamps::try_claim) the timestamps and is recording.
+const RECORDING: u8 = 1;
+/// The readback buffer has been submitted for mapping but is not yet mapped.
+const MAPPING: u8 = 2;
+/// The readback buffer is mapped and contains the timestamps of the most recent timed job.
+const MAPPED: u8 = 3;
+
+/// The size, in bytes, of the begin and end timestamps.
+const TIMESTAMPS_SIZE: BufferAddress = 2 * std::mem::size_of::<u64>() as BufferAddress;
+
+/// The resources for timing [jobs](super::Job) on the GPU.
+///
+/// Timestamps are read back asynchronously. A job is only timed if it
+/// [claims](Self::try_claim) the timestamps, which fails while another job holds them or a readback
+/// is in flight, so at most one job is timed per readback.
+pub(super) struct Timestamps {
+    query_set: QuerySet,
+    /// The CPU-readable buffer into which [`query_set`](Self::query_set) is resolved.
+    readback_buffer: Buffer,
+    /// The state of [`readback_buffer`](Self::readback_buffer), which is one of [`IDLE`],
+    /// [`RECORDING`], [`MAPPING`], or [`MAPPED`].
+    readback_state: Arc<AtomicU8>,
+    /// The number of nanoseconds per timestamp tick.
+    period: f32,
+    /// The GPU time, in milliseconds, of the most recently read-back job.
+    last_gpu_time: Mutex<Option<f64>>,
+}
+
+impl fmt::Debug for Timestamps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // `QuerySet` doesn't implement `Debug`.
+        f.debug_struct("Timestamps")
+            .field("readback_buffer", &self.readback_buffer)
+            .field("readback_state", &self.readback_state)
+            .field("period", &self.period)
+            .field("last_gpu_time", &self.last_gpu_time)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Timestamps {
+    /// Creates a new `Timestamps`.
+    ///
+    /// `device` must have been created with [`Features::TIMESTAMP_QUERY`].
+    pub(super) fn new(device: &Device, queue: &Queue) -> Self {
+        Self {
+            query_set: device.create_query_set(&QuerySetDescriptor {
+                label: Some("Pylon timestamp query set"),
+                ty: QueryType::Timestamp,
+                count: 2,
+            }),
+            readback_buffer: device.create_buffer(&BufferDescriptor {
+                label: Some("Pylon timestamp readback buffer"),
+                size: TIMESTAMPS_SIZE,
+                usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }),
+            readback_state: Arc::new(AtomicU8::new(IDLE)),
+            period: queue.get_timestamp_period(),
+            last_gpu_time: Mutex::new(None),
+        }
+    }
+
+    /// Claims these timestamps for a new job, or returns `None` if they are already claimed or a
+    /// readback is in flight.
+    ///
+    /// The claim is taken atomically, so of any number of live jobs, at most one is timed.
+    pub(super) fn try_claim(&self) -> Option<TimestampsClaim<'_>> {
+        self.readback_state
+            .compare_exchange(IDLE, RECORDING, Ordering::AcqRel, Ordering::Acquire)
+            .ok()?;
+
+        Some(TimestampsClaim { timestamps: self, is_submitted: false })
+    }
+
+    /// Reads the timestamps if they have arrived and returns the GPU time, in milliseconds, of the
+    /// most recently read-back job.
+    fn poll(&self) -> Option<f64> {
+        let mut last_gpu_time = self.last_gpu_time.lock().unwrap();
+
+        if self.readback_state.load(Ordering::Acquire) == MAPPED {
+            {
+                let bytes = self.readback_buffer.slice(..).get_mapped_range();
+                let [begin, end]: [u64; 2] = bytemuck::pod_read_unaligned(&bytes);
+                let ticks = end.wrapping_sub(begin);
+                *last_gpu_time = Some((ticks as f64) * f64::from(self.period) / 1_000_000.);
+            }
+            self.readback_buffer.unmap();
+            self.readback_state.store(IDLE, Ordering::Release);
+        }
+
+        *last_gpu_time
+    }
+}
+
+/// The claim of a single [job](super::Job) on [`Timestamps`].
+///
+/// If the job is dropped without being submitted, the claim is released so that a later job may be
+/// timed.
+pub(super) struct TimestampsClaim<'a> {
+    timestamps: &'a Timestamps,
+    /// Whether or not [`start_readback`](Self::start_readback) has been called, after which the
+    /// readback, rather than this claim, releases the timestamps.
+    is_submitted: bool,
+}
+
+impl TimestampsClaim<'_> {
+    /// Writes the timestamp that begins a timed job.
+    pub(super) fn write_begin(&self, encoder: &mut CommandEncoder) {
+        encoder.write_timestamp(&self.timestamps.query_set, 0);
+    }
+
+    /// Writes the timestamp that ends a timed job and resolves both timestamps into the readback
+    /// buffer.
+    pub(super) fn write_end(&self, encoder: &mut CommandEncoder) {
+        let timestamps = self.timestamps;
+        encoder.write_timestamp(&timestamps.query_set, 1);
+        encoder.resolve_query_set(&timestamps.query_set, 0..2, &timestamps.readback_buffer, 0);
+    }
+
+    /// Begins reading back the timestamps written by [`write_end`](Self::write_end).
+    ///
+    /// This must be called after the commands written by `write_end` are submitted.
+    pub(super) fn start_readback(mut self) {
+        self.is_submitted = true;
+        let timestamps = self.timestamps;
+        timestamps.readback_state.store(MAPPING, Ordering::Release);
+
+        let state = Arc::clone(&timestamps.readback_state);
+        timestamps.readback_buffer.slice(..).map_async(MapMode::Read, move |result| {
+            // If mapping failed, there is nothing to read, and the next job may try again.
+            state.store(if result.is_ok() { MAPPED } else { IDLE }, Ordering::Release);
+        });
+    }
+}
+
+impl Drop for TimestampsClaim<'_> {
+    fn drop(&mut self) {
+        if !self.is_submitted {
+            self.timestamps.readback_state.store(IDLE, Ordering::Release);
+        }
+    }
+}
+
+impl Renderer {
+    /// The time, in milliseconds, that the GPU spent executing the most recently timed
+    /// [job](super::Job).
+    ///
+    /// Timing requires the [`TIMESTAMP_QUERY`](Features::TIMESTAMP_QUERY) feature, which may be
+    /// requested with [`RendererBuilder::features`](super::RendererBuilder::features); without
+    /// it, this always returns `None`. `None` is also returned until the first timed job has been
+    /// read back.
+    ///
+    /// Timestamps are read back asynchronously, so the result typically lags by a frame. While
+    /// another job is timed or its readback is in flight, newly created jobs are not timed.
+    pub fn last_frame_gpu_time(&self) -> Option<f64> {
+        let timestamps = self.timestamps.as_ref()?;
+        // This drives the readback callback without blocking.
+        self.device.poll(Maintain::Poll);
+
+        timestamps.poll()
+    }
+}
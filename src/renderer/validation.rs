@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Logging *wgpu* validation errors through *tracing*.
+
+use std::{
+    future::Future as _,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use wgpu::{Device, ErrorFilter};
+
+use super::Renderer;
+
+impl Renderer {
+    /// Runs `f`, logging any validation error that it raises if
+    /// [validation logging](super::RendererBuilder::log_validation_errors) is enabled.
+    ///
+    /// `operation` describes what `f` does, as in "uniform buffer creation", for the log message.
+    pub(super) fn log_validation_errors<T>(&self, operation: &str, f: impl FnOnce() -> T) -> T {
+        log_validation_errors(&self.device, self.log_validation_errors, operation, f)
+    }
+}
+
+/// Runs `f`, logging any validation error that it raises if `enabled` is `true`.
+///
+/// A logged error is captured by an error scope, so it no longer reaches the uncaptured error
+/// handler. On the web, errors are only reported once the browser gets around to it, so no scope
+/// is pushed and errors reach the uncaptured error handler as if logging were disabled.
+pub(super) fn log_validation_errors<T>(
+    device: &Device,
+    enabled: bool,
+    operation: &str,
+    f: impl FnOnce() -> T,
+) -> T {
+    if !enabled || cfg!(target_arch = "wasm32") {
+        return f();
+    }
+
+    device.push_error_scope(ErrorFilter::Validation);
+    let result = f();
+    if let Some(error) = pop_error_scope_now(device) {
+        tracing::error!("wgpu validation error during {}: {}", operation, error);
+    }
+
+    result
+}
+
+/// Pops the innermost error scope without waiting.
+///
+/// This is only called on native platforms, where *wgpu* validates eagerly, so the error is known
+/// by the time the scope is popped.
+fn pop_error_scope_now(device: &Device) -> Option<wgpu::Error> {
+    let mut error = pin!(device.pop_error_scope());
+    match error.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(error) => error,
+        Poll::Pending => None,
+    }
+}
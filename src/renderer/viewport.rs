@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Rendering several cameras into regions of one frame.
+
+use super::{Rect, RenderError, RenderStats, Renderer};
+use crate::{Camera, CameraResources, Object};
+
+impl Renderer {
+    /// Renders and presents a frame in which each camera draws its objects into its own region of
+    /// the surface.
+    ///
+    /// This is intended for editors with several views of the same scene, as with top, front,
+    /// side, and perspective views. Each region acts as both the viewport and the scissor
+    /// rectangle, so no camera draws outside its region. Regions may overlap, in which case later
+    /// cameras are drawn over earlier ones where depth allows; as the depth texture is cleared only
+    /// once, overlapping regions should be avoided. Each camera's [aspect ratio](Camera::aspect)
+    /// should match its region.
+    ///
+    /// Each camera's uniform must already contain its current transformation matrix. Nothing is
+    /// rendered if a region is empty or extends beyond the surface. Otherwise, the frame is
+    /// presented even if an object fails to draw, in which case the error is returned; otherwise,
+    /// the [stats](RenderStats) of the frame are returned.
+    pub fn render_viewports<R: CameraResources>(
+        &self,
+        viewports: &[(&Camera<R>, Rect, &[&dyn Object])],
+    ) -> Result<RenderStats, RenderError> {
+        for (_, viewport, _) in viewports {
+            if !viewport.fits_within(self.surface_size) {
+                return Err(RenderError::ViewportOutOfBounds {
+                    viewport: *viewport,
+                    surface_size: self.surface_size,
+                });
+            }
+        }
+
+        let mut job = self.create_render();
+        let result = {
+            let mut pass = job.add_pass();
+            viewports.iter().try_for_each(|(camera, viewport, objects)| {
+                let Rect { x, y, width, height } = *viewport;
+                pass.set_viewport(x as f32, y as f32, width as f32, height as f32);
+                pass.set_scissor_rect(x, y, width, height);
+                pass.set_camera(camera.transforms_uniform());
+                pass.draw_objects_dyn(objects).map(|_| ())
+            })
+        };
+        let stats = job.stats();
+        job.submit();
+
+        result.map(|_| stats)
+    }
+}
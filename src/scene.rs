@@ -0,0 +1,173 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The [`Scene`], which ties a camera, a node hierarchy, and objects together.
+
+use std::rc::Rc;
+
+use crate::{
+    renderer::{RenderError, RenderStats},
+    tree::Node,
+    Camera,
+    CameraResources,
+    CameraTransformsUniform,
+    Object,
+    ObjectTransforms,
+    ObjectTransformsUniform,
+    Point,
+    Renderer,
+};
+
+/// A camera, the nodes of a transformation hierarchy, and the objects attached to those nodes.
+///
+/// This is a high-level façade over [`Camera`], [`Node`], [`Object`], and [`Renderer`]. The scene
+/// owns the uniform buffers behind the camera and each object; on every
+/// [`render`](Self::render), it writes the current camera transformation matrix and the
+/// [global transformation matrix](Node::global_transformation_matrix) of each object's node to
+/// those buffers, and then draws every object in one pass.
+///
+/// Nodes reference their parents only weakly, so the scene keeps [added nodes](Self::add_node)
+/// alive; these are typically the roots of the hierarchy and any parents that aren't otherwise
+/// owned. The node of each object is kept alive by the object itself. As with any [`Node`],
+/// cached matrices must be [invalidated](Node::invalidate_cache) after a node is modified.
+///
+/// Objects may be of any single type `O`, or of mixed types with the default `dyn Object`.
+pub struct Scene<O: Object + ?Sized = dyn Object> {
+    /// The active camera.
+    pub camera: Camera<SceneCameraResources>,
+    nodes: Vec<Rc<Node>>,
+    objects: Vec<SceneObject<O>>,
+}
+
+/// The GPU resources of the camera of a [`Scene`], which are managed by the scene.
+pub struct SceneCameraResources {
+    transforms_buffer: wgpu::Buffer,
+    transforms_uniform: CameraTransformsUniform,
+}
+
+impl CameraResources for SceneCameraResources {
+    fn transforms_uniform(&self) -> &CameraTransformsUniform {
+        &self.transforms_uniform
+    }
+}
+
+/// An object within a [`Scene`] and the node that places it.
+struct SceneObject<O: Object + ?Sized> {
+    node: Rc<Node>,
+    /// The buffer behind the object's [transforms uniform](Object::transforms_uniform).
+    transforms_buffer: wgpu::Buffer,
+    object: Box<O>,
+}
+
+impl<O: Object + ?Sized> Scene<O> {
+    /// Creates an empty `Scene` whose camera is at `position` and looks at `target`.
+    ///
+    /// The camera otherwise has the defaults of [`Camera::new`].
+    pub fn new(renderer: &Renderer, position: Point, target: Point) -> Self {
+        // The camera transformation matrix is written on every render.
+        let transforms_buffer =
+            renderer.create_uniform_buffer(&[0; std::mem::size_of::<[[f32; 4]; 4]>()]);
+        let transforms_uniform = renderer
+            .create_camera_transforms_uniform(transforms_buffer.as_entire_buffer_binding());
+
+        Self {
+            camera: Camera::new(
+                position,
+                target,
+                SceneCameraResources { transforms_buffer, transforms_uniform },
+            ),
+            nodes: Vec::new(),
+            objects: Vec::new(),
+        }
+    }
+
+    /// Keeps the given node alive for as long as this scene.
+    pub fn add_node(&mut self, node: Rc<Node>) {
+        self.nodes.push(node);
+    }
+
+    /// The nodes that this scene keeps alive, in order of addition.
+    pub fn nodes(&self) -> &[Rc<Node>] {
+        &self.nodes
+    }
+
+    /// Adds an object that is placed by `node`.
+    ///
+    /// The scene creates the object's transforms uniform and passes it to `create`, which should
+    /// return an object whose [`transforms_uniform`](Object::transforms_uniform) is that uniform
+    /// and whose [`transforms_offset`](Object::transforms_offset) is zero.
+    pub fn add_object(
+        &mut self,
+        renderer: &Renderer,
+        node: Rc<Node>,
+        create: impl FnOnce(ObjectTransformsUniform) -> Box<O>,
+    ) {
+        let transforms = ObjectTransforms::new(&node.global_transformation_matrix());
+        let transforms_buffer = renderer.create_uniform_buffer(bytemuck::bytes_of(&transforms));
+        let object = create(
+            renderer.create_object_transforms_uniform(transforms_buffer.as_entire_buffer_binding()),
+        );
+
+        self.objects.push(SceneObject { node, transforms_buffer, object });
+    }
+
+    /// The number of objects in this scene.
+    pub fn object_count(&self) -> usize {
+        self.objects.len()
+    }
+
+    /// The objects in this scene, in order of addition, with the nodes that place them.
+    pub fn objects(&self) -> impl Iterator<Item = (&Rc<Node>, &O)> {
+        self.objects.iter().map(|entry| (&entry.node, &*entry.object))
+    }
+
+    /// Updates the camera and object transforms and then renders and presents a frame containing
+    /// every object.
+    ///
+    /// Objects are [grouped by pipeline](crate::renderer::Pass::draw_objects_batched). The frame
+    /// is presented even if an object fails to draw, in which case the error is returned;
+    /// otherwise, the [stats](RenderStats) of the frame are returned.
+    pub fn render(&self, renderer: &Renderer) -> Result<RenderStats, RenderError> {
+        self.update_transforms(renderer);
+
+        let mut job = renderer.create_render();
+        let result = {
+            job.add_pass()
+                .with_camera(self.camera.transforms_uniform())
+                .draw_objects_batched(self.objects.iter().map(|entry| &*entry.object))
+                .map(|_| ())
+        };
+        let stats = job.stats();
+        job.submit();
+
+        result.map(|_| stats)
+    }
+
+    /// Like [`render`](Self::render), but writes the depth of every object in a depth-only
+    /// pre-pass first.
+    ///
+    /// See [`Renderer::render_with_depth_prepass`] for the requirements on object pipelines and
+    /// for when this helps.
+    pub fn render_with_depth_prepass(
+        &self,
+        renderer: &Renderer,
+    ) -> Result<RenderStats, RenderError> {
+        self.update_transforms(renderer);
+
+        let objects: Vec<&O> = self.objects.iter().map(|entry| &*entry.object).collect();
+        renderer.render_with_depth_prepass(&self.camera, &objects)
+    }
+
+    /// Writes the current camera and object transforms to their uniform buffers.
+    fn update_transforms(&self, renderer: &Renderer) {
+        let queue = renderer.queue();
+        queue.write_buffer(
+            &self.camera.resources.transforms_buffer,
+            0,
+            bytemuck::bytes_of(&self.camera.transformation_matrix().to_array()),
+        );
+        for entry in self.objects.iter() {
+            let transforms = ObjectTransforms::new(&entry.node.global_transformation_matrix());
+            queue.write_buffer(&entry.transforms_buffer, 0, bytemuck::bytes_of(&transforms));
+        }
+    }
+}
@@ -0,0 +1,71 @@
+/// The output of this vertex shader.
+struct Output {
+    /// The position of the current vertex in clip space.
+    @builtin(position) position: vec4<f32>,
+    /// The unit normal of the current vertex in world space.
+    @location(0) normal: vec3<f32>,
+    /// The linear RGBA color of the instance that the current vertex belongs to.
+    @location(1) color: vec4<f32>,
+}
+
+/// The transformation matrices for the object that the current vertex belongs to.
+///
+/// This must agree with `ObjectTransforms` on the CPU.
+struct ObjectTransforms {
+    /// The precompiled transformation matrix from mesh space to world space.
+    transformation_matrix: mat4x4<f32>,
+    /// The inverse-transpose of the transformation matrix, padded to 4x4, for transforming normals.
+    normal_matrix: mat4x4<f32>,
+}
+
+/// The attributes of the instance that the current vertex belongs to.
+///
+/// This must agree with `ObjectInstance` on the CPU.
+struct Instance {
+    /// The columns of the transformation matrix of this instance, which is applied before the
+    /// object transformation matrix.
+    @location(4) transformation_matrix_0: vec4<f32>,
+    @location(5) transformation_matrix_1: vec4<f32>,
+    @location(6) transformation_matrix_2: vec4<f32>,
+    @location(7) transformation_matrix_3: vec4<f32>,
+    /// The linear RGBA color of this instance.
+    @location(8) color: vec4<f32>,
+}
+
+/// The precompiled camera transformation matrix supplied by the CPU.
+///
+/// This transformation matrix is to be applied after the object transformation matrix.
+@group(0) @binding(0)
+var<uniform> camera_transformation_matrix: mat4x4<f32>;
+
+/// The transformation matrices for the object that the current vertex belongs to.
+@group(1) @binding(0)
+var<uniform> object_transforms: ObjectTransforms;
+
+@vertex
+fn main(
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    instance: Instance,
+) -> Output {
+    let instance_matrix = mat4x4<f32>(
+        instance.transformation_matrix_0,
+        instance.transformation_matrix_1,
+        instance.transformation_matrix_2,
+        instance.transformation_matrix_3,
+    );
+
+    var output: Output;
+    output.position = camera_transformation_matrix
+        * object_transforms.transformation_matrix
+        * instance_matrix
+        * vec4<f32>(position, 1.0);
+    output.position.y *= -1.0;
+    // The instance matrix is assumed to scale uniformly, so it transforms normals as it does
+    // directions; renormalization undoes the scale.
+    let instance_normal = vec4<f32>((instance_matrix * vec4<f32>(normal, 0.0)).xyz, 0.0);
+    output.normal = normalize((object_transforms.normal_matrix * instance_normal).xyz);
+    output.color = instance.color;
+
+    return output;
+}
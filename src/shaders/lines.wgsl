@@ -0,0 +1,29 @@
+/// The output of the vertex shader.
+struct Output {
+    /// The position of the current vertex in clip space.
+    @builtin(position) position: vec4<f32>,
+    /// The color of the line that the current vertex belongs to.
+    @location(0) color: vec4<f32>,
+}
+
+/// The precompiled camera transformation matrix supplied by the CPU.
+@group(0) @binding(0)
+var<uniform> camera_transformation_matrix: mat4x4<f32>;
+
+/// Transforms one end of a line segment from world space to clip space.
+///
+/// Line segments have no object transformation matrix, as their ends are already in world space.
+@vertex
+fn vertex_main(@location(0) position: vec3<f32>, @location(1) color: vec4<f32>) -> Output {
+    var output: Output;
+    output.position = camera_transformation_matrix * vec4<f32>(position, 1.0);
+    output.position.y *= -1.0;
+    output.color = color;
+
+    return output;
+}
+
+@fragment
+fn fragment_main(input: Output) -> @location(0) vec4<f32> {
+    return input.color;
+}
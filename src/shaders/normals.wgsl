@@ -0,0 +1,57 @@
+/// The output of the vertex shader.
+struct Output {
+    /// The position of the current vertex in clip space.
+    @builtin(position) position: vec4<f32>,
+}
+
+/// The length, in mesh space, of each normal line segment.
+let NORMAL_LENGTH: f32 = 0.1;
+
+/// The precompiled camera transformation matrix supplied by the CPU.
+@group(0) @binding(0)
+var<uniform> camera_transformation_matrix: mat4x4<f32>;
+
+/// The layout of the object transforms uniform.
+///
+/// This must agree with the built-in vertex shader.
+struct ObjectTransforms {
+    transformation_matrix: mat4x4<f32>,
+    normal_matrix: mat4x4<f32>,
+}
+
+/// The transformation matrices for the object that the current vertex belongs to.
+@group(1) @binding(0)
+var<uniform> object_transforms: ObjectTransforms;
+
+/// Transforms the given point in mesh space to clip space.
+///
+/// This must agree with the built-in vertex shader.
+fn transform_position(position: vec3<f32>) -> vec4<f32> {
+    let transformation = camera_transformation_matrix * object_transforms.transformation_matrix;
+
+    return transformation * vec4<f32>(position.xyz, 1.0);
+}
+
+/// Produces one end of the line segment for a mesh vertex.
+///
+/// Each mesh vertex is an instance. Vertex 0 of each instance lies at the mesh vertex, and vertex 1
+/// lies `NORMAL_LENGTH` along its normal.
+@vertex
+fn vertex_main(
+    @builtin(vertex_index) index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+) -> Output {
+    var output: Output;
+    let end = position + (f32(index) * NORMAL_LENGTH * normal);
+    output.position = transform_position(end);
+    output.position.y *= -1.0;
+
+    return output;
+}
+
+/// Every normal is drawn in the same flat color.
+@fragment
+fn fragment_main() -> @location(0) vec4<f32> {
+    return vec4<f32>(0.0, 0.0, 1.0, 1.0);
+}
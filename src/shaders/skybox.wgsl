@@ -0,0 +1,44 @@
+/// The output of the vertex shader.
+struct Output {
+    /// The position of the current vertex in clip space, on the far clipping plane.
+    @builtin(position) position: vec4<f32>,
+    /// The direction in world space from the camera through the current vertex.
+    @location(0) direction: vec3<f32>,
+}
+
+/// The inverse of the camera transformation matrix with the camera's translation removed.
+///
+/// This transforms points in clip space to points in world space relative to the camera.
+@group(0) @binding(0)
+var<uniform> inverse_camera_rotation_matrix: mat4x4<f32>;
+
+/// The cubemap that surrounds the scene.
+@group(0) @binding(1)
+var cubemap: texture_cube<f32>;
+
+@group(0) @binding(2)
+var cubemap_sampler: sampler;
+
+/// Produces one vertex of a triangle that covers the viewport.
+@vertex
+fn vertex_main(@builtin(vertex_index) index: u32) -> Output {
+    // The vertices are at (-1, -1), (3, -1), and (-1, 3), so the triangle covers [-1, 1] in X and
+    // Y.
+    let xy = (vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u)) * 2.0) - 1.0;
+
+    var output: Output;
+    // A depth of 1 puts the sky on the far clipping plane, behind everything else.
+    output.position = vec4<f32>(xy, 1.0, 1.0);
+    // The other vertex shaders flip Y after the camera transformation, so it is flipped back
+    // before the inverse is applied. Points on the far plane vary linearly across the viewport, so
+    // dividing here rather than per fragment is exact.
+    let far_point = inverse_camera_rotation_matrix * vec4<f32>(xy.x, -xy.y, 1.0, 1.0);
+    output.direction = far_point.xyz / far_point.w;
+
+    return output;
+}
+
+@fragment
+fn fragment_main(input: Output) -> @location(0) vec4<f32> {
+    return textureSample(cubemap, cubemap_sampler, input.direction);
+}
@@ -0,0 +1,39 @@
+// Tonemapping and gamma encoding helpers, which user fragment shaders may opt into by prepending
+// this source to their own.
+
+/// Maps each channel of a high-dynamic-range color from `[0, inf)` into `[0, 1)` with the Reinhard
+/// operator.
+fn tonemap_reinhard(color: vec3<f32>) -> vec3<f32> {
+    return color / (color + vec3<f32>(1.0));
+}
+
+/// Maps a high-dynamic-range color into `[0, 1]` with Narkowicz's fit of the ACES filmic curve.
+fn tonemap_aces(color: vec3<f32>) -> vec3<f32> {
+    let a = 2.51;
+    let b = 0.03;
+    let c = 2.43;
+    let d = 0.59;
+    let e = 0.14;
+
+    let mapped = (color * (a * color + b)) / (color * (c * color + d) + e);
+    return clamp(mapped, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+/// Encodes a linear color in `[0, 1]` with the sRGB transfer function.
+///
+/// This is only needed when the surface format is linear; sRGB surface formats encode colors in
+/// hardware.
+fn linear_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let low = color * 12.92;
+    let high = 1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055;
+
+    return select(high, low, color <= vec3<f32>(0.0031308));
+}
+
+/// Decodes an sRGB-encoded color in `[0, 1]` to linear.
+fn srgb_to_linear(color: vec3<f32>) -> vec3<f32> {
+    let low = color / 12.92;
+    let high = pow((color + 0.055) / 1.055, vec3<f32>(2.4));
+
+    return select(high, low, color <= vec3<f32>(0.04045));
+}
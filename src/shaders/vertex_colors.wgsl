@@ -0,0 +1,65 @@
+/// The output of this vertex shader.
+struct Output {
+    /// The position of the current vertex in clip space.
+    @builtin(position) position: vec4<f32>,
+    /// The unit normal of the current vertex in world space.
+    @location(0) normal: vec3<f32>,
+    /// The linear RGBA color of the current vertex, which is interpolated across triangles.
+    @location(1) color: vec4<f32>,
+}
+
+/// The transformation matrices for the object that the current vertex belongs to.
+///
+/// This must agree with `ObjectTransforms` on the CPU.
+struct ObjectTransforms {
+    /// The precompiled transformation matrix from mesh space to world space.
+    transformation_matrix: mat4x4<f32>,
+    /// The inverse-transpose of the transformation matrix, padded to 4x4, for transforming normals.
+    normal_matrix: mat4x4<f32>,
+}
+
+/// The precompiled camera transformation matrix supplied by the CPU.
+///
+/// This transformation matrix is to be applied after the object transformation matrix.
+@group(0) @binding(0)
+var<uniform> camera_transformation_matrix: mat4x4<f32>;
+
+/// The transformation matrices for the object that the current vertex belongs to.
+@group(1) @binding(0)
+var<uniform> object_transforms: ObjectTransforms;
+
+/// The transformation matrix to be applied to the current vertex.
+///
+/// This is a combination of the object and camera transformation matrices.
+fn vertex_transformation_matrix() -> mat4x4<f32> {
+    return camera_transformation_matrix * object_transforms.transformation_matrix;
+}
+
+/// Transforms the given vertex according to the vertex transformation matrix.
+///
+/// The W component of the result is kept so that the GPU performs the perspective divide.
+fn transform_position(position: vec3<f32>) -> vec4<f32> {
+    return vertex_transformation_matrix() * vec4<f32>(position.xyz, 1.0);
+}
+
+/// Transforms the given normal from mesh space to world space.
+///
+/// Normals are directions, so the W component is 0 and translation doesn't apply.
+fn transform_normal(normal: vec3<f32>) -> vec3<f32> {
+    return normalize((object_transforms.normal_matrix * vec4<f32>(normal, 0.0)).xyz);
+}
+
+@vertex
+fn main(
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) color: vec4<f32>,
+) -> Output {
+    var output: Output;
+    output.position = transform_position(position);
+    output.position.y *= -1.0;
+    output.normal = transform_normal(normal);
+    output.color = color;
+
+    return output;
+}
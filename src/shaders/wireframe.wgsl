@@ -0,0 +1,7 @@
+/// The fragment shader for wireframe debug overlays.
+///
+/// Every edge is drawn in the same flat color.
+@fragment
+fn main() -> @location(0) vec4<f32> {
+    return vec4<f32>(0.0, 1.0, 0.0, 1.0);
+}
@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Position, rotation, and scale.
+
+use crate::{Matrix, Point, Rotation, Vector};
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// The position, rotation, and scale of something in space.
+///
+/// This is the transform held by each [`tree::Node`](crate::tree::Node), but it is independent of
+/// the transformation hierarchy and so may be used on its own, as in a flat world.
+///
+/// The default `Transform` is [`IDENTITY`](Self::IDENTITY).
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transform {
+    /// The translation, applied third.
+    pub position: Point,
+    /// The rotation, applied second.
+    pub rotation: Rotation,
+    /// The X, Y, and Z scale factors, applied first.
+    pub scale: [f32; 3],
+}
+
+impl Transform {
+    /// The transform that leaves everything as it is.
+    pub const IDENTITY: Self = Self {
+        position: Point::ORIGIN,
+        rotation: Rotation::ZERO,
+        scale: [1.; 3],
+    };
+
+    /// Creates a new `Transform` that scales uniformly in all axes.
+    pub fn new(position: Point, rotation: Rotation, scale: f32) -> Self {
+        Self { position, rotation, scale: [scale; 3] }
+    }
+
+    /// Creates a transformation matrix for this transform.
+    ///
+    /// This is the product of the position, rotation, and scale matrices.
+    pub fn to_matrix(&self) -> Matrix {
+        // Because we're using pre-multiplication, the order here is reversed. The true order is:
+        // 1. Scale.
+        // 2. Rotate.
+        // 3. Translate.
+        self.create_position_matrix() * self.rotation.to_matrix() * self.create_scale_matrix()
+    }
+
+    /// Creates a transformation matrix for the position transform.
+    fn create_position_matrix(&self) -> Matrix {
+        let Point { x, y, z } = self.position;
+        let mut m = Matrix::IDENTITY;
+        // The W component of the identity matrix's last column must remain 1.
+        m.columns_mut()[3] += Vector::from_direction([x, y, z]);
+
+        m
+    }
+
+    /// Creates a transformation matrix for the scale transform.
+    fn create_scale_matrix(&self) -> Matrix {
+        let [x, y, z] = self.scale;
+
+        Matrix::new(
+             x, 0., 0., 0.,
+            0.,  y, 0., 0.,
+            0., 0.,  z, 0.,
+            0., 0., 0., 1.,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::Node;
+
+    #[test]
+    fn matrix_matches_node() {
+        let position = Point { x: 1., y: 2., z: 3. };
+        let rotation = Rotation::new(0., 0., std::f32::consts::FRAC_PI_2);
+        let transform = Transform::new(position, rotation, 2.);
+        let node: std::rc::Rc<Node> = Node::root(position, rotation, 2.);
+
+        let matrix = transform.to_matrix();
+        assert_eq!(matrix.to_array(), node.local_transformation_matrix().to_array());
+        assert_eq!(matrix.to_array()[3], [1., 2., 3., 1.]);
+
+        // The point is scaled, then rotated a quarter turn from X to Y, and then translated.
+        let Point { x, y, z } = matrix * Point { x: 1., y: 0., z: 0. };
+        assert!((x - 1.).abs() < 1e-6 && (y - 4.).abs() < 1e-6 && (z - 3.).abs() < 1e-6);
+    }
+}
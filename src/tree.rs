@@ -1,67 +1,169 @@
-use std::{cell::Cell, rc::Weak};
+use std::{cell::{Cell, RefCell}, fmt, rc::{Rc, Weak}};
 
-use crate::{Matrix, Point, Rotation, Vector};
+use crate::{linear::{Float, Scalar}, Matrix, Point, Rotation, Transform};
 
-impl Default for Node {
+impl<S: Float> Default for Node<S> {
     fn default() -> Self {
         Self {
-            parent: Weak::new(),
-            position: Point::ORIGIN,
-            rotation: Rotation::ZERO,
-            scale: 1.0,
+            parent: RefCell::new(Weak::new()),
+            transform: Transform::IDENTITY,
             cached_transformation_matrices: Default::default(),
         }
     }
 }
 
-pub struct Node {
-    parent: Weak<Node>,
-    /// The position of this node relative to its parent.
-    position: Point,
-    /// The rotation of this node relative to the rotation of its parent.
-    rotation: Rotation,
-    /// The scale factor of this node's coordinates.
-    scale: f32,
+/// A node within a transformation hierarchy.
+///
+/// Global transformation matrices are composed in the [`Float`] type `S`, which is [`Scalar`] by
+/// default. Choosing `f64` reduces the error accumulated over deep hierarchies; the resulting
+/// matrices should be [cast](Matrix::cast) to [`Scalar`] before they are uploaded to the GPU.
+/// Local transformation matrices are always computed in [`Scalar`] and then cast.
+pub struct Node<S: Float = Scalar> {
+    parent: RefCell<Weak<Node<S>>>,
+    /// The position, rotation, and scale of this node relative to its parent.
+    transform: Transform,
     /// Cached global and local transformation matrices.
     ///
     /// If a transformation matrix is available and valid from a previous call to
     /// [`global_transformation_matrix`](Self::global_transformation_matrix) or
     /// [`local_transformation_matrix`](Self::local_transformation_matrix), it is pulled from here.
     /// Otherwise, the newly-created matrix is cached to here.
-    cached_transformation_matrices: CachedTransformationMatrices,
+    cached_transformation_matrices: CachedTransformationMatrices<S>,
 }
 
-impl Node {
-    pub fn parent(&self) -> &Weak<Node> {
-        &self.parent
+impl<S: Float> Node<S> {
+    /// Creates a new `Node` that is a child of `parent`.
+    ///
+    /// The child holds only a [`Weak`] reference to its parent, so the hierarchy is owned from the
+    /// leaves up: the caller must keep an [`Rc`] to `parent` alive for as long as the child should
+    /// be transformed relative to it. If the parent is dropped, the child behaves like a root.
+    /// Parents must never hold strong references to their children, as this would create a
+    /// reference cycle that is never freed.
+    ///
+    /// `scale` is applied uniformly in all axes.
+    pub fn new(
+        parent: &Rc<Node<S>>,
+        position: Point,
+        rotation: Rotation,
+        scale: f32,
+    ) -> Rc<Self> {
+        Rc::new(Self {
+            parent: RefCell::new(Rc::downgrade(parent)),
+            ..Self::with_transform(position, rotation, scale)
+        })
+    }
+
+    /// Creates a new `Node` without a parent.
+    ///
+    /// `scale` is applied uniformly in all axes.
+    pub fn root(position: Point, rotation: Rotation, scale: f32) -> Rc<Self> {
+        Rc::new(Self::with_transform(position, rotation, scale))
+    }
+
+    fn with_transform(position: Point, rotation: Rotation, scale: f32) -> Self {
+        Self {
+            transform: Transform::new(position, rotation, scale),
+            ..Default::default()
+        }
+    }
+
+    /// The parent of this node.
+    ///
+    /// This is a clone of the [`Weak`] reference held by this node, which is dangling for a root.
+    pub fn parent(&self) -> Weak<Node<S>> {
+        self.parent.borrow().clone()
     }
 
-    pub fn parent_mut(&mut self) -> &mut Weak<Node> {
-        &mut self.parent
+    /// A mutable reference to the parent of this node.
+    ///
+    /// Unlike [`set_parent`](Self::set_parent), this neither checks for cycles nor invalidates the
+    /// global cache.
+    pub fn parent_mut(&mut self) -> &mut Weak<Node<S>> {
+        self.parent.get_mut()
+    }
+
+    /// Makes this node a child of `parent`.
+    ///
+    /// This fails, leaving the hierarchy as it was, if `parent` is this node or one of its
+    /// descendants, as the hierarchy would then contain a cycle. Otherwise, the global cache of
+    /// this node is invalidated; as with any change to a node, the caches of its descendants must
+    /// be invalidated by the caller.
+    pub fn set_parent(&self, parent: &Rc<Node<S>>) -> Result<(), TreeError> {
+        let mut ancestor = Some(Rc::clone(parent));
+        while let Some(node) = ancestor {
+            if std::ptr::eq(Rc::as_ptr(&node), self) {
+                return Err(TreeError::Cycle);
+            }
+            ancestor = node.parent().upgrade();
+        }
+
+        *self.parent.borrow_mut() = Rc::downgrade(parent);
+        self.invalidate_global_cache();
+
+        Ok(())
+    }
+
+    /// The position, rotation, and scale of this node relative to its parent.
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    pub fn transform_mut(&mut self) -> &mut Transform {
+        &mut self.transform
     }
 
     pub fn position(&self) -> Point {
-        self.position
+        self.transform.position
     }
 
     pub fn position_mut(&mut self) -> &mut Point {
-        &mut self.position
+        &mut self.transform.position
     }
 
     pub fn rotation(&self) -> Rotation {
-        self.rotation
+        self.transform.rotation
     }
 
     pub fn rotation_mut(&mut self) -> &mut Rotation {
-        &mut self.rotation
+        &mut self.transform.rotation
     }
 
+    /// The X scale factor of this node.
+    ///
+    /// Nodes previously supported only uniform scaling; for non-uniformly scaled nodes, this is not
+    /// representative of the Y and Z scale factors.
+    #[deprecated(note = "use `scale_factors` for non-uniform scale")]
     pub fn scale(&self) -> f32 {
-        self.scale
+        self.transform.scale[0]
+    }
+
+    /// The X, Y, and Z scale factors of this node.
+    pub fn scale_factors(&self) -> [f32; 3] {
+        self.transform.scale
+    }
+
+    /// A mutable reference to the uniform scale factor of this node.
+    ///
+    /// The factor starts as the X scale factor, and whatever is written through the returned guard
+    /// is applied to all three axes, as with [`set_uniform_scale`](Self::set_uniform_scale), when
+    /// the guard is dropped.
+    #[deprecated(note = "use `set_uniform_scale` or `scale_factors_mut`")]
+    pub fn scale_mut(&mut self) -> UniformScaleMut<'_> {
+        UniformScaleMut {
+            factor: self.transform.scale[0],
+            scale: &mut self.transform.scale,
+        }
+    }
+
+    pub fn scale_factors_mut(&mut self) -> &mut [f32; 3] {
+        &mut self.transform.scale
     }
 
-    pub fn scale_mut(&mut self) -> &mut f32 {
-        &mut self.scale
+    /// Scales this node by the same factor in all axes.
+    ///
+    /// This replaces the deprecated [`scale_mut`](Self::scale_mut) accessor.
+    pub fn set_uniform_scale(&mut self, factor: f32) {
+        self.transform.scale = [factor; 3];
     }
 
     pub fn invalidate_global_cache(&self) {
@@ -74,8 +176,11 @@ impl Node {
 
     /// The global transformation matrix for this node.
     ///
+    /// This is the parent's global transformation matrix multiplied by this node's local
+    /// transformation matrix, in that order, so that the local transformation is applied first.
+    ///
     /// This will return a cached copy if one is available.
-    pub fn global_transformation_matrix(&self) -> Matrix {
+    pub fn global_transformation_matrix(&self) -> Matrix<S> {
         self.transformation_matrix(
             &self.cached_transformation_matrices.global,
             Self::create_global_transformation_matrix,
@@ -85,7 +190,7 @@ impl Node {
     /// The local transformation matrix for this node.
     ///
     /// This will return a cached copy if one is available.
-    pub fn local_transformation_matrix(&self) -> Matrix {
+    pub fn local_transformation_matrix(&self) -> Matrix<S> {
         self.transformation_matrix(
             &self.cached_transformation_matrices.local,
             Self::create_local_transformation_matrix,
@@ -94,9 +199,9 @@ impl Node {
 
     fn transformation_matrix(
         &self,
-        cell: &Cell<Option<Matrix>>,
-        create: impl FnOnce(&Self) -> Matrix,
-    ) -> Matrix {
+        cell: &Cell<Option<Matrix<S>>>,
+        create: impl FnOnce(&Self) -> Matrix<S>,
+    ) -> Matrix<S> {
         if let Some(matrix) = cell.get() {
             return matrix;
         }
@@ -108,7 +213,53 @@ impl Node {
     }
 }
 
-impl Default for CachedTransformationMatrices {
+/// A mutable reference to the uniform scale factor of a [`Node`], returned by the deprecated
+/// [`Node::scale_mut`].
+///
+/// When this is dropped, the factor is written to all three scale factors of the node.
+pub struct UniformScaleMut<'a> {
+    factor: f32,
+    scale: &'a mut [f32; 3],
+}
+
+impl std::ops::Deref for UniformScaleMut<'_> {
+    type Target = f32;
+
+    fn deref(&self) -> &Self::Target {
+        &self.factor
+    }
+}
+
+impl std::ops::DerefMut for UniformScaleMut<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.factor
+    }
+}
+
+impl Drop for UniformScaleMut<'_> {
+    fn drop(&mut self) {
+        *self.scale = [self.factor; 3];
+    }
+}
+
+/// An error that occurs when [reparenting a node](Node::set_parent).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TreeError {
+    /// The new parent was the node itself or one of its descendants.
+    Cycle,
+}
+
+impl fmt::Display for TreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cycle => write!(f, "node cannot be a descendant of itself"),
+        }
+    }
+}
+
+impl std::error::Error for TreeError {}
+
+impl<S: Float> Default for CachedTransformationMatrices<S> {
     fn default() -> Self {
         Self {
             global: Cell::new(None),
@@ -118,12 +269,12 @@ impl Default for CachedTransformationMatrices {
 }
 
 #[derive(Debug)]
-struct CachedTransformationMatrices {
-    global: Cell<Option<Matrix>>,
-    local: Cell<Option<Matrix>>,
+struct CachedTransformationMatrices<S: Float> {
+    global: Cell<Option<Matrix<S>>>,
+    local: Cell<Option<Matrix<S>>>,
 }
 
-impl CachedTransformationMatrices {
+impl<S: Float> CachedTransformationMatrices<S> {
     fn invalidate_global(&self) {
         self.global.set(None);
     }
@@ -134,115 +285,368 @@ impl CachedTransformationMatrices {
     }
 }
 
-impl Node {
-    fn create_global_transformation_matrix(&self) -> Matrix {
-        let mut matrix = self.local_transformation_matrix();
+impl<S: Float> Node<S> {
+    /// Creates a global transformation matrix for this node.
+    ///
+    /// Rather than recursing into the parent, which could overflow the stack for deep hierarchies,
+    /// this collects the ancestors up to the nearest one with a cached global transformation
+    /// matrix and then folds their local transformation matrices from the top down. The global
+    /// transformation matrix of each of those ancestors is cached along the way.
+    fn create_global_transformation_matrix(&self) -> Matrix<S> {
+        let mut ancestors = Vec::new();
+        let mut global = Matrix::IDENTITY;
+        let mut next = self.parent().upgrade();
+        while let Some(node) = next {
+            if let Some(matrix) = node.cached_transformation_matrices.global.get() {
+                global = matrix;
+                break;
+            }
+            next = node.parent().upgrade();
+            ancestors.push(node);
+        }
 
-        // Because we're using pre-multiplication, the order of application is in reverse;
-        // although the local transformation matrix is applied last, we start with the local
-        // transformation matrix and traverse the tree upwards.
-        if let Some(node) = self.parent.upgrade() {
-            matrix *= node.global_transformation_matrix();
+        // Because we're using pre-multiplication, the order of application is in reverse; the
+        // local transformation matrix is applied first, so the parent's global transformation
+        // matrix goes on the left.
+        for node in ancestors.iter().rev() {
+            global *= node.local_transformation_matrix();
+            node.cached_transformation_matrices.global.set(Some(global));
         }
 
-        matrix
+        global * self.local_transformation_matrix()
     }
 
     /// Creates a local transformation matrix for this node.
     ///
-    /// This is the product of local position, rotation, and scale matrices.
-    fn create_local_transformation_matrix(&self) -> Matrix {
-        // Because we're using pre-multiplication, the order here is reversed. The true order is:
-        // 1. Scale.
-        // 2. Rotate.
-        // 3. Translate.
-        return
-            self.create_local_position_matrix() *
-            self.create_local_rotation_matrix() *
-            self.create_local_scale_matrix();
+    /// This is [the matrix of this node's transform](Transform::to_matrix).
+    fn create_local_transformation_matrix(&self) -> Matrix<S> {
+        let matrix: Matrix<S> = self.transform.to_matrix().cast();
+        debug_assert!(
+            matrix.is_finite(),
+            "local transformation matrix of node is not finite; position: {:?}, rotation: {:?}, \
+            scale: {:?}",
+            self.transform.position,
+            self.transform.rotation,
+            self.transform.scale,
+        );
+
+        matrix
     }
+}
 
-    /// Creates a local transformation matrix for the position transform of this node.
-    ///
-    /// This transform is applied third.
-    fn create_local_position_matrix(&self) -> Matrix {
-        let mut m = Matrix::IDENTITY;
-        m.columns_mut()[3] += Vector::from(self.position);
+/// The number of nodes from which a level of a [`Hierarchy`] is updated in parallel.
+///
+/// Small levels aren't worth the overhead of distributing across threads.
+#[cfg(feature = "rayon")]
+const MIN_PARALLEL_LEVEL_LEN: usize = 256;
+
+/// A transformation hierarchy stored by level, whose global transformation matrices are computed in
+/// bulk.
+///
+/// Unlike [`Node`], which computes and caches global transformation matrices lazily, a
+/// `Hierarchy` computes all of them at once in [`update_transforms`](Self::update_transforms).
+/// Nodes are stored by their depth in the hierarchy so that each level depends only on the level
+/// above it; with the **rayon** feature, the nodes of each level are then updated in parallel.
+///
+/// As with [`Node`], global transformation matrices are composed in `S`, while local
+/// transformation matrices are always computed in [`Scalar`] and then cast; `f64` therefore limits
+/// the error accumulated across levels but not that of each individual [`Transform`].
+///
+/// Nodes cannot be removed, and [`HierarchyNodeId`]s are only meaningful to the `Hierarchy` that
+/// created them.
+#[derive(Debug)]
+pub struct Hierarchy<S: Float = Scalar> {
+    /// The nodes of this hierarchy, by depth.
+    levels: Vec<Vec<HierarchyNode<S>>>,
+}
 
-        return m;
+impl<S: Float> Default for Hierarchy<S> {
+    fn default() -> Self {
+        Self { levels: Vec::new() }
     }
+}
 
-    /// Creates a local transformation matrix for the rotation transform of this node.
-    ///
-    /// This transform is applied third.
-    fn create_local_rotation_matrix(&self) -> Matrix {
-        return
-            self.create_local_x_rotation_matrix() *
-            self.create_local_y_rotation_matrix() *
-            self.create_local_z_rotation_matrix();
+/// A handle to a node within a [`Hierarchy`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HierarchyNodeId {
+    depth: usize,
+    index: usize,
+}
+
+#[derive(Debug)]
+struct HierarchyNode<S: Float> {
+    /// The index of the parent of this node within the level above, or `None` for a root.
+    parent: Option<usize>,
+    /// The position, rotation, and scale of this node relative to its parent.
+    transform: Transform,
+    /// The global transformation matrix of this node as of the last
+    /// [`update_transforms`](Hierarchy::update_transforms).
+    global: Matrix<S>,
+}
+
+impl<S: Float> Hierarchy<S> {
+    /// Creates a new, empty `Hierarchy`.
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    /// Creates a local transformation matrix for the X rotation transform of this node.
-    fn create_local_x_rotation_matrix(&self) -> Matrix {
-        let SinCos { sin: s, cos: c } = SinCos::new(self.rotation.x);
+    /// The number of nodes in this hierarchy.
+    pub fn len(&self) -> usize {
+        self.levels.iter().map(Vec::len).sum()
+    }
 
-        Matrix::new(
-            1., 0., 0., 0.,
-            0.,  c, -s, 0.,
-            0.,  s,  c, 0.,
-            0., 0., 0., 1.,
-        )
+    /// Whether or not this hierarchy contains no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
     }
 
-    /// Creates a local transformation matrix for the Y rotation transform of this node.
-    fn create_local_y_rotation_matrix(&self) -> Matrix {
-        let SinCos { sin: s, cos: c } =  SinCos::new(self.rotation.y);
+    /// Adds a node without a parent.
+    pub fn add_root(&mut self, transform: Transform) -> HierarchyNodeId {
+        self.push(0, None, transform)
+    }
 
-        Matrix::new(
-             c, 0.,  s, 0.,
-            0., 1., 0., 0.,
-            -s, 0.,  c, 0.,
-            0., 0., 0., 1.,
-        )
+    /// Adds a node that is a child of `parent`.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `parent` does not belong to this hierarchy.
+    pub fn add_child(&mut self, parent: HierarchyNodeId, transform: Transform) -> HierarchyNodeId {
+        // This validates `parent`.
+        let _ = self.node(parent);
+
+        self.push(parent.depth + 1, Some(parent.index), transform)
     }
 
-    /// Creates a local transformation matrix for the Z rotation transform of this node.
-    fn create_local_z_rotation_matrix(&self) -> Matrix {
-        let SinCos { sin: s, cos: c } = SinCos::new(self.rotation.z);
+    fn push(
+        &mut self,
+        depth: usize,
+        parent: Option<usize>,
+        transform: Transform,
+    ) -> HierarchyNodeId {
+        if depth == self.levels.len() {
+            self.levels.push(Vec::new());
+        }
+        let level = &mut self.levels[depth];
+        level.push(HierarchyNode {
+            parent,
+            transform,
+            global: Matrix::IDENTITY,
+        });
+
+        HierarchyNodeId { depth, index: level.len() - 1 }
+    }
 
-        Matrix::new(
-             c, -s, 0., 0.,
-             s,  c, 0., 0.,
-            0., 0., 1., 0.,
-            0., 0., 0., 1.,
-        )
+    /// The position, rotation, and scale of the given node relative to its parent.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `id` does not belong to this hierarchy.
+    pub fn transform(&self, id: HierarchyNodeId) -> &Transform {
+        &self.node(id).transform
     }
 
-    /// Creates a local transformation matrix for scale transform of this node.
+    /// A mutable reference to the transform of the given node.
     ///
-    /// This transform is applied first.
-    fn create_local_scale_matrix(&self) -> Matrix {
-        let f = self.scale;
+    /// The change is reflected in global transformation matrices after the next
+    /// [`update_transforms`](Self::update_transforms).
+    ///
+    /// # Panics
+    ///
+    /// This panics if `id` does not belong to this hierarchy.
+    pub fn transform_mut(&mut self, id: HierarchyNodeId) -> &mut Transform {
+        &mut self.levels[id.depth][id.index].transform
+    }
 
-        Matrix::new(
-             f, 0., 0., 0.,
-            0.,  f, 0., 0.,
-            0., 0.,  f, 0.,
-            0., 0., 0., 1.,
-        )
+    /// The global transformation matrix of the given node as of the last
+    /// [`update_transforms`](Self::update_transforms).
+    ///
+    /// This is the identity matrix for nodes added since.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `id` does not belong to this hierarchy.
+    pub fn global_transformation_matrix(&self, id: HierarchyNodeId) -> Matrix<S> {
+        self.node(id).global
     }
-}
 
-impl SinCos {
-    fn new(radians: f32) -> Self {
-        Self {
-            sin: radians.sin(),
-            cos: radians.cos(),
+    fn node(&self, id: HierarchyNodeId) -> &HierarchyNode<S> {
+        &self.levels[id.depth][id.index]
+    }
+
+    /// Computes the global transformation matrix of every node.
+    ///
+    /// Levels are processed from the roots down, as each node's global transformation matrix is
+    /// the product of its parent's and its own local transformation matrix. With the **rayon**
+    /// feature, the nodes within each level are processed in parallel.
+    pub fn update_transforms(&mut self) {
+        let mut parents: &[HierarchyNode<S>] = &[];
+        for level in self.levels.iter_mut() {
+            Self::update_level(parents, level);
+            parents = level;
+        }
+    }
+
+    fn update_level(parents: &[HierarchyNode<S>], level: &mut [HierarchyNode<S>]) {
+        let update = |node: &mut HierarchyNode<S>| {
+            let local: Matrix<S> = node.transform.to_matrix().cast();
+            // See `Node::create_global_transformation_matrix` for the order of multiplication.
+            node.global = match node.parent {
+                Some(parent) => parents[parent].global * local,
+                None => local,
+            };
+        };
+
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+
+            if level.len() >= MIN_PARALLEL_LEVEL_LEN {
+                level.par_iter_mut().for_each(update);
+                return;
+            }
         }
+
+        level.iter_mut().for_each(update);
     }
 }
 
-struct SinCos {
-    sin: f32,
-    cos: f32,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_non_uniformly() {
+        let mut node: Node = Node::default();
+        *node.scale_factors_mut() = [2., 1., 1.];
+
+        let point = node.local_transformation_matrix() * Point { x: 1., y: 0., z: 0. };
+        assert_eq!(point, Point { x: 2., y: 0., z: 0. });
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_scale_mut_scales_uniformly() {
+        let mut node: Node = Node::default();
+        *node.scale_mut() = 3.;
+        assert_eq!(node.scale_factors(), [3.; 3]);
+
+        *node.scale_mut() *= 2.;
+        assert_eq!(node.scale(), 6.);
+        assert_eq!(node.scale_factors(), [6.; 3]);
+    }
+
+    #[test]
+    fn applies_local_transform_before_parent_transform() {
+        // With `parent * local`, the child's translation is scaled by its parent. With
+        // `local * parent`, it would not be.
+        let expected = Point { x: 2., y: 0., z: 0. };
+        let translation = Point { x: 1., y: 0., z: 0. };
+
+        let parent: Rc<Node> = Node::root(Point::ORIGIN, Rotation::default(), 2.);
+        let child = Node::new(&parent, translation, Rotation::default(), 1.);
+        assert_eq!(child.global_transformation_matrix() * Point::ORIGIN, expected);
+
+        let mut hierarchy: Hierarchy = Hierarchy::new();
+        let parent = hierarchy.add_root(Transform::new(Point::ORIGIN, Rotation::default(), 2.));
+        let child =
+            hierarchy.add_child(parent, Transform::new(translation, Rotation::default(), 1.));
+        hierarchy.update_transforms();
+        assert_eq!(hierarchy.global_transformation_matrix(child) * Point::ORIGIN, expected);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "local transformation matrix of node is not finite")]
+    fn rejects_non_finite_local_transformation_matrix() {
+        let node: Rc<Node> = Node::root(Point::ORIGIN, Rotation::default(), f32::NAN);
+        node.local_transformation_matrix();
+    }
+
+    #[test]
+    fn rejects_parent_cycle() {
+        let a: Rc<Node> = Node::default().into();
+        let b = Node::new(&a, Point::ORIGIN, Rotation::default(), 1.);
+
+        // A -> B -> A would be a cycle, as would A -> A.
+        assert_eq!(a.set_parent(&b), Err(TreeError::Cycle));
+        assert_eq!(a.set_parent(&a), Err(TreeError::Cycle));
+        // The hierarchy is left as it was.
+        assert!(a.parent().upgrade().is_none());
+        assert!(Rc::ptr_eq(&b.parent().upgrade().unwrap(), &a));
+    }
+
+    #[test]
+    fn composes_deep_chain_without_recursion() {
+        const DEPTH: usize = 10_000;
+        let step = Point { x: 1., y: 0., z: 0. };
+
+        let mut chain: Vec<Rc<Node>> = vec![Node::root(Point::ORIGIN, Rotation::default(), 1.)];
+        for _ in 1..DEPTH {
+            let node = Node::new(chain.last().unwrap(), step, Rotation::default(), 1.);
+            chain.push(node);
+        }
+
+        let leaf = chain.last().unwrap().global_transformation_matrix();
+        assert_eq!(leaf * Point::ORIGIN, Point { x: (DEPTH - 1) as f32, y: 0., z: 0. });
+        // The global transformation matrices of the ancestors are cached along the way.
+        let middle = chain[DEPTH / 2].cached_transformation_matrices.global.get();
+        let expected = Point { x: (DEPTH / 2) as f32, y: 0., z: 0. };
+        assert_eq!(middle.map(|matrix| matrix * Point::ORIGIN), Some(expected));
+    }
+
+    #[test]
+    fn hierarchy_composes_in_f64() {
+        const DEPTH: usize = 1_000;
+        let step = Transform::new(Point { x: 0.1, y: 0., z: 0. }, Rotation::default(), 1.);
+
+        fn leaf_x<S: Float>(step: Transform) -> S {
+            let mut hierarchy = Hierarchy::<S>::new();
+            let mut node = hierarchy.add_root(step);
+            for _ in 1..DEPTH {
+                node = hierarchy.add_child(node, step);
+            }
+            hierarchy.update_transforms();
+            hierarchy.global_transformation_matrix(node).column(3).x()
+        }
+
+        // Local transformation matrices are computed in `f32`, so each step is `0.1f32` exactly;
+        // only the composition is carried out in `S`.
+        let expected: f64 = (0..DEPTH).map(|_| f64::from(0.1f32)).sum();
+        assert!((leaf_x::<f64>(step) - expected).abs() < 1e-9);
+        assert!((f64::from(leaf_x::<f32>(step)) - expected).abs() > 1e-6);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_update_matches_nodes() {
+        // Both levels below the root are wide enough to be updated in parallel.
+        const WIDTH: usize = 2 * MIN_PARALLEL_LEVEL_LEN;
+        let transform = |i: usize| {
+            let i = i as f32;
+            Transform::new(Point { x: i, y: -i, z: 0.5 * i }, Rotation::new(0.01 * i, 0., 0.1), 1.)
+        };
+        // `Node` composes the same matrices serially.
+        let node = |parent: &Rc<Node>, transform: Transform| {
+            Node::new(parent, transform.position, transform.rotation, 1.)
+        };
+
+        let mut hierarchy: Hierarchy = Hierarchy::new();
+        let root_id = hierarchy.add_root(transform(1));
+        let root: Rc<Node> = Node::root(transform(1).position, transform(1).rotation, 1.);
+        let mut leaves = Vec::new();
+        for i in 0..WIDTH {
+            let child_id = hierarchy.add_child(root_id, transform(i));
+            let child = node(&root, transform(i));
+            let grandchild_id = hierarchy.add_child(child_id, transform(WIDTH - i));
+            leaves.push((grandchild_id, node(&child, transform(WIDTH - i)), child));
+        }
+        hierarchy.update_transforms();
+
+        for (id, grandchild, _child) in &leaves {
+            assert_eq!(
+                hierarchy.global_transformation_matrix(*id).to_array(),
+                grandchild.global_transformation_matrix().to_array(),
+            );
+        }
+    }
 }
@@ -0,0 +1,1119 @@
+//! Rendering tests that run without a window.
+//!
+//! These require a graphics adapter, which may be a software one. On machines without any, set
+//! `PYLON_SKIP_GPU_TESTS` to skip them rather than fail.
+
+use std::{borrow::Cow, sync::Arc};
+
+use pylon_engine::{
+    renderer::{
+        BatchedObject,
+        DebugOverlay,
+        PipelineOptions,
+        RenderError,
+        RendererBuilder,
+        SurfaceSize,
+    },
+    BindGroupSlot,
+    Camera,
+    CameraResources,
+    CameraTransformsUniform,
+    Color,
+    Matrix,
+    Mesh,
+    MeshTriangle,
+    MeshVertex,
+    Object,
+    ObjectTransforms,
+    ObjectTransformsUniform,
+    Point,
+    Renderer,
+    Rotation,
+    Scene,
+    Vector,
+};
+use wgpu::util::DeviceExt as _;
+
+/// The width and height, in pixels, of the rendered frame.
+const FRAME_LENGTH: u32 = 4;
+
+/// Creates a headless renderer with an sRGB or linear surface.
+///
+/// See [`build_renderer`].
+fn create_renderer(srgb: bool) -> Option<Renderer> {
+    build_renderer(
+        Renderer::builder(SurfaceSize { width: FRAME_LENGTH, height: FRAME_LENGTH }).srgb(srgb),
+    )
+}
+
+/// Builds a headless renderer from the given builder.
+///
+/// # Panics
+///
+/// This panics if no adapter is available, unless the `PYLON_SKIP_GPU_TESTS` environment variable
+/// is set, in which case `None` is returned and the calling test passes without rendering.
+fn build_renderer(builder: RendererBuilder) -> Option<Renderer> {
+    match pollster::block_on(builder.build_headless()) {
+        Ok(renderer) => Some(renderer),
+        // Machines without an adapter, software or otherwise, must opt out explicitly.
+        Err(_) if std::env::var_os("PYLON_SKIP_GPU_TESTS").is_some() => None,
+        Err(e) => panic!("failed to create renderer (set PYLON_SKIP_GPU_TESTS to skip): {:?}", e),
+    }
+}
+
+/// Clears a frame to the given color and reads back its pixels.
+fn render_cleared_frame(renderer: &mut Renderer, color: Color) -> Vec<u8> {
+    renderer.set_clear_color(Some(color));
+
+    let identity: Matrix = Matrix::IDENTITY;
+    let camera_buffer = renderer.create_uniform_buffer(bytemuck::bytes_of(&identity.to_array()));
+    let camera = renderer.create_camera_transforms_uniform(
+        camera_buffer.as_entire_buffer_binding(),
+    );
+
+    let mut job = renderer.create_render();
+    job.add_pass().with_camera(&camera);
+    job.submit();
+
+    renderer.read_pixels().expect("renderer should be headless")
+}
+
+#[test]
+fn renders_cleared_frame() {
+    let mut renderer = match create_renderer(true) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+    let pixels = render_cleared_frame(&mut renderer, Color::new(1., 0., 0., 1.));
+    assert_eq!(pixels.len(), (4 * FRAME_LENGTH * FRAME_LENGTH) as usize);
+    for pixel in pixels.chunks_exact(4) {
+        // Pixels are in BGRA order.
+        assert_eq!(pixel, [0, 0, 255, 255]);
+    }
+}
+
+#[test]
+fn encodes_mid_gray_by_surface_format() {
+    let mid_gray = Color::new(0.5, 0.5, 0.5, 1.);
+    // The sRGB encoding of linear 0.5 is about 0.735, or 187.5 of 255.
+    for (srgb, expected) in [(true, 187..=188), (false, 127..=128)] {
+        let mut renderer = match create_renderer(srgb) {
+            Some(renderer) => renderer,
+            None => return,
+        };
+
+        let pixels = render_cleared_frame(&mut renderer, mid_gray);
+        for pixel in pixels.chunks_exact(4) {
+            for channel in &pixel[..3] {
+                assert!(
+                    expected.contains(channel),
+                    "channel {} is outside {:?} for an {} surface",
+                    channel,
+                    expected,
+                    if srgb { "sRGB" } else { "linear" },
+                );
+            }
+            assert_eq!(pixel[3], 255);
+        }
+    }
+}
+
+#[test]
+fn clears_without_drawing() {
+    let renderer = match create_renderer(true) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+    renderer.clear(Color::new(0., 1., 0., 1.));
+
+    let pixels = renderer.read_pixels().expect("renderer should be headless");
+    for pixel in pixels.chunks_exact(4) {
+        assert_eq!(pixel, [0, 255, 0, 255]);
+    }
+}
+
+#[test]
+fn renders_empty_scene() {
+    let mut renderer = match create_renderer(true) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+    renderer.set_clear_color(Some(Color::new(0., 1., 0., 1.)));
+
+    let scene: Scene = Scene::new(&renderer, Point { x: 0., y: 0., z: -1. }, Point::ORIGIN);
+    let stats = scene.render(&renderer).expect("empty scene should render");
+    assert_eq!(stats.objects, 0);
+
+    let pixels = renderer.read_pixels().expect("renderer should be headless");
+    for pixel in pixels.chunks_exact(4) {
+        assert_eq!(pixel, [0, 255, 0, 255]);
+    }
+}
+
+#[test]
+fn clears_job_without_passes() {
+    let mut renderer = match create_renderer(true) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+    renderer.set_clear_color(Some(Color::new(0., 1., 0., 1.)));
+
+    renderer.create_render().submit();
+
+    let pixels = renderer.read_pixels().expect("renderer should be headless");
+    for pixel in pixels.chunks_exact(4) {
+        assert_eq!(pixel, [0, 255, 0, 255]);
+    }
+}
+
+/// A quad whose vertex and index buffers are separate buffers that may be shared.
+struct SharedQuad<'a> {
+    vertex_buffer: &'a wgpu::Buffer,
+    index_buffer: &'a wgpu::Buffer,
+    render_pipeline: &'a wgpu::RenderPipeline,
+    transforms_uniform: ObjectTransformsUniform,
+    bind_group_slots: Vec<BindGroupSlot<'a>>,
+    visible: bool,
+    /// The buffer behind [`transforms_uniform`](Self::transforms_uniform) and the offset of the
+    /// quad's transforms within it, if exposed.
+    transforms_buffer: Option<(&'a wgpu::Buffer, wgpu::BufferAddress)>,
+}
+
+impl Object for SharedQuad<'_> {
+    fn triangle_count(&self) -> u32 {
+        2
+    }
+
+    fn vertex_count(&self) -> u32 {
+        8
+    }
+
+    fn render_pipeline(&self) -> &wgpu::RenderPipeline {
+        self.render_pipeline
+    }
+
+    fn transforms_uniform(&self) -> &ObjectTransformsUniform {
+        &self.transforms_uniform
+    }
+
+    fn transforms_buffer(&self) -> Option<(&wgpu::Buffer, wgpu::BufferAddress)> {
+        self.transforms_buffer
+    }
+
+    fn visible(&self) -> bool {
+        self.visible
+    }
+
+    fn bind_group_slots<'a>(&'a self) -> &'a [BindGroupSlot<'a>] {
+        &self.bind_group_slots
+    }
+
+    fn index_buffer<'a>(&'a self) -> wgpu::BufferSlice<'a> {
+        self.index_buffer.slice(..)
+    }
+
+    fn vertex_buffer<'a>(&'a self) -> wgpu::BufferSlice<'a> {
+        self.vertex_buffer.slice(..)
+    }
+}
+
+#[test]
+fn draws_separate_buffers_with_base_vertex() {
+    let mut renderer = match create_renderer(true) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+    renderer.set_clear_color(Some(Color::new(1., 0., 0., 1.)));
+
+    // The first quad is degenerate, so only the second, which covers the frame, is visible.
+    let corner = |x, y| MeshVertex::new(Point { x, y, z: 0.5 }, Point::ORIGIN);
+    let vertices = [
+        [MeshVertex::new(Point::ORIGIN, Point::ORIGIN); 4],
+        [corner(-1., -1.), corner(1., -1.), corner(1., 1.), corner(-1., 1.)],
+    ];
+    // Both quads share these indices, which are relative to the first vertex of a quad.
+    let indices: [u32; 6] = [0, 1, 2, 0, 2, 3];
+    let device = renderer.device();
+    let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: None,
+        contents: bytemuck::cast_slice(&vertices),
+        usage: wgpu::BufferUsages::VERTEX,
+    });
+    let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: None,
+        contents: bytemuck::cast_slice(&indices),
+        usage: wgpu::BufferUsages::INDEX,
+    });
+
+    let identity: Matrix = Matrix::IDENTITY;
+    let camera_buffer = renderer.create_uniform_buffer(bytemuck::bytes_of(&identity.to_array()));
+    let camera = renderer.create_camera_transforms_uniform(
+        camera_buffer.as_entire_buffer_binding(),
+    );
+    let transforms_buffer =
+        renderer.create_uniform_buffer(bytemuck::bytes_of(&ObjectTransforms::new(&identity)));
+    let quad = SharedQuad {
+        vertex_buffer: &vertex_buffer,
+        index_buffer: &index_buffer,
+        render_pipeline: &pollster::block_on(renderer.create_pipeline(wgpu::ShaderSource::Wgsl(
+            Cow::Borrowed("
+                @fragment
+                fn main(@location(0) normal: vec3<f32>) -> @location(0) vec4<f32> {
+                    return vec4<f32>(0.0, 1.0, 0.0, 1.0);
+                }
+            "),
+        )))
+        .expect("pipeline should be valid"),
+        transforms_uniform: renderer.create_object_transforms_uniform(
+            transforms_buffer.as_entire_buffer_binding(),
+        ),
+        bind_group_slots: Vec::new(),
+        visible: true,
+        transforms_buffer: None,
+    };
+
+    let mut job = renderer.create_render();
+    job.add_pass()
+        .with_camera(&camera)
+        .draw_object_with_base_vertex(&quad, 4)
+        .expect("quad should draw");
+    job.submit();
+
+    let pixels = renderer.read_pixels().expect("renderer should be headless");
+    for pixel in pixels.chunks_exact(4) {
+        assert_eq!(pixel, [0, 255, 0, 255]);
+    }
+}
+
+#[test]
+fn draws_vertex_colors() {
+    let mut renderer = match create_renderer(true) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+    renderer.set_clear_color(Some(Color::new(1., 0., 0., 1.)));
+
+    // Both quads cover the frame; only the first is drawn.
+    let corner = |x, y| {
+        MeshVertex::new(Point { x, y, z: 0.5 }, Point::ORIGIN).with_color([0., 0., 1., 1.])
+    };
+    let quad = [corner(-1., -1.), corner(1., -1.), corner(1., 1.), corner(-1., 1.)];
+    let vertices = [quad, quad];
+    let indices: [u32; 6] = [0, 1, 2, 0, 2, 3];
+    let device = renderer.device();
+    let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: None,
+        contents: bytemuck::cast_slice(&vertices),
+        usage: wgpu::BufferUsages::VERTEX,
+    });
+    let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: None,
+        contents: bytemuck::cast_slice(&indices),
+        usage: wgpu::BufferUsages::INDEX,
+    });
+
+    let identity: Matrix = Matrix::IDENTITY;
+    let camera_buffer = renderer.create_uniform_buffer(bytemuck::bytes_of(&identity.to_array()));
+    let camera = renderer.create_camera_transforms_uniform(
+        camera_buffer.as_entire_buffer_binding(),
+    );
+    let transforms_buffer =
+        renderer.create_uniform_buffer(bytemuck::bytes_of(&ObjectTransforms::new(&identity)));
+    let quad = SharedQuad {
+        vertex_buffer: &vertex_buffer,
+        index_buffer: &index_buffer,
+        render_pipeline: &pollster::block_on(renderer.create_vertex_colored_pipeline(
+            wgpu::ShaderSource::Wgsl(Cow::Borrowed("
+                @fragment
+                fn main(
+                    @location(0) normal: vec3<f32>,
+                    @location(1) color: vec4<f32>,
+                ) -> @location(0) vec4<f32> {
+                    return color;
+                }
+            ")),
+            &Default::default(),
+        ))
+        .expect("pipeline should be valid"),
+        transforms_uniform: renderer.create_object_transforms_uniform(
+            transforms_buffer.as_entire_buffer_binding(),
+        ),
+        bind_group_slots: Vec::new(),
+        visible: true,
+        transforms_buffer: None,
+    };
+
+    let mut job = renderer.create_render();
+    job.add_pass().with_camera(&camera).draw_object(&quad).expect("quad should draw");
+    job.submit();
+
+    let pixels = renderer.read_pixels().expect("renderer should be headless");
+    for pixel in pixels.chunks_exact(4) {
+        // Pixels are in BGRA order.
+        assert_eq!(pixel, [255, 0, 0, 255]);
+    }
+}
+
+/// Creates the uniform of a camera whose transformation matrix is the identity, along with the
+/// buffer behind it.
+fn create_identity_camera(renderer: &Renderer) -> (wgpu::Buffer, CameraTransformsUniform) {
+    let identity: Matrix = Matrix::IDENTITY;
+    let camera_buffer = renderer.create_uniform_buffer(bytemuck::bytes_of(&identity.to_array()));
+    let camera = renderer.create_camera_transforms_uniform(
+        camera_buffer.as_entire_buffer_binding(),
+    );
+
+    (camera_buffer, camera)
+}
+
+/// Creates the buffers of a quad that covers the frame, as drawn by a [`SharedQuad`].
+fn create_quad_buffers(renderer: &Renderer) -> (wgpu::Buffer, wgpu::Buffer) {
+    let corner = |x, y| MeshVertex::new(Point { x, y, z: 0.5 }, Point::ORIGIN);
+    let vertices = [corner(-1., -1.), corner(1., -1.), corner(1., 1.), corner(-1., 1.)];
+    let indices: [u32; 6] = [0, 1, 2, 0, 2, 3];
+    let device = renderer.device();
+    let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: None,
+        contents: bytemuck::cast_slice(&vertices),
+        usage: wgpu::BufferUsages::VERTEX,
+    });
+    let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: None,
+        contents: bytemuck::cast_slice(&indices),
+        usage: wgpu::BufferUsages::INDEX,
+    });
+
+    (vertex_buffer, index_buffer)
+}
+
+/// Creates a pipeline that draws in the given linear color.
+fn create_solid_pipeline(renderer: &Renderer, [r, g, b]: [f32; 3]) -> wgpu::RenderPipeline {
+    create_color_pipeline(renderer, [r, g, b, 1.], &PipelineOptions::default())
+}
+
+/// Creates a pipeline with the given options that draws in the given linear color and alpha.
+fn create_color_pipeline(
+    renderer: &Renderer,
+    [r, g, b, a]: [f32; 4],
+    options: &PipelineOptions,
+) -> wgpu::RenderPipeline {
+    let shader = format!(
+        "
+        @fragment
+        fn main(@location(0) normal: vec3<f32>) -> @location(0) vec4<f32> {{
+            return vec4<f32>({:?}, {:?}, {:?}, {:?});
+        }}
+        ",
+        r,
+        g,
+        b,
+        a,
+    );
+    let source = wgpu::ShaderSource::Wgsl(Cow::Owned(shader));
+
+    pollster::block_on(renderer.create_pipeline_with_options(source, options))
+        .expect("pipeline should be valid")
+}
+
+/// Creates a quad that covers the frame from the given buffers and pipeline, which is placed by the
+/// identity matrix.
+fn create_quad<'a>(
+    renderer: &Renderer,
+    (vertex_buffer, index_buffer): &'a (wgpu::Buffer, wgpu::Buffer),
+    render_pipeline: &'a wgpu::RenderPipeline,
+) -> SharedQuad<'a> {
+    let identity: Matrix = Matrix::IDENTITY;
+    let transforms_buffer =
+        renderer.create_uniform_buffer(bytemuck::bytes_of(&ObjectTransforms::new(&identity)));
+
+    SharedQuad {
+        vertex_buffer,
+        index_buffer,
+        render_pipeline,
+        transforms_uniform: renderer.create_object_transforms_uniform(
+            transforms_buffer.as_entire_buffer_binding(),
+        ),
+        bind_group_slots: Vec::new(),
+        visible: true,
+        transforms_buffer: None,
+    }
+}
+
+#[test]
+fn rejects_out_of_range_bind_group_slot() {
+    let renderer = match create_renderer(true) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+
+    let device = renderer.device();
+    let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: None,
+        entries: &[],
+    });
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        layout: &layout,
+        entries: &[],
+    });
+    let max_bind_groups = device.limits().max_bind_groups;
+
+    let buffers = create_quad_buffers(&renderer);
+    let pipeline = create_solid_pipeline(&renderer, [0., 1., 0.]);
+    let mut quad = create_quad(&renderer, &buffers, &pipeline);
+    quad.bind_group_slots.push(BindGroupSlot { index: max_bind_groups, bind_group: &bind_group });
+
+    let mut job = renderer.create_render();
+    let mut pass = job.add_pass();
+    let result = pass.draw_object(&quad).map(|_| ());
+    assert!(
+        matches!(
+            result,
+            Err(RenderError::BindGroupSlotOutOfRange { index, max_bind_groups: max })
+                if index == max_bind_groups && max == max_bind_groups
+        ),
+        "drawing with bind group slot {} should fail",
+        max_bind_groups,
+    );
+    assert_eq!(pass.stats().draw_calls, 0);
+    drop(pass);
+    job.submit();
+}
+
+#[test]
+fn batches_interleaved_objects_by_pipeline() {
+    let renderer = match create_renderer(true) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+
+    let buffers = create_quad_buffers(&renderer);
+    let pipelines = [
+        create_solid_pipeline(&renderer, [1., 0., 0.]),
+        create_solid_pipeline(&renderer, [0., 1., 0.]),
+    ];
+    // Of N = 6 objects, every other one uses the same of P = 2 pipelines.
+    let quads: Vec<SharedQuad> = (0..6)
+        .map(|i| create_quad(&renderer, &buffers, &pipelines[i % 2]))
+        .collect();
+    let (_camera_buffer, camera) = create_identity_camera(&renderer);
+
+    let mut job = renderer.create_render();
+    let mut pass = job.add_pass().with_camera(&camera);
+    for quad in &quads {
+        pass.draw_object(quad).expect("quad should draw");
+    }
+    let unbatched = pass.stats();
+    drop(pass);
+
+    let mut pass = job.add_pass().with_camera(&camera);
+    pass.draw_objects_batched(&quads).expect("quads should draw");
+    let batched = pass.stats();
+    drop(pass);
+    job.submit();
+
+    // Drawn in order, the pipeline is switched for every object; batched, once per pipeline.
+    assert_eq!(unbatched.pipeline_switches, 6);
+    assert_eq!(batched.pipeline_switches, 2);
+    assert_eq!(batched.draw_calls, unbatched.draw_calls);
+}
+
+/// A [`SharedQuad`] of which only the first triangle is drawn.
+struct HalfQuad<'a>(SharedQuad<'a>);
+
+impl Object for HalfQuad<'_> {
+    fn triangle_count(&self) -> u32 {
+        1
+    }
+
+    fn vertex_count(&self) -> u32 {
+        self.0.vertex_count()
+    }
+
+    fn render_pipeline(&self) -> &wgpu::RenderPipeline {
+        self.0.render_pipeline()
+    }
+
+    fn transforms_uniform(&self) -> &ObjectTransformsUniform {
+        self.0.transforms_uniform()
+    }
+
+    fn bind_group_slots<'a>(&'a self) -> &'a [BindGroupSlot<'a>] {
+        self.0.bind_group_slots()
+    }
+
+    fn index_buffer<'a>(&'a self) -> wgpu::BufferSlice<'a> {
+        self.0.index_buffer()
+    }
+
+    fn vertex_buffer<'a>(&'a self) -> wgpu::BufferSlice<'a> {
+        self.0.vertex_buffer()
+    }
+}
+
+#[test]
+fn draws_different_object_types_together() {
+    let renderer = match create_renderer(true) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+
+    let buffers = create_quad_buffers(&renderer);
+    let pipelines = [
+        create_solid_pipeline(&renderer, [1., 0., 0.]),
+        create_solid_pipeline(&renderer, [0., 1., 0.]),
+    ];
+    let quads = [
+        create_quad(&renderer, &buffers, &pipelines[0]),
+        create_quad(&renderer, &buffers, &pipelines[0]),
+    ];
+    let half_quad = HalfQuad(create_quad(&renderer, &buffers, &pipelines[1]));
+    let objects: [&dyn Object; 3] = [&quads[0], &half_quad, &quads[1]];
+    let (_camera_buffer, camera) = create_identity_camera(&renderer);
+
+    let mut job = renderer.create_render();
+    let mut pass = job.add_pass().with_camera(&camera);
+    pass.draw_objects_dyn(&objects).expect("objects should draw");
+    let stats = pass.stats();
+    drop(pass);
+    job.submit();
+
+    assert_eq!(stats.objects, 3);
+    assert_eq!(stats.triangles, 2 + 1 + 2);
+    // The quads share a pipeline, so they are drawn together.
+    assert_eq!(stats.pipeline_switches, 2);
+}
+
+#[test]
+fn grows_dynamic_mesh_only_past_capacity() {
+    let renderer = match create_renderer(true) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+    let vertices = |count| vec![MeshVertex::new(Point::ORIGIN, Point::ORIGIN); count];
+    let triangles = |count| vec![MeshTriangle::new([0, 0, 0]); count];
+
+    let mut mesh = renderer.create_dynamic_mesh_with_capacity(4, 2);
+    let (vertex_buffer_size, index_buffer_size) =
+        (mesh.vertex_buffer_size(), mesh.index_buffer_size());
+
+    // Updates within capacity write in place.
+    for count in [3, 4, 0] {
+        mesh.update_vertices(&renderer, &vertices(count));
+        mesh.update_triangles(&renderer, &triangles(count / 2));
+        assert_eq!(mesh.vertex_count(), count as u32);
+        assert_eq!((mesh.vertex_capacity(), mesh.triangle_capacity()), (4, 2));
+        assert_eq!(mesh.vertex_buffer_size(), vertex_buffer_size);
+        assert_eq!(mesh.index_buffer_size(), index_buffer_size);
+    }
+
+    // Exceeding capacity by one element doubles it.
+    mesh.update_vertices(&renderer, &vertices(5));
+    mesh.update_triangles(&renderer, &triangles(3));
+    assert_eq!((mesh.vertex_capacity(), mesh.triangle_capacity()), (8, 4));
+    assert_eq!(mesh.vertex_buffer_size(), 2 * vertex_buffer_size);
+    assert_eq!(mesh.index_buffer_size(), 2 * index_buffer_size);
+
+    // Exceeding double the capacity grows it to exactly what is needed.
+    mesh.update_vertices(&renderer, &vertices(20));
+    assert_eq!(mesh.vertex_capacity(), 20);
+
+    // Capacity never shrinks.
+    mesh.update_vertices(&renderer, &vertices(1));
+    assert_eq!(mesh.vertex_capacity(), 20);
+}
+
+#[test]
+fn skips_invisible_objects() {
+    let mut renderer = match create_renderer(true) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+    renderer.set_clear_color(Some(Color::new(1., 0., 0., 1.)));
+
+    let buffers = create_quad_buffers(&renderer);
+    let pipeline = create_solid_pipeline(&renderer, [0., 1., 0.]);
+    let mut quad = create_quad(&renderer, &buffers, &pipeline);
+    quad.visible = false;
+    let (_camera_buffer, camera) = create_identity_camera(&renderer);
+
+    let mut job = renderer.create_render();
+    let mut pass = job.add_pass().with_camera(&camera);
+    pass.draw_object(&quad).expect("invisible quad should be skipped");
+    let stats = pass.stats();
+    drop(pass);
+    job.submit();
+
+    assert_eq!(stats.draw_calls, 0);
+    assert_eq!(stats.objects, 0);
+    let pixels = renderer.read_pixels().expect("renderer should be headless");
+    for pixel in pixels.chunks_exact(4) {
+        assert_eq!(pixel, [0, 0, 255, 255]);
+    }
+}
+
+#[test]
+fn shares_cached_pipelines_between_identical_requests() {
+    let renderer = match create_renderer(true) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+    let source = "
+        @fragment
+        fn main(@location(0) normal: vec3<f32>) -> @location(0) vec4<f32> {
+            return vec4<f32>(1.0, 1.0, 1.0, 1.0);
+        }
+    ";
+    let request = |options: &PipelineOptions| {
+        let source = wgpu::ShaderSource::Wgsl(Cow::Borrowed(source));
+        pollster::block_on(renderer.create_pipeline_cached(source, options))
+            .expect("pipeline should be valid")
+    };
+
+    let options = PipelineOptions::default();
+    let first = request(&options);
+    for _ in 0..3 {
+        assert!(Arc::ptr_eq(&request(&options), &first));
+        assert_eq!(renderer.cached_pipeline_count(), 1);
+    }
+
+    // Any difference in the options is a different request.
+    let read_only = PipelineOptions { depth_write_enabled: false, ..PipelineOptions::default() };
+    assert!(!Arc::ptr_eq(&request(&read_only), &first));
+    assert_eq!(renderer.cached_pipeline_count(), 2);
+
+    renderer.clear_pipeline_cache();
+    assert_eq!(renderer.cached_pipeline_count(), 0);
+    assert!(!Arc::ptr_eq(&request(&options), &first));
+}
+
+#[test]
+fn updates_object_transforms_in_buffer() {
+    let renderer = match create_renderer(true) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+    let device = renderer.device();
+    let transforms_size = std::mem::size_of::<ObjectTransforms>() as wgpu::BufferAddress;
+    // The transforms of the quad follow those of another object.
+    let offset = 256;
+    let transforms_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: offset + transforms_size,
+        usage: wgpu::BufferUsages::UNIFORM
+            | wgpu::BufferUsages::COPY_DST
+            | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+    let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: transforms_size,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let buffers = create_quad_buffers(&renderer);
+    let pipeline = create_solid_pipeline(&renderer, [0., 1., 0.]);
+    let mut quad = create_quad(&renderer, &buffers, &pipeline);
+    quad.transforms_buffer = Some((&transforms_buffer, offset));
+
+    let matrix = Matrix::new(
+        2., 0., 0., 1.,
+        0., 3., 0., 2.,
+        0., 0., 4., 3.,
+        0., 0., 0., 1.,
+    );
+    renderer.update_object_transform(&quad, &matrix);
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    encoder.copy_buffer_to_buffer(&transforms_buffer, offset, &readback_buffer, 0, transforms_size);
+    renderer.queue().submit(Some(encoder.finish()));
+    let slice = readback_buffer.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |result| result.expect("buffer should map"));
+    device.poll(wgpu::Maintain::Wait);
+
+    assert_eq!(&slice.get_mapped_range()[..], bytemuck::bytes_of(&ObjectTransforms::new(&matrix)));
+}
+
+#[test]
+fn blends_translucent_quad_over_clear_color() {
+    // A linear surface keeps the arithmetic of blending exact.
+    let mut renderer = match create_renderer(false) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+    renderer.set_clear_color(Some(Color::new(1., 0., 0., 1.)));
+
+    let buffers = create_quad_buffers(&renderer);
+    let options = PipelineOptions::transparent();
+    let pipeline = create_color_pipeline(&renderer, [0., 1., 0., 0.5], &options);
+    let quad = create_quad(&renderer, &buffers, &pipeline);
+    let (_camera_buffer, camera) = create_identity_camera(&renderer);
+
+    let mut job = renderer.create_render();
+    job.add_pass().with_camera(&camera).draw_object(&quad).expect("quad should draw");
+    job.submit();
+
+    let pixels = renderer.read_pixels().expect("renderer should be headless");
+    for pixel in pixels.chunks_exact(4) {
+        // Half of the green quad over half of the red clear color, in BGRA order.
+        let [b, g, r, _] = [pixel[0], pixel[1], pixel[2], pixel[3]];
+        assert_eq!(b, 0);
+        assert!((127..=128).contains(&g) && (127..=128).contains(&r), "{:?}", pixel);
+    }
+}
+
+#[test]
+fn returns_error_for_invalid_wgsl() {
+    let renderer = match create_renderer(true) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+
+    let shader = "@fragment fn main() -> @location(0) vec4<f32> { return undefined_color; }";
+    let result = pollster::block_on(
+        renderer.create_pipeline(wgpu::ShaderSource::Wgsl(Cow::Borrowed(shader))),
+    );
+    let error = result.expect_err("invalid WGSL should fail to compile");
+    assert!(!error.message.is_empty());
+
+    // The renderer remains usable.
+    create_solid_pipeline(&renderer, [1., 1., 1.]);
+}
+
+#[test]
+fn skips_wireframe_without_polygon_mode_line() {
+    let mut renderer = match create_renderer(true) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+    // `create_renderer` requests no features, so wireframes are unsupported.
+    assert!(!renderer.device().features().contains(wgpu::Features::POLYGON_MODE_LINE));
+    renderer.set_clear_color(Some(Color::new(0., 0., 0., 1.)));
+
+    let buffers = create_quad_buffers(&renderer);
+    let pipeline = create_solid_pipeline(&renderer, [1., 1., 1.]);
+    let quad = create_quad(&renderer, &buffers, &pipeline);
+    let (_camera_buffer, camera) = create_identity_camera(&renderer);
+
+    let mut job = renderer.create_render();
+    job.add_pass().with_camera(&camera);
+    let overlay = DebugOverlay { wireframe: true, normals: false };
+    renderer.render_debug(&mut job, &camera, [&quad], overlay);
+    job.submit();
+
+    // The wireframe is skipped rather than drawn or panicking.
+    let pixels = renderer.read_pixels().expect("renderer should be headless");
+    assert!(pixels.chunks_exact(4).all(|pixel| pixel == [0, 0, 0, 255]));
+}
+
+/// An object drawn from one of the meshes of a [`MeshBatch`](pylon_engine::renderer::MeshBatch).
+struct BatchedQuad<'a> {
+    mesh_index: usize,
+    render_pipeline: &'a wgpu::RenderPipeline,
+    transforms_uniform: ObjectTransformsUniform,
+}
+
+impl BatchedObject for BatchedQuad<'_> {
+    fn mesh_index(&self) -> usize {
+        self.mesh_index
+    }
+
+    fn render_pipeline(&self) -> &wgpu::RenderPipeline {
+        self.render_pipeline
+    }
+
+    fn transforms_uniform(&self) -> &ObjectTransformsUniform {
+        &self.transforms_uniform
+    }
+}
+
+#[test]
+fn draws_second_mesh_of_batch() {
+    let mut renderer = match create_renderer(true) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+    renderer.set_clear_color(Some(Color::new(1., 0., 0., 1.)));
+
+    // The first mesh is a degenerate triangle, so only the second, a quad that covers the frame,
+    // is visible; it is drawn only if its base vertex and index range are honored.
+    let triangle = Mesh::from_arrays(&[[0., 0., 0.]; 3], None, None, &[0, 1, 2])
+        .expect("triangle should be valid");
+    let quad_positions = [[-1., -1., 0.5], [1., -1., 0.5], [1., 1., 0.5], [-1., 1., 0.5]];
+    let quad = Mesh::from_arrays(&quad_positions, None, None, &[0, 1, 2, 0, 2, 3])
+        .expect("quad should be valid");
+    let batch = renderer.create_mesh_batch([&triangle, &quad]);
+    assert_eq!(batch.len(), 2);
+
+    let pipeline = create_solid_pipeline(&renderer, [0., 1., 0.]);
+    let identity: Matrix = Matrix::IDENTITY;
+    let transforms_buffer =
+        renderer.create_uniform_buffer(bytemuck::bytes_of(&ObjectTransforms::new(&identity)));
+    let object = BatchedQuad {
+        mesh_index: 1,
+        render_pipeline: &pipeline,
+        transforms_uniform: renderer.create_object_transforms_uniform(
+            transforms_buffer.as_entire_buffer_binding(),
+        ),
+    };
+    let (_camera_buffer, camera) = create_identity_camera(&renderer);
+
+    let mut job = renderer.create_render();
+    job.add_pass()
+        .with_camera(&camera)
+        .draw_batch(&batch, [&object])
+        .expect("batched quad should draw");
+    job.submit();
+
+    let pixels = renderer.read_pixels().expect("renderer should be headless");
+    assert!(pixels.chunks_exact(4).all(|pixel| pixel == [0, 255, 0, 255]));
+}
+
+#[test]
+fn rejects_out_of_range_mesh_index() {
+    let renderer = match create_renderer(true) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+
+    let triangle = Mesh::from_arrays(&[[0., 0., 0.]; 3], None, None, &[0, 1, 2])
+        .expect("triangle should be valid");
+    let batch = renderer.create_mesh_batch([&triangle]);
+
+    let pipeline = create_solid_pipeline(&renderer, [0., 1., 0.]);
+    let identity: Matrix = Matrix::IDENTITY;
+    let transforms_buffer =
+        renderer.create_uniform_buffer(bytemuck::bytes_of(&ObjectTransforms::new(&identity)));
+    let object = BatchedQuad {
+        mesh_index: 1,
+        render_pipeline: &pipeline,
+        transforms_uniform: renderer.create_object_transforms_uniform(
+            transforms_buffer.as_entire_buffer_binding(),
+        ),
+    };
+    let (_camera_buffer, camera) = create_identity_camera(&renderer);
+
+    let mut job = renderer.create_render();
+    let result = job.add_pass().with_camera(&camera).draw_batch(&batch, [&object]).map(|_| ());
+    assert!(matches!(
+        result,
+        Err(RenderError::MeshIndexOutOfRange { index: 1, mesh_count: 1 }),
+    ));
+}
+
+#[test]
+fn masks_draws_with_stencil_reference() {
+    let builder = Renderer::builder(SurfaceSize { width: FRAME_LENGTH, height: FRAME_LENGTH });
+    let mut renderer = match build_renderer(builder.stencil(true)) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+    renderer.set_clear_color(Some(Color::new(1., 0., 0., 1.)));
+
+    let face = |compare, pass_op| wgpu::StencilFaceState {
+        compare,
+        fail_op: wgpu::StencilOperation::Keep,
+        depth_fail_op: wgpu::StencilOperation::Keep,
+        pass_op,
+    };
+    let stencil = |face: wgpu::StencilFaceState| wgpu::StencilState {
+        front: face,
+        back: face,
+        read_mask: 0xff,
+        write_mask: 0xff,
+    };
+    // The mask writes the reference value, but no depth, so that the quad may be drawn over it.
+    let mask_options = PipelineOptions {
+        depth_write_enabled: false,
+        stencil: stencil(face(wgpu::CompareFunction::Always, wgpu::StencilOperation::Replace)),
+        ..Default::default()
+    };
+    let masked_options = PipelineOptions {
+        stencil: stencil(face(wgpu::CompareFunction::Equal, wgpu::StencilOperation::Keep)),
+        ..Default::default()
+    };
+    let mask_pipeline = create_color_pipeline(&renderer, [0., 0., 1., 1.], &mask_options);
+    let masked_pipeline = create_color_pipeline(&renderer, [0., 1., 0., 1.], &masked_options);
+
+    let buffers = create_quad_buffers(&renderer);
+    // The vertex shader flips Y, so the mask covers the upper-right half of the frame.
+    let mask = HalfQuad(create_quad(&renderer, &buffers, &mask_pipeline));
+    let quad = create_quad(&renderer, &buffers, &masked_pipeline);
+    let (_camera_buffer, camera) = create_identity_camera(&renderer);
+
+    let mut job = renderer.create_render();
+    let mut pass = job.add_pass().with_camera(&camera);
+    pass.set_stencil_reference(1);
+    pass.draw_object(&mask).expect("mask should draw");
+    pass.draw_object(&quad).expect("quad should draw");
+    drop(pass);
+    job.submit();
+
+    let pixels = renderer.read_pixels().expect("renderer should be headless");
+    for (i, pixel) in pixels.chunks_exact(4).enumerate() {
+        let (x, y) = (i as u32 % FRAME_LENGTH, i as u32 / FRAME_LENGTH);
+        // Pixels on the diagonal edge of the mask may fall on either side of it.
+        if x > y {
+            assert_eq!(pixel, [0, 255, 0, 255], "pixel ({}, {}) should pass", x, y);
+        } else if x < y {
+            assert_eq!(pixel, [0, 0, 255, 255], "pixel ({}, {}) should be masked", x, y);
+        }
+    }
+}
+
+#[test]
+fn draws_instances_in_their_own_colors() {
+    let mut renderer = match create_renderer(false) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+    renderer.set_clear_color(Some(Color::new(0., 0., 0., 1.)));
+
+    let shader = "
+        @fragment
+        fn main(
+            @location(0) normal: vec3<f32>,
+            @location(1) color: vec4<f32>,
+        ) -> @location(0) vec4<f32> {
+            return color;
+        }
+    ";
+    let pipeline = pollster::block_on(renderer.create_instanced_pipeline(
+        wgpu::ShaderSource::Wgsl(Cow::Borrowed(shader)),
+        &PipelineOptions::default(),
+    ))
+    .expect("instanced pipeline should be valid");
+    let buffers = create_quad_buffers(&renderer);
+    let quad = create_quad(&renderer, &buffers, &pipeline);
+    // Each instance squeezes the quad into one half of the frame.
+    let half = |x: f32| {
+        Matrix::from_scale_rotation_translation(
+            Vector::from_direction([0.5, 1., 1.]),
+            Rotation::default(),
+            Vector::from_direction([x, 0., 0.]),
+        )
+    };
+    let instances = renderer.create_instance_buffer(&[
+        (half(-0.5), Color::new(1., 0., 0., 1.)),
+        (half(0.5), Color::new(0., 0., 1., 1.)),
+    ]);
+    let (_camera_buffer, camera) = create_identity_camera(&renderer);
+
+    let mut job = renderer.create_render();
+    job.add_pass()
+        .with_camera(&camera)
+        .draw_object_instanced(&quad, &instances)
+        .expect("instances should draw");
+    job.submit();
+
+    let pixels = renderer.read_pixels().expect("renderer should be headless");
+    for (i, pixel) in pixels.chunks_exact(4).enumerate() {
+        let x = i as u32 % FRAME_LENGTH;
+        // The left instance is red and the right one blue, in BGRA order.
+        let expected = if x < FRAME_LENGTH / 2 { [0, 0, 255, 255] } else { [255, 0, 0, 255] };
+        assert_eq!(pixel, expected, "pixel {}", i);
+    }
+}
+
+/// A writer that appends to a shared buffer, for capturing log output.
+#[derive(Clone, Default)]
+struct CapturedLog(Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl std::io::Write for CapturedLog {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn logs_validation_errors_instead_of_panicking() {
+    let builder = Renderer::builder(SurfaceSize { width: FRAME_LENGTH, height: FRAME_LENGTH });
+    let renderer = match build_renderer(builder.log_validation_errors(true)) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+
+    let log = CapturedLog::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_ansi(false)
+        .with_writer({
+            let log = log.clone();
+            move || log.clone()
+        })
+        .finish();
+    tracing::subscriber::with_default(subscriber, || {
+        // The vertex buffer would far exceed the maximum buffer size.
+        let limit = renderer.device().limits().max_buffer_size;
+        renderer.create_dynamic_mesh_with_capacity(limit as usize, 1);
+    });
+
+    let log = String::from_utf8(log.0.lock().unwrap().clone()).unwrap();
+    assert!(log.contains("ERROR"), "{}", log);
+    assert!(log.contains("wgpu validation error during dynamic mesh buffer creation"), "{}", log);
+}
+
+#[test]
+fn renders_skybox_in_view_direction() {
+    let renderer = match create_renderer(true) {
+        Some(renderer) => renderer,
+        None => return,
+    };
+
+    // Each face is a solid color, and the faces are large enough that no pixel is filtered across
+    // an edge between them.
+    const FACE_LENGTH: u32 = 16;
+    let colors: [[u8; 4]; 6] = [
+        [255, 0, 0, 255],
+        [0, 255, 0, 255],
+        [0, 0, 255, 255],
+        [255, 255, 0, 255],
+        [0, 255, 255, 255],
+        [255, 0, 255, 255],
+    ];
+    let faces = colors.map(|color| color.repeat((FACE_LENGTH * FACE_LENGTH) as usize));
+    let cubemap = renderer.create_cubemap(FACE_LENGTH, [
+        &faces[0],
+        &faces[1],
+        &faces[2],
+        &faces[3],
+        &faces[4],
+        &faces[5],
+    ]);
+
+    // The camera's position is ignored; only the direction of view matters.
+    let position = Point { x: 5., y: 5., z: 5. };
+    let camera_buffer = renderer.create_uniform_buffer(&[0; std::mem::size_of::<[[f32; 4]; 4]>()]);
+    let camera = Camera::new(
+        position,
+        Point { z: position.z - 1., ..position },
+        SkyboxCameraResources(renderer.create_camera_transforms_uniform(
+            camera_buffer.as_entire_buffer_binding(),
+        )),
+    );
+
+    let mut job = renderer.create_render();
+    job.add_pass().with_camera(camera.transforms_uniform());
+    renderer.render_skybox(&mut job, &camera, &cubemap);
+    job.submit();
+
+    // The camera looks along the negative Z axis, so only the last face is visible.
+    let pixels = renderer.read_pixels().expect("renderer should be headless");
+    for pixel in pixels.chunks_exact(4) {
+        // Pixels are in BGRA order.
+        assert_eq!(pixel, [255, 0, 255, 255]);
+    }
+}
+
+struct SkyboxCameraResources(CameraTransformsUniform);
+
+impl CameraResources for SkyboxCameraResources {
+    fn transforms_uniform(&self) -> &CameraTransformsUniform {
+        &self.0
+    }
+}